@@ -0,0 +1,114 @@
+//! เทียบ throughput ของการหา boundary แบบเดิม (เทียบ slice ทุก offset) กับ [`Finder`]
+//! และวัด throughput ของ [`StreamingParser`] ทั้งตัวด้วย chunk 8KB เหมือน sub_lab2
+//!
+//! รันด้วย `cargo bench -p multipart-core`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use multipart_core::search::Finder;
+use multipart_core::{Event, StreamingParser};
+
+const PAYLOAD_SIZE: usize = 64 * 1024 * 1024;
+const CHUNK_SIZE: usize = 8192;
+const BOUNDARY: &str = "----WebKitFormBoundary7MA4YWxkTrZu0gW";
+
+/// การหา pattern แบบเดิมของ sub_lab2 (`find_boundary` / `find_pattern`)
+fn naive_find(data: &[u8], pattern: &[u8]) -> Option<usize> {
+    for i in 0..=data.len().saturating_sub(pattern.len()) {
+        if data[i..i + pattern.len()] == *pattern {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// ข้อมูลสุ่มแบบ xorshift ให้ผลซ้ำได้ทุกครั้ง
+fn random_payload(len: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+fn report(name: &str, bytes: usize, elapsed: Duration) {
+    let mb = bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "{:<32} {:>8.2} ms {:>10.1} MB/s",
+        name,
+        elapsed.as_secs_f64() * 1000.0,
+        mb / elapsed.as_secs_f64()
+    );
+}
+
+fn bench<F: FnMut() -> usize>(name: &str, bytes: usize, mut f: F) {
+    // warm up แล้วเอาเวลาที่ดีที่สุดจาก 3 รอบ
+    black_box(f());
+    let best = (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap();
+    report(name, bytes, best);
+}
+
+fn main() {
+    let payload = random_payload(PAYLOAD_SIZE);
+    let delimiter = format!("\r\n--{}", BOUNDARY).into_bytes();
+
+    println!("payload: {} MB, boundary: {:?}", PAYLOAD_SIZE / (1024 * 1024), BOUNDARY);
+    println!("{}", "─".repeat(64));
+
+    // หาใน payload ที่ไม่มี boundary เลย (worst case ต้องสแกนทั้งหมด)
+    bench("naive find (no match)", payload.len(), || {
+        naive_find(&payload, &delimiter).unwrap_or(0)
+    });
+
+    let finder = Finder::new(&delimiter);
+    bench("Finder::find (no match)", payload.len(), || {
+        finder.find(&payload).unwrap_or(0)
+    });
+
+    // payload ที่เป็นตัวอักษรเดียวกันหมดแบบไฟล์ทดสอบใน README (dd | tr '\0' 'a')
+    let text = vec![b'a'; PAYLOAD_SIZE];
+    bench("naive find ('a' x N)", text.len(), || {
+        naive_find(&text, &delimiter).unwrap_or(0)
+    });
+    bench("Finder::find ('a' x N)", text.len(), || {
+        finder.find(&text).unwrap_or(0)
+    });
+
+    // parser ทั้งตัว feed ทีละ 8KB
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"bench.bin\"\r\n\r\n",
+        BOUNDARY
+    )
+    .into_bytes();
+    body.extend_from_slice(&payload);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+
+    bench("StreamingParser (8KB chunks)", body.len(), || {
+        let mut parser = StreamingParser::new(&format!("--{}", BOUNDARY)).unwrap();
+        let mut received = 0;
+        for chunk in body.chunks(CHUNK_SIZE) {
+            parser.feed(chunk);
+            while let Some(event) = parser.next_event().unwrap() {
+                if let Event::PartData(data) = event {
+                    received += data.len();
+                }
+            }
+        }
+        parser.end_of_input();
+        while parser.next_event().unwrap().is_some() {}
+        assert_eq!(received, PAYLOAD_SIZE);
+        received
+    });
+}
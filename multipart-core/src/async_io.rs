@@ -0,0 +1,637 @@
+//! Async driver บน tokio (เปิดด้วย feature `tokio`)
+//!
+//! อ่าน socket ด้วย async I/O แล้วส่ง body ให้ [`UploadProcessor`] ตัวเดียวกับแบบ sync
+//! ทำให้ server รับ upload หลาย connection พร้อมกันได้โดยไม่ต้องมี thread ต่อ connection
+
+use std::future::poll_fn;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncReadExt, ReadBuf};
+use tokio::runtime::Handle;
+
+use crate::chunked::{ChunkedDecoder, ChunkedEvent};
+use crate::digest::DigestAlgorithm;
+use crate::error::MultipartError;
+use crate::filename::NamingStrategy;
+use crate::http::{BodyFraming, Connection, HeadLimits, RequestHead};
+use crate::limits::Limits;
+use crate::storage::StorageRoutes;
+use crate::upload::{FsyncMode, Stats, UploadProcessor};
+
+/// ขนาด batch สูงสุดที่ [`read_body`] สะสมจาก socket ก่อนส่งเข้า processor ใน blocking thread (256KB)
+///
+/// ใหญ่กว่า buffer ของ server แบบ sync เพราะการส่งเข้า blocking thread แต่ละครั้งมีค่าใช้จ่าย
+/// batch ถูกส่งเร็วกว่านั้นเมื่อ socket ไม่มีข้อมูลพร้อมอ่านแล้ว
+pub const BUFFER_SIZE: usize = 256 * 1024;
+
+/// อ่าน request head ถัดไปจาก connection แบบ async ทำงานเหมือน [`Connection::read_head`]
+///
+/// คืน `Ok(None)` ถ้า connection ปิดก่อนมี request ใหม่
+pub async fn read_head<S: AsyncRead + Unpin>(
+    conn: &mut Connection<S>,
+    limits: &HeadLimits,
+) -> Result<Option<RequestHead>, MultipartError> {
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(head) = conn.take_head(limits)? {
+            return Ok(Some(head));
+        }
+        let n = conn.get_mut().read(&mut chunk).await?;
+        if n == 0 {
+            return if conn.has_buffered() {
+                Err(MultipartError::MalformedRequest("connection closed in request head".to_string()))
+            } else {
+                Ok(None)
+            };
+        }
+        conn.extend_buffer(&chunk[..n]);
+    }
+}
+
+/// อ่าน bytes ที่ค้างใน buffer ก่อน แล้วค่อยอ่านจาก stream เหมือน `Read` ของรุ่น sync
+impl<S: AsyncRead + Unpin> AsyncRead for Connection<S> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.has_buffered() {
+            let n = this.read_buffered(buf.initialize_unfilled());
+            buf.advance(n);
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(this.get_mut()).poll_read(cx, buf)
+    }
+}
+
+/// รุ่น async ของ [`UploadProcessor`] สำหรับใช้ใน tokio task
+///
+/// ข้างในเป็น [`UploadProcessor`] ตัวเดียวกับแบบ sync ที่รันใน blocking thread ของ tokio
+/// (แบบเดียวกับที่ `tokio::fs` ทำ) จึงได้ไฟล์, [`Stats`] และ error เหมือนกันทุกอย่าง
+/// รวมถึง [`StorageBackend`](crate::storage::StorageBackend) ที่ส่งผ่าน [`with_storage`](Self::with_storage)
+pub struct AsyncUploadProcessor {
+    /// `None` ระหว่างที่ processor อยู่ใน blocking thread หรือถ้า call ก่อนหน้าถูกยกเลิกกลางคัน
+    inner: Option<UploadProcessor>,
+}
+
+impl AsyncUploadProcessor {
+    /// สร้าง processor จาก delimiter (`--` + boundary) และ directory ที่จะเก็บไฟล์
+    pub async fn new(boundary: &str, upload_dir: &str) -> Result<Self, MultipartError> {
+        let (boundary, upload_dir) = (boundary.to_string(), upload_dir.to_string());
+        let inner = unblock(move || UploadProcessor::new(&boundary, &upload_dir)).await??;
+        Ok(Self { inner: Some(inner) })
+    }
+
+    /// กำหนดขนาดสูงสุดของค่า text field แต่ละตัว (ชนะ [`with_limits`](Self::with_limits) ไม่ว่าจะเรียกก่อนหรือหลัง)
+    pub fn with_max_field_size(self, max_field_size: usize) -> Self {
+        self.map(|inner| inner.with_max_field_size(max_field_size))
+    }
+
+    /// กำหนดขอบเขตทั้งหมดของ body
+    pub fn with_limits(self, limits: Limits) -> Self {
+        self.map(|inner| inner.with_limits(limits))
+    }
+
+    /// กำหนดวิธีตั้งชื่อไฟล์ที่บันทึก
+    pub fn with_naming(self, naming: NamingStrategy) -> Self {
+        self.map(|inner| inner.with_naming(naming))
+    }
+
+    /// กำหนดการ fsync ไฟล์ที่บันทึก
+    pub fn with_fsync(self, fsync: FsyncMode) -> Self {
+        self.map(|inner| inner.with_fsync(fsync))
+    }
+
+    /// เก็บไฟล์ผ่าน backend ที่ `routes` เลือกให้ (ดู [`UploadProcessor::with_storage`])
+    pub fn with_storage(self, routes: StorageRoutes, route: &str) -> Self {
+        self.map(|inner| inner.with_storage(routes, route))
+    }
+
+    /// กำหนด digest ที่คำนวณระหว่าง stream ให้ทุกไฟล์
+    pub fn with_digests(self, digests: &[DigestAlgorithm]) -> Self {
+        self.map(|inner| inner.with_digests(digests))
+    }
+
+    /// ตรวจ digest ที่ client ส่งมาหรือไม่ (default เปิด)
+    pub fn with_digest_verification(self, verify: bool) -> Self {
+        self.map(|inner| inner.with_digest_verification(verify))
+    }
+
+    /// decode `Content-Transfer-Encoding` ของแต่ละ part หรือไม่ (default เปิด)
+    pub fn with_transfer_decoding(self, decode: bool) -> Self {
+        self.map(|inner| inner.with_transfer_decoding(decode))
+    }
+
+    /// ป้อน chunk ถัดไปของ body เข้า processor (error จะลบไฟล์ชั่วคราวที่ค้างอยู่)
+    pub async fn process_chunk(&mut self, chunk: &[u8]) -> Result<(), MultipartError> {
+        let chunk = chunk.to_vec();
+        self.run(move |inner| inner.process_chunk(&chunk)).await?
+    }
+
+    /// ป้อน `buffer[..len]` โดยย้าย buffer เข้า blocking thread แทนการ copy แล้วคืน buffer กลับมาใช้ต่อ
+    async fn process_buffer(&mut self, buffer: Vec<u8>, len: usize) -> Result<Vec<u8>, MultipartError> {
+        let (buffer, result) = self
+            .run(move |inner| {
+                let result = inner.process_chunk(&buffer[..len]);
+                (buffer, result)
+            })
+            .await?;
+        result.map(|()| buffer)
+    }
+
+    /// เรียกหลังอ่าน body หมดแล้ว เพื่อตรวจว่า body จบครบ
+    pub async fn finalize(&mut self) -> Result<(), MultipartError> {
+        self.run(UploadProcessor::finalize).await?
+    }
+
+    /// เจอ closing delimiter (`--boundary--`) แล้วหรือยัง
+    pub fn is_complete(&self) -> bool {
+        self.inner.as_ref().is_some_and(UploadProcessor::is_complete)
+    }
+
+    /// สถิติที่สะสมมาตั้งแต่เริ่ม parse
+    ///
+    /// คืน `None` ถ้า call ก่อนหน้าถูกยกเลิกกลางคัน (future ถูกทิ้งหรือ processor panic)
+    /// เพราะ processor และสถิติของมันหายไปพร้อมกับ blocking thread นั้นแล้ว
+    pub fn get_stats(&self) -> Option<&Stats> {
+        self.inner.as_ref().map(UploadProcessor::get_stats)
+    }
+
+    fn map(mut self, f: impl FnOnce(UploadProcessor) -> UploadProcessor) -> Self {
+        self.inner = self.inner.take().map(f);
+        self
+    }
+
+    /// ย้าย processor เข้า blocking thread ไปทำ `f` แล้วย้ายกลับมา
+    ///
+    /// ถ้า future ถูกทิ้งระหว่างรอ processor จะถูก drop ใน thread นั้นซึ่งลบไฟล์ชั่วคราวให้เอง
+    async fn run<T: Send + 'static>(
+        &mut self,
+        f: impl FnOnce(&mut UploadProcessor) -> T + Send + 'static,
+    ) -> Result<T, MultipartError> {
+        let mut inner = self
+            .inner
+            .take()
+            .ok_or_else(|| io::Error::other("upload processor was cancelled"))?;
+        let (inner, result) = unblock(move || {
+            let result = f(&mut inner);
+            (inner, result)
+        })
+        .await?;
+        self.inner = Some(inner);
+        Ok(result)
+    }
+}
+
+impl Drop for AsyncUploadProcessor {
+    /// part ที่ค้างอยู่ถูกทิ้งใน blocking thread เพราะ backend อย่าง S3 ต้องคุยผ่าน network ตอนเก็บกวาด
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.take()
+            && let Ok(runtime) = Handle::try_current()
+        {
+            runtime.spawn_blocking(move || drop(inner));
+        }
+    }
+}
+
+/// รัน `f` ใน blocking thread ของ tokio (panic ใน `f` กลายเป็น [`MultipartError::Io`])
+async fn unblock<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> Result<T, MultipartError> {
+    tokio::task::spawn_blocking(f).await.map_err(|e| io::Error::other(e).into())
+}
+
+/// อ่าน body จาก `reader` ตาม `framing` แล้ว feed เข้า `upload` จนจบ body
+/// (ครบ Content-Length, เจอ last-chunk หรือ connection ปิด) แล้วเรียก `finalize`
+///
+/// bytes ที่อ่านเกิน last-chunk จะถูกคืนเข้า `conn` ให้ request ถัดไป
+/// คืนจำนวน bytes ดิบที่อ่านจาก socket
+///
+/// body ถูกส่งเข้า processor เป็น batch ละไม่เกิน [`BUFFER_SIZE`] โดยส่งเมื่อ batch เต็ม, body จบ
+/// หรือ socket ยังไม่มีข้อมูลใหม่ จึงไม่ต้องรอ client ที่ส่งช้าจนครบ batch
+pub async fn read_body<R: AsyncRead + Unpin>(
+    conn: &mut Connection<R>,
+    framing: BodyFraming,
+    upload: &mut AsyncUploadProcessor,
+) -> Result<usize, MultipartError> {
+    let mut batch = vec![0u8; BUFFER_SIZE];
+    let mut filled = 0;
+    let mut decoded = Vec::new();
+    let mut bytes_read = 0usize;
+    let mut decoder = (framing == BodyFraming::Chunked).then(ChunkedDecoder::new);
+
+    loop {
+        let finished = decoder.as_ref().is_some_and(ChunkedDecoder::is_finished);
+        let to_read = if finished { None } else { framing.next_read(bytes_read, BUFFER_SIZE - filled) };
+        // batch ว่าง: รอข้อมูลจาก socket ตามปกติ, มีข้อมูลแล้ว: อ่านต่อเฉพาะที่พร้อมอยู่โดยไม่รอ
+        let n = match to_read {
+            Some(0) | None => None,
+            Some(to_read) if filled == 0 => Some(conn.read(&mut batch[..to_read]).await?),
+            Some(to_read) => read_ready(conn, &mut batch[filled..filled + to_read]).await?,
+        };
+        let eof = match n {
+            Some(0) => true,
+            Some(n) => {
+                filled += n;
+                bytes_read += n;
+                continue;
+            }
+            None if filled == 0 => break,
+            None => false,
+        };
+
+        match &mut decoder {
+            Some(decoder) => feed_chunked(decoder, upload, &batch[..filled], &mut decoded).await?,
+            None if filled > 0 => batch = upload.process_buffer(batch, filled).await?,
+            None => {}
+        }
+        filled = 0;
+        if eof {
+            if let Some(decoder) = &mut decoder {
+                decoder.end_of_input();
+                feed_chunked(decoder, upload, &[], &mut decoded).await?;
+            }
+            break;
+        }
+    }
+
+    if let Some(decoder) = &decoder {
+        conn.unread(decoder.remaining());
+    }
+    upload.finalize().await?;
+    Ok(bytes_read)
+}
+
+/// อ่านเฉพาะ bytes ที่ `reader` มีพร้อมอยู่แล้ว คืน `None` ถ้าต้องรอข้อมูลใหม่จาก socket
+async fn read_ready<R: AsyncRead + Unpin>(reader: &mut R, buffer: &mut [u8]) -> io::Result<Option<usize>> {
+    let mut buffer = ReadBuf::new(buffer);
+    poll_fn(|cx| match Pin::new(&mut *reader).poll_read(cx, &mut buffer) {
+        Poll::Ready(result) => Poll::Ready(result.map(|()| Some(buffer.filled().len()))),
+        Poll::Pending => Poll::Ready(Ok(None)),
+    })
+    .await
+}
+
+/// decode `data` แล้วส่ง data ของทุก chunk ที่ได้เข้า processor ในครั้งเดียว (`decoded` เป็น buffer ที่ใช้ซ้ำ)
+async fn feed_chunked(
+    decoder: &mut ChunkedDecoder,
+    upload: &mut AsyncUploadProcessor,
+    data: &[u8],
+    decoded: &mut Vec<u8>,
+) -> Result<(), MultipartError> {
+    decoder.feed(data);
+    decoded.clear();
+    while let Some(event) = decoder.next_event()? {
+        if let ChunkedEvent::Data(data) = event {
+            decoded.extend_from_slice(data);
+        }
+    }
+    if !decoded.is_empty() {
+        let len = decoded.len();
+        *decoded = upload.process_buffer(std::mem::take(decoded), len).await?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::digest::DigestAlgorithm;
+    use crate::storage::{MemoryStorage, NewFile, PartSink, StorageBackend};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    fn temp_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("multipart-core-async-{}-{}", test, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn files_in(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// field สองตัวชื่อซ้ำ, ไฟล์ชื่อซ้ำกัน (ต้องเติม suffix) และไฟล์ที่ยาวกว่า buffer หลายเท่า
+    fn body() -> Vec<u8> {
+        let mut body = Vec::new();
+        for (name, value) in [("tag", "a"), ("tag", "b")] {
+            body.extend_from_slice(format!("--b\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", name, value).as_bytes());
+        }
+        let large: Vec<u8> = (0..3 * BUFFER_SIZE + 17).map(|i| (i % 251) as u8).collect();
+        for (filename, content) in [("a.txt", &b"first"[..]), ("a.txt", b"second"), ("big.bin", &large)] {
+            body.extend_from_slice(
+                format!("--b\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"{}\"\r\n\r\n", filename).as_bytes(),
+            );
+            body.extend_from_slice(content);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(b"--b--\r\n");
+        body
+    }
+
+    fn chunked(body: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        for chunk in body.chunks(1000) {
+            encoded.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+            encoded.extend_from_slice(chunk);
+            encoded.extend_from_slice(b"\r\n");
+        }
+        encoded.extend_from_slice(b"0\r\n\r\n");
+        encoded
+    }
+
+    fn sync_upload(dir: &Path, body: &[u8], limits: Limits) -> Result<UploadProcessor, MultipartError> {
+        let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap())?
+            .with_limits(limits)
+            .with_digests(&[DigestAlgorithm::Sha256]);
+        for chunk in body.chunks(BUFFER_SIZE) {
+            upload.process_chunk(chunk)?;
+        }
+        upload.finalize()?;
+        Ok(upload)
+    }
+
+    async fn async_upload(dir: &Path, limits: Limits) -> AsyncUploadProcessor {
+        AsyncUploadProcessor::new("--b", dir.to_str().unwrap())
+            .await
+            .unwrap()
+            .with_limits(limits)
+            .with_digests(&[DigestAlgorithm::Sha256])
+    }
+
+    /// ทุกอย่างใน `Stats` ต้องเหมือนกัน ยกเว้น path ที่อยู่คนละ directory (จำนวน chunk ขึ้นกับ framing)
+    fn assert_same_uploads(sync: &Stats, sync_dir: &Path, other: &Stats, other_dir: &Path) {
+        assert_eq!(sync.total_bytes, other.total_bytes);
+        assert_eq!((sync.fields_count, sync.files_count), (other.fields_count, other.files_count));
+        assert_eq!(sync.fields, other.fields);
+        assert_eq!(sync.files_saved.len(), other.files_saved.len());
+        for (expected, actual) in sync.files_saved.iter().zip(&other.files_saved) {
+            assert_eq!(expected.filename, actual.filename);
+            assert_eq!(expected.original_filename, actual.original_filename);
+            assert_eq!(expected.field_name, actual.field_name);
+            assert_eq!(expected.size, actual.size);
+            assert_eq!(expected.digests, actual.digests);
+            assert_eq!(std::fs::read(&expected.path).unwrap(), std::fs::read(&actual.path).unwrap());
+        }
+        assert_eq!(files_in(sync_dir), files_in(other_dir));
+    }
+
+    #[test]
+    fn saves_the_same_files_as_the_sync_processor() {
+        let body = body();
+        let sync_dir = temp_dir("same-sync");
+        let sync = sync_upload(&sync_dir, &body, Limits::default()).unwrap();
+        assert_eq!(files_in(&sync_dir), ["a-1.txt", "a.txt", "big.bin"]);
+        assert_eq!(sync.get_stats().field_values("tag"), ["a", "b"]);
+
+        for (test, framing, raw) in [
+            ("content-length", BodyFraming::ContentLength(body.len()), body.clone()),
+            ("until-close", BodyFraming::UntilClose, body.clone()),
+            ("chunked", BodyFraming::Chunked, chunked(&body)),
+        ] {
+            let dir = temp_dir(test);
+            let stats = block_on(async {
+                let mut upload = async_upload(&dir, Limits::default()).await;
+                let mut conn = Connection::new(&raw[..]);
+                assert_eq!(read_body(&mut conn, framing, &mut upload).await.unwrap(), raw.len());
+                assert!(upload.is_complete());
+                upload.get_stats().unwrap().clone()
+            });
+            assert_same_uploads(sync.get_stats(), &sync_dir, &stats, &dir);
+            if framing != BodyFraming::Chunked {
+                assert_eq!(sync.get_stats().total_chunks, stats.total_chunks, "{}", test);
+            }
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+        std::fs::remove_dir_all(&sync_dir).unwrap();
+    }
+
+    /// ส่งทีละ piece และตอบ `Pending` หนึ่งครั้งระหว่าง piece เหมือน socket ที่ข้อมูลยังมาไม่ถึง
+    struct Trickle {
+        pieces: std::collections::VecDeque<Vec<u8>>,
+        waiting: bool,
+    }
+
+    impl AsyncRead for Trickle {
+        fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+            if std::mem::take(&mut self.waiting) {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            if let Some(mut piece) = self.pieces.pop_front() {
+                let n = piece.len().min(buf.remaining());
+                buf.put_slice(&piece[..n]);
+                if n < piece.len() {
+                    self.pieces.push_front(piece.split_off(n));
+                } else {
+                    self.waiting = true;
+                }
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn sends_a_batch_once_the_socket_has_no_more_data() {
+        let body = body();
+        let pieces = [&body[..100], &body[100..5000], &body[5000..]].map(<[u8]>::to_vec);
+        let dir = temp_dir("trickle");
+        let stats = block_on(async {
+            let mut upload = async_upload(&dir, Limits::default()).await;
+            let trickle = Trickle {
+                pieces: pieces.into(),
+                waiting: false,
+            };
+            let mut conn = Connection::new(trickle);
+            read_body(&mut conn, BodyFraming::UntilClose, &mut upload).await.unwrap();
+            upload.get_stats().unwrap().clone()
+        });
+        // ไม่รอจนเต็ม batch: ส่ง 2 piece แรกทันทีที่ socket ว่าง ส่วน piece สุดท้ายถูกแบ่งเป็น batch เต็ม
+        assert_eq!(stats.total_chunks, 2 + (body.len() - 5000).div_ceil(BUFFER_SIZE));
+        assert_eq!(stats.total_bytes, body.len());
+        assert_eq!(files_in(&dir), ["a-1.txt", "a.txt", "big.bin"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reads_pipelined_requests_around_the_body() {
+        let body = body();
+        let mut raw = b"POST /upload HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+        raw.extend_from_slice(&chunked(&body));
+        raw.extend_from_slice(b"GET /next HTTP/1.1\r\n\r\n");
+
+        let dir = temp_dir("pipelined");
+        block_on(async {
+            let limits = HeadLimits::default();
+            let mut conn = Connection::new(&raw[..]);
+            let head = read_head(&mut conn, &limits).await.unwrap().unwrap();
+            assert_eq!(head.path(), "/upload");
+            let framing = crate::http::body_framing(&head).unwrap();
+            assert_eq!(framing, BodyFraming::Chunked);
+
+            let mut upload = async_upload(&dir, Limits::default()).await;
+            read_body(&mut conn, framing, &mut upload).await.unwrap();
+            assert_eq!(upload.get_stats().unwrap().files_count, 3);
+
+            // bytes ที่อ่านเกิน last-chunk ต้องกลับมาเป็น request ถัดไป
+            let next = read_head(&mut conn, &limits).await.unwrap().unwrap();
+            assert_eq!(next.path(), "/next");
+            assert!(read_head(&mut conn, &limits).await.unwrap().is_none());
+
+            // Content-Length: 0 ไม่อ่านอะไรเลย request ถัดไปต้องยังอยู่ครบ
+            let mut conn = Connection::new(&b"GET /after HTTP/1.1\r\n\r\n"[..]);
+            let mut upload = async_upload(&dir, Limits::default()).await;
+            let err = read_body(&mut conn, BodyFraming::ContentLength(0), &mut upload).await.unwrap_err();
+            assert!(matches!(err, MultipartError::UnexpectedEof), "{:?}", err);
+            assert_eq!(read_head(&mut conn, &limits).await.unwrap().unwrap().path(), "/after");
+
+            let mut truncated = Connection::new(&b"GET / HTTP/1.1\r\nHost: x"[..]);
+            let err = read_head(&mut truncated, &limits).await.unwrap_err();
+            assert!(matches!(err, MultipartError::MalformedRequest(_)), "{:?}", err);
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn fails_like_the_sync_processor() {
+        let body = body();
+        let tight = Limits {
+            max_file_size: 100,
+            ..Limits::default()
+        };
+        // body ขาดกลางไฟล์ใหญ่ และไฟล์ใหญ่เกิน limit
+        let cases = [("early-eof", &body[..body.len() - 200], Limits::default()), ("limit", &body[..], tight)];
+        for (test, raw, limits) in cases {
+            let sync_dir = temp_dir(&format!("{}-sync", test));
+            let sync_err = sync_upload(&sync_dir, raw, limits).err().unwrap();
+
+            let dir = temp_dir(test);
+            let async_err = block_on(async {
+                let mut upload = async_upload(&dir, limits).await;
+                let mut conn = Connection::new(raw);
+                let err = read_body(&mut conn, BodyFraming::ContentLength(body.len()), &mut upload).await.unwrap_err();
+                assert!(!upload.is_complete());
+                // error ปกติไม่ทำให้สถิติหาย
+                assert_eq!(upload.get_stats().unwrap().fields_count, 2);
+                err
+            });
+            assert_eq!(sync_err.to_string(), async_err.to_string(), "{}", test);
+            assert_eq!(async_err.status(), sync_err.status());
+            // ไฟล์ที่จบก่อน error ยังอยู่ ส่วนไฟล์ชั่วคราวของ part ที่ล้มถูกลบ
+            assert_eq!(files_in(&sync_dir), ["a-1.txt", "a.txt"]);
+            assert_eq!(files_in(&dir), files_in(&sync_dir));
+
+            std::fs::remove_dir_all(&sync_dir).unwrap();
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn commits_files_and_cleans_up_when_dropped() {
+        let dir = temp_dir("drop");
+        let head = b"--b\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\npartial";
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(async {
+            let mut upload = async_upload(&dir, Limits::default()).await;
+            upload.process_chunk(head).await.unwrap();
+            // ระหว่าง part มีแค่ไฟล์ชั่วคราว
+            let temp = files_in(&dir);
+            assert_eq!(temp.len(), 1);
+            assert_ne!(temp, ["a.txt"]);
+
+            upload.process_chunk(b" data\r\n--b--\r\n").await.unwrap();
+            upload.finalize().await.unwrap();
+            assert_eq!(files_in(&dir), ["a.txt"]);
+            assert_eq!(std::fs::read(dir.join("a.txt")).unwrap(), b"partial data");
+
+            let mut upload = async_upload(&dir, Limits::default()).await;
+            upload.process_chunk(head).await.unwrap();
+            assert_eq!(files_in(&dir).len(), 2);
+            drop(upload);
+        });
+        // Drop ส่ง part ที่ค้างไปเก็บกวาดใน blocking thread
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while files_in(&dir).len() > 1 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(files_in(&dir), ["a.txt"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    struct PanickingBackend;
+
+    impl StorageBackend for PanickingBackend {
+        fn create(&self, _file: &NewFile<'_>) -> Result<Box<dyn PartSink>, MultipartError> {
+            panic!("backend panicked");
+        }
+    }
+
+    #[test]
+    fn has_no_stats_after_the_processor_is_lost() {
+        let dir = temp_dir("lost");
+        let routes = StorageRoutes::new(Arc::new(PanickingBackend));
+        block_on(async {
+            let mut upload = async_upload(&dir, Limits::default()).await.with_storage(routes, "/");
+            upload.process_chunk(b"--b\r\nContent-Disposition: form-data; name=\"tag\"\r\n\r\na\r\n").await.unwrap();
+            assert_eq!(upload.get_stats().unwrap().fields_count, 1);
+
+            let err = upload
+                .process_chunk(b"--b\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\nx")
+                .await
+                .unwrap_err();
+            assert!(matches!(err, MultipartError::Io(_)), "{:?}", err);
+            assert!(upload.get_stats().is_none());
+            assert!(!upload.is_complete());
+            assert!(upload.finalize().await.is_err());
+        });
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn keeps_max_field_size_in_either_call_order() {
+        let dir = temp_dir("field-size");
+        let field = b"--b\r\nContent-Disposition: form-data; name=\"note\"\r\n\r\n0123456789\r\n--b--\r\n";
+        let limits = Limits {
+            max_parts: 5,
+            ..Limits::default()
+        };
+        block_on(async {
+            let new = || AsyncUploadProcessor::new("--b", dir.to_str().unwrap());
+            for mut upload in [
+                new().await.unwrap().with_max_field_size(9).with_limits(limits),
+                new().await.unwrap().with_limits(limits).with_max_field_size(9),
+            ] {
+                let err = upload.process_chunk(field).await.unwrap_err();
+                assert!(matches!(err, MultipartError::LimitExceeded { limit: "field size", .. }), "{:?}", err);
+            }
+        });
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stores_files_through_storage_routes() {
+        let dir = temp_dir("routes");
+        let memory = MemoryStorage::new();
+        let routes = StorageRoutes::new(Arc::new(memory.clone()));
+        let body = body();
+        let stats = block_on(async {
+            let mut upload = async_upload(&dir, Limits::default()).await.with_storage(routes, "/upload");
+            let mut conn = Connection::new(&body[..]);
+            read_body(&mut conn, BodyFraming::UntilClose, &mut upload).await.unwrap();
+            upload.get_stats().unwrap().clone()
+        });
+        assert_eq!(memory.names(), ["a-1.txt", "a.txt", "big.bin"]);
+        assert_eq!(memory.get("a-1.txt").unwrap(), b"second");
+        assert_eq!(stats.files_saved[0].path, "memory:a.txt");
+        assert!(files_in(&dir).is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,217 @@
+//! Decoder ของ `Transfer-Encoding: chunked` แบบ sans-IO
+//!
+//! ทำงานแบบเดียวกับ [`StreamingParser`](crate::StreamingParser): feed bytes ดิบจาก socket
+//! แล้วดึง [`ChunkedEvent`] ออกมาทีละตัว ข้อมูล body ที่ decode แล้วอยู่ใน
+//! [`ChunkedEvent::Data`] ซึ่งส่งต่อเข้า multipart parser ได้ทันที
+//!
+//! ```text
+//! 1f4;ext=1\r\n      ← chunk-size (hex) + extensions
+//! ...500 bytes...\r\n
+//! 0\r\n              ← last-chunk
+//! X-Checksum: abc\r\n ← trailer
+//! \r\n
+//! ```
+
+use crate::error::MultipartError;
+
+/// ความยาวสูงสุดของบรรทัด chunk-size (รวม extensions)
+pub const MAX_CHUNK_LINE: usize = 4096;
+/// ขนาดรวมสูงสุดของ trailer section
+pub const MAX_TRAILER_SIZE: usize = 8192;
+
+/// Event ที่ decoder ปล่อยออกมา
+#[derive(Debug, PartialEq)]
+pub enum ChunkedEvent<'a> {
+    /// อ่านบรรทัด chunk-size แล้ว (`size == 0` คือ last-chunk)
+    ChunkStart { size: u64, extensions: String },
+    /// ข้อมูล body ของ chunk ปัจจุบัน (อาจมาหลายครั้งต่อ chunk)
+    Data(&'a [u8]),
+    /// จบ chunk (อ่าน CRLF หลังข้อมูลแล้ว)
+    ChunkEnd,
+    /// trailer header หลัง last-chunk
+    Trailer { name: String, value: String },
+    /// จบ chunked body แล้ว bytes ที่เหลือดูได้จาก [`ChunkedDecoder::remaining`]
+    Finished,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Size,
+    Data { remaining: u64 },
+    DataCrlf,
+    Trailers,
+    Done,
+}
+
+/// Decoder ของ chunked body
+pub struct ChunkedDecoder {
+    buffer: Vec<u8>,
+    pos: usize,
+    state: State,
+    trailer_bytes: usize,
+    eof: bool,
+}
+
+impl Default for ChunkedDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChunkedDecoder {
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            pos: 0,
+            state: State::Size,
+            trailer_bytes: 0,
+            eof: false,
+        }
+    }
+
+    /// ป้อน bytes ดิบถัดไปจาก socket
+    pub fn feed(&mut self, data: &[u8]) {
+        if self.pos == self.buffer.len() {
+            self.buffer.clear();
+            self.pos = 0;
+        } else if self.pos > 0 && self.pos >= self.buffer.len() / 2 {
+            self.buffer.drain(..self.pos);
+            self.pos = 0;
+        }
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// บอก decoder ว่า connection ปิดแล้ว
+    pub fn end_of_input(&mut self) {
+        self.eof = true;
+    }
+
+    /// อ่าน last-chunk และ trailer ครบแล้วหรือยัง
+    pub fn is_finished(&self) -> bool {
+        self.state == State::Done
+    }
+
+    /// bytes ที่ feed เข้ามาแต่ยังไม่ถูกใช้ (หลัง [`ChunkedEvent::Finished`] คือข้อมูลของ request ถัดไป)
+    pub fn remaining(&self) -> &[u8] {
+        &self.buffer[self.pos..]
+    }
+
+    /// ดึง event ถัดไป คืน `Ok(None)` เมื่อต้องการข้อมูลเพิ่ม (หรือจบไปแล้ว)
+    pub fn next_event(&mut self) -> Result<Option<ChunkedEvent<'_>>, MultipartError> {
+        let data = &self.buffer[self.pos..];
+
+        match self.state {
+            State::Size => {
+                let Some(line_end) = find_crlf(data) else {
+                    if data.len() > MAX_CHUNK_LINE {
+                        return Err(invalid("chunk-size line too long"));
+                    }
+                    return self.wait_or_eof();
+                };
+                if line_end > MAX_CHUNK_LINE {
+                    return Err(invalid("chunk-size line too long"));
+                }
+
+                let (size, extensions) = parse_size_line(&data[..line_end])?;
+                self.pos += line_end + 2;
+                self.state = if size == 0 {
+                    State::Trailers
+                } else {
+                    State::Data { remaining: size }
+                };
+                Ok(Some(ChunkedEvent::ChunkStart { size, extensions }))
+            }
+
+            State::Data { remaining } => {
+                if data.is_empty() {
+                    return self.wait_or_eof();
+                }
+                let n = (remaining.min(data.len() as u64)) as usize;
+                let start = self.pos;
+                self.pos += n;
+                self.state = if remaining == n as u64 {
+                    State::DataCrlf
+                } else {
+                    State::Data { remaining: remaining - n as u64 }
+                };
+                Ok(Some(ChunkedEvent::Data(&self.buffer[start..self.pos])))
+            }
+
+            State::DataCrlf => {
+                if data.len() < 2 {
+                    return self.wait_or_eof();
+                }
+                if &data[..2] != b"\r\n" {
+                    return Err(invalid("missing CRLF after chunk data"));
+                }
+                self.pos += 2;
+                self.state = State::Size;
+                Ok(Some(ChunkedEvent::ChunkEnd))
+            }
+
+            State::Trailers => {
+                let Some(line_end) = find_crlf(data) else {
+                    if self.trailer_bytes + data.len() > MAX_TRAILER_SIZE {
+                        return Err(invalid("trailer section too large"));
+                    }
+                    return self.wait_or_eof();
+                };
+
+                self.trailer_bytes += line_end + 2;
+                if self.trailer_bytes > MAX_TRAILER_SIZE {
+                    return Err(invalid("trailer section too large"));
+                }
+
+                let line = String::from_utf8_lossy(&data[..line_end]).into_owned();
+                self.pos += line_end + 2;
+
+                if line.is_empty() {
+                    self.state = State::Done;
+                    return Ok(Some(ChunkedEvent::Finished));
+                }
+
+                let Some((name, value)) = line.split_once(':') else {
+                    return Err(invalid("malformed trailer line"));
+                };
+                Ok(Some(ChunkedEvent::Trailer {
+                    name: name.trim().to_string(),
+                    value: value.trim().to_string(),
+                }))
+            }
+
+            State::Done => Ok(None),
+        }
+    }
+
+    fn wait_or_eof(&self) -> Result<Option<ChunkedEvent<'_>>, MultipartError> {
+        if self.eof {
+            Err(invalid("unexpected end of chunked body"))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+fn invalid(reason: &str) -> MultipartError {
+    MultipartError::InvalidChunkedEncoding(reason.to_string())
+}
+
+fn find_crlf(data: &[u8]) -> Option<usize> {
+    data.windows(2).position(|w| w == b"\r\n")
+}
+
+/// แยก `chunk-size [ BWS ; chunk-ext ]` แล้วตรวจว่า chunk-size เป็น hex ที่ไม่ overflow
+fn parse_size_line(line: &[u8]) -> Result<(u64, String), MultipartError> {
+    let line = std::str::from_utf8(line).map_err(|_| invalid("chunk-size line is not ASCII"))?;
+    let (size, extensions) = match line.split_once(';') {
+        Some((size, extensions)) => (size.trim_end_matches([' ', '\t']), extensions.trim()),
+        None => (line.trim_end_matches([' ', '\t']), ""),
+    };
+
+    if size.is_empty() || !size.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(invalid("chunk-size is not a hex number"));
+    }
+    let size = u64::from_str_radix(size, 16).map_err(|_| invalid("chunk-size overflows"))?;
+
+    Ok((size, extensions.to_string()))
+}
@@ -0,0 +1,528 @@
+//! Hash แบบ streaming ที่เขียนเอง (ไม่พึ่ง crate ภายนอก)
+//!
+//! รับข้อมูลทีละ chunk ผ่าน `update` แล้วเรียก `finalize` ตอนจบ part
+//! จึงใช้ hash ไฟล์ขนาดใหญ่ได้โดยไม่ต้องเก็บทั้งไฟล์ไว้ใน memory
+//!
+//! มี SHA-256, MD5 และ CRC32C และอ่าน digest ที่ client ส่งมาได้จาก `Content-MD5` (RFC 1864),
+//! `Digest` (RFC 3230) และ `Repr-Digest` (RFC 9530) ดู [`parse_digest_header`]
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::MultipartError;
+
+/// ค่าเริ่มต้นของ SHA-256 (FIPS 180-4 §5.3.3)
+const SHA256_INIT: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// round constants ของ SHA-256 (FIPS 180-4 §4.2.2)
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// ค่าเริ่มต้นของ MD5 (RFC 1321 §3.3)
+const MD5_INIT: [u32; 4] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476];
+
+/// จำนวน bit ที่ rotate ในแต่ละ step ของ MD5 (RFC 1321 §3.4)
+#[rustfmt::skip]
+const MD5_S: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20,
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+/// `floor(abs(sin(i + 1)) * 2^32)` ของ MD5 (RFC 1321 §3.4)
+const MD5_K: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+/// ตาราง CRC32C (Castagnoli, polynomial 0x1EDC6F41 แบบ reflected) สร้างตอน compile
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0x82f63b78 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+pub(crate) const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// algorithm ที่ใช้คำนวณ digest ของไฟล์ได้
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DigestAlgorithm {
+    Sha256,
+    Md5,
+    Crc32c,
+}
+
+impl DigestAlgorithm {
+    /// ชื่อตาม IANA HTTP Digest Algorithm registry (`sha-256`, `md5`, `crc32c`)
+    pub fn name(self) -> &'static str {
+        match self {
+            DigestAlgorithm::Sha256 => "sha-256",
+            DigestAlgorithm::Md5 => "md5",
+            DigestAlgorithm::Crc32c => "crc32c",
+        }
+    }
+
+    /// ความยาวของ digest เป็น bytes
+    pub fn output_len(self) -> usize {
+        match self {
+            DigestAlgorithm::Sha256 => 32,
+            DigestAlgorithm::Md5 => 16,
+            DigestAlgorithm::Crc32c => 4,
+        }
+    }
+
+    /// อ่านรายการ algorithm จาก env `UPLOAD_DIGESTS` คั่นด้วย `,` (เช่น `sha256,md5`)
+    /// ชื่อที่ไม่รู้จักจะถูกข้าม ไม่มี env คือไม่คำนวณ digest
+    pub fn list_from_env() -> Vec<Self> {
+        std::env::var("UPLOAD_DIGESTS")
+            .map(|value| value.split(',').filter_map(|name| name.parse().ok()).collect())
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for DigestAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DigestAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sha-256" | "sha256" => Ok(Self::Sha256),
+            "md5" => Ok(Self::Md5),
+            "crc32c" => Ok(Self::Crc32c),
+            other => Err(format!("unknown digest algorithm {:?}", other)),
+        }
+    }
+}
+
+/// buffer ของ block 64 bytes ที่ SHA-256 และ MD5 ใช้ร่วมกัน
+#[derive(Debug, Clone)]
+struct Blocks {
+    /// block ที่ยังไม่ครบ 64 bytes
+    block: [u8; 64],
+    block_len: usize,
+    total_len: u64,
+}
+
+impl Blocks {
+    fn new() -> Self {
+        Self {
+            block: [0; 64],
+            block_len: 0,
+            total_len: 0,
+        }
+    }
+
+    /// ต่อ `data` เข้า buffer แล้วส่งทุก block ที่ครบ 64 bytes ให้ `compress`
+    fn update(&mut self, mut data: &[u8], mut compress: impl FnMut(&[u8; 64])) {
+        self.total_len += data.len() as u64;
+
+        // เติม block ที่ค้างจาก chunk ก่อนให้ครบก่อน
+        if self.block_len > 0 {
+            let take = data.len().min(64 - self.block_len);
+            self.block[self.block_len..self.block_len + take].copy_from_slice(&data[..take]);
+            self.block_len += take;
+            data = &data[take..];
+            if self.block_len < 64 {
+                return;
+            }
+            compress(&self.block);
+            self.block_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            compress(block.try_into().unwrap());
+        }
+        let rest = blocks.remainder();
+        self.block[..rest.len()].copy_from_slice(rest);
+        self.block_len = rest.len();
+    }
+
+    /// padding: 0x80, ตามด้วย 0 จนเหลือ 8 bytes สุดท้ายของ block ไว้ใส่ความยาวเป็น bit
+    /// (SHA-256 เป็น big-endian ส่วน MD5 เป็น little-endian)
+    fn padding(&self, encode_len: fn(u64) -> [u8; 8]) -> Vec<u8> {
+        let mut padding = vec![0x80];
+        let used = (self.block_len + 1) % 64;
+        padding.resize(1 + (120 - used) % 64, 0);
+        padding.extend_from_slice(&encode_len(self.total_len.wrapping_mul(8)));
+        padding
+    }
+}
+
+/// SHA-256 แบบ streaming
+#[derive(Debug, Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    blocks: Blocks,
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Self {
+            state: SHA256_INIT,
+            blocks: Blocks::new(),
+        }
+    }
+
+    /// hash ข้อมูลทั้งก้อนในครั้งเดียว
+    pub fn digest(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| sha256_compress(state, block));
+    }
+
+    pub fn finalize(mut self) -> [u8; 32] {
+        let padding = self.blocks.padding(u64::to_be_bytes);
+        self.update(&padding);
+        debug_assert_eq!(self.blocks.block_len, 0);
+
+        let mut out = [0u8; 32];
+        for (chunk, word) in out.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        out
+    }
+}
+
+fn sha256_compress(state: &mut [u32; 8], block: &[u8; 64]) {
+    let mut w = [0u32; 64];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes(word.try_into().unwrap());
+    }
+    for i in 16..64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for i in 0..64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    for (state, value) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *state = state.wrapping_add(value);
+    }
+}
+
+/// MD5 แบบ streaming (ใช้ตรวจ `Content-MD5` เท่านั้น ไม่ควรใช้เพื่อความปลอดภัย)
+#[derive(Debug, Clone)]
+pub struct Md5 {
+    state: [u32; 4],
+    blocks: Blocks,
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Md5 {
+    pub fn new() -> Self {
+        Self {
+            state: MD5_INIT,
+            blocks: Blocks::new(),
+        }
+    }
+
+    /// hash ข้อมูลทั้งก้อนในครั้งเดียว
+    pub fn digest(data: &[u8]) -> [u8; 16] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.blocks.update(data, |block| md5_compress(state, block));
+    }
+
+    pub fn finalize(mut self) -> [u8; 16] {
+        let padding = self.blocks.padding(u64::to_le_bytes);
+        self.update(&padding);
+        debug_assert_eq!(self.blocks.block_len, 0);
+
+        let mut out = [0u8; 16];
+        for (chunk, word) in out.chunks_exact_mut(4).zip(self.state) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+}
+
+fn md5_compress(state: &mut [u32; 4], block: &[u8; 64]) {
+    let mut m = [0u32; 16];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        m[i] = u32::from_le_bytes(word.try_into().unwrap());
+    }
+
+    let [mut a, mut b, mut c, mut d] = *state;
+    for i in 0..64 {
+        let (f, g) = match i / 16 {
+            0 => ((b & c) | (!b & d), i),
+            1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+            2 => (b ^ c ^ d, (3 * i + 5) % 16),
+            _ => (c ^ (b | !d), (7 * i) % 16),
+        };
+        let f = f.wrapping_add(a).wrapping_add(MD5_K[i]).wrapping_add(m[g]);
+        a = d;
+        d = c;
+        c = b;
+        b = b.wrapping_add(f.rotate_left(MD5_S[i]));
+    }
+
+    for (state, value) in state.iter_mut().zip([a, b, c, d]) {
+        *state = state.wrapping_add(value);
+    }
+}
+
+/// CRC32C (Castagnoli) แบบ streaming ผลลัพธ์เป็น 4 bytes big-endian
+#[derive(Debug, Clone)]
+pub struct Crc32c {
+    crc: u32,
+}
+
+impl Default for Crc32c {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Crc32c {
+    pub fn new() -> Self {
+        Self { crc: !0 }
+    }
+
+    /// คำนวณ checksum ของข้อมูลทั้งก้อนในครั้งเดียว
+    pub fn digest(data: &[u8]) -> [u8; 4] {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.crc = CRC32C_TABLE[((self.crc ^ byte as u32) & 0xff) as usize] ^ (self.crc >> 8);
+        }
+    }
+
+    pub fn finalize(self) -> [u8; 4] {
+        (!self.crc).to_be_bytes()
+    }
+}
+
+/// hasher ของ algorithm ที่เลือกตอน runtime
+#[derive(Debug, Clone)]
+pub enum Hasher {
+    Sha256(Sha256),
+    Md5(Md5),
+    Crc32c(Crc32c),
+}
+
+impl Hasher {
+    pub fn new(algorithm: DigestAlgorithm) -> Self {
+        match algorithm {
+            DigestAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            DigestAlgorithm::Md5 => Hasher::Md5(Md5::new()),
+            DigestAlgorithm::Crc32c => Hasher::Crc32c(Crc32c::new()),
+        }
+    }
+
+    pub fn algorithm(&self) -> DigestAlgorithm {
+        match self {
+            Hasher::Sha256(_) => DigestAlgorithm::Sha256,
+            Hasher::Md5(_) => DigestAlgorithm::Md5,
+            Hasher::Crc32c(_) => DigestAlgorithm::Crc32c,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Md5(hasher) => hasher.update(data),
+            Hasher::Crc32c(hasher) => hasher.update(data),
+        }
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        match self {
+            Hasher::Sha256(hasher) => hasher.finalize().to_vec(),
+            Hasher::Md5(hasher) => hasher.finalize().to_vec(),
+            Hasher::Crc32c(hasher) => hasher.finalize().to_vec(),
+        }
+    }
+}
+
+/// HMAC-SHA256 (RFC 2104) ใช้เซ็น request ของ S3 (SigV4)
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    // key ที่ยาวกว่า block ต้อง hash ก่อน
+    let mut block = [0u8; 64];
+    if key.len() > 64 {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(&block.map(|b| b ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha256::new();
+    outer.update(&block.map(|b| b ^ 0x5c));
+    outer.update(&inner.finalize());
+    outer.finalize()
+}
+
+/// อ่าน digest ที่ client ส่งมาใน header (หรือ form field) ชื่อ `name`
+///
+/// - `Content-MD5`: base64 ของ MD5 (RFC 1864)
+/// - `Digest`: `alg=base64` คั่นด้วย `,` ส่วน `crc32c` เป็น hex (RFC 3230 / IANA registry)
+/// - `Repr-Digest`: structured dictionary `alg=:base64:` (RFC 9530)
+///
+/// algorithm ที่ไม่รู้จักจะถูกข้าม ชื่ออื่นคืนรายการว่าง
+/// ค่าที่ decode ไม่ได้หรือยาวไม่ตรงกับ algorithm คืน [`MultipartError::InvalidDigest`]
+pub fn parse_digest_header(name: &str, value: &str) -> Result<Vec<(DigestAlgorithm, Vec<u8>)>, MultipartError> {
+    let invalid = || MultipartError::InvalidDigest(format!("{}: {:?}", name, value));
+    let name = name.to_ascii_lowercase();
+    let mut digests = Vec::new();
+
+    if name == "content-md5" {
+        digests.push((DigestAlgorithm::Md5, decode_base64(value.trim()).ok_or_else(invalid)?));
+    } else if name == "digest" || name == "repr-digest" {
+        for member in value.split(',').filter(|member| !member.trim().is_empty()) {
+            let (algorithm, encoded) = member.split_once('=').ok_or_else(invalid)?;
+            let Ok(algorithm) = algorithm.parse::<DigestAlgorithm>() else {
+                continue;
+            };
+
+            let bytes = if name == "repr-digest" {
+                // ตัด parameters ของ dictionary member (`;key=value`) ออกก่อน
+                let encoded = encoded.split(';').next().unwrap_or_default().trim();
+                encoded
+                    .strip_prefix(':')
+                    .and_then(|encoded| encoded.strip_suffix(':'))
+                    .and_then(decode_base64)
+            } else if algorithm == DigestAlgorithm::Crc32c {
+                let encoded = encoded.trim();
+                (encoded.len() <= 8 && encoded.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .then(|| u32::from_str_radix(encoded, 16).ok())
+                    .flatten()
+                    .map(|crc| crc.to_be_bytes().to_vec())
+            } else {
+                decode_base64(encoded.trim())
+            };
+            digests.push((algorithm, bytes.ok_or_else(invalid)?));
+        }
+    }
+
+    if digests.iter().any(|(algorithm, bytes)| bytes.len() != algorithm.output_len()) {
+        return Err(invalid());
+    }
+    Ok(digests)
+}
+
+/// แปลง bytes เป็น hex ตัวพิมพ์เล็ก
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// แปลง bytes เป็น base64 แบบมี padding (รูปแบบเดียวกับ `Content-MD5`)
+pub fn to_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 4];
+        group[1..=chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes(group);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// decode base64 (alphabet มาตรฐาน, padding ใส่หรือไม่ใส่ก็ได้) คืน `None` ถ้าผิดรูปแบบ
+pub(crate) fn decode_base64(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.strip_suffix("==").or_else(|| encoded.strip_suffix('=')).unwrap_or(encoded);
+    if encoded.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(encoded.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+    for c in encoded.bytes() {
+        let value = BASE64_ALPHABET.iter().position(|&b| b == c)?;
+        bits = (bits << 6 | value as u32) & 0xffff;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push((bits >> bit_count) as u8);
+        }
+    }
+    Some(out)
+}
@@ -0,0 +1,163 @@
+//! Parser ของ `Content-Disposition` ของแต่ละ part ตาม RFC 7578 และ RFC 2231 / RFC 5987
+//!
+//! รองรับรูปแบบที่ client จริงส่งมา
+//! - browser (HTML spec): ค่าอยู่ใน quote, `"` กลายเป็น `%22`, CR/LF เป็น `%0D`/`%0A`
+//!   และไม่ escape `\` ส่วนชื่อภาษาไทยส่งเป็น UTF-8 ตรงๆ
+//! - Firefox รุ่นเก่าและ curl: escape `"` และ `\` ด้วย `\`
+//! - Python requests (urllib3 1.x): ชื่อที่ไม่ใช่ ASCII ส่งเป็น `filename*=utf-8''%E0%B9%84...`
+//! - RFC 2231 continuation: `filename*0*=UTF-8''...; filename*1=...`
+
+use crate::error::MultipartError;
+use crate::mime::{ParamSyntax, is_token_char, parse_params};
+
+/// `Content-Disposition` ที่ parse แล้ว
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentDisposition {
+    /// เช่น `form-data`, `attachment`, `inline` (ตัวพิมพ์เล็ก)
+    pub disposition_type: String,
+    /// ค่าของ `name` (หรือ `name*` ถ้ามี)
+    pub name: Option<String>,
+    /// ค่าของ `filename` ตามที่ส่งมา (ถอด quote และ `%22`/`%0D`/`%0A` แล้ว)
+    pub filename: Option<String>,
+    /// ค่าของ `filename*` ที่ decode charset และ percent-encoding แล้ว
+    pub extended_filename: Option<String>,
+}
+
+impl ContentDisposition {
+    /// parse ค่าของ header (ไม่รวม `Content-Disposition:`)
+    pub fn parse(input: &str) -> Result<Self, MultipartError> {
+        let input = input.trim_matches([' ', '\t']);
+        let type_end = input.find(';').unwrap_or(input.len());
+        let disposition_type = input[..type_end].trim_end_matches([' ', '\t']);
+        if disposition_type.is_empty() || !disposition_type.bytes().all(is_token_char) {
+            return Err(malformed("invalid Content-Disposition type"));
+        }
+
+        let params = parse_params(&input[type_end..], ParamSyntax::Lenient)
+            .map_err(|reason| malformed(&format!("Content-Disposition: {}", reason)))?;
+        for (i, (name, _)) in params.iter().enumerate() {
+            if params[..i].iter().any(|(other, _)| other == name) {
+                return Err(malformed(&format!("duplicate Content-Disposition parameter {:?}", name)));
+            }
+        }
+
+        let name = match extended_param(&params, "name")? {
+            Some(name) => Some(name),
+            None => plain_param(&params, "name"),
+        };
+
+        Ok(Self {
+            disposition_type: disposition_type.to_ascii_lowercase(),
+            name,
+            filename: plain_param(&params, "filename"),
+            extended_filename: extended_param(&params, "filename")?,
+        })
+    }
+
+    /// ชื่อไฟล์ที่ควรใช้: `filename*` ถ้ามี ไม่งั้น `filename`
+    pub fn resolved_filename(&self) -> Option<&str> {
+        self.extended_filename.as_deref().or(self.filename.as_deref())
+    }
+
+    /// เป็น `form-data` ตาม RFC 7578 หรือไม่
+    pub fn is_form_data(&self) -> bool {
+        self.disposition_type == "form-data"
+    }
+}
+
+fn malformed(reason: &str) -> MultipartError {
+    MultipartError::MalformedPartHeaders(reason.to_string())
+}
+
+/// ค่าปกติของพารามิเตอร์ ถอด `%22`, `%0D`, `%0A` ที่ browser ใช้แทน `"`, CR, LF
+fn plain_param(params: &[(String, String)], key: &str) -> Option<String> {
+    params
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.replace("%22", "\"").replace("%0D", "\r").replace("%0A", "\n"))
+}
+
+/// ค่าแบบ RFC 2231 ของ `key*` หรือ `key*0`, `key*1*`, ... ที่ต่อกัน
+///
+/// คืน `Ok(None)` ถ้าไม่มีหรือเป็น charset ที่ไม่รองรับ (ให้ใช้ค่าปกติแทน)
+fn extended_param(params: &[(String, String)], key: &str) -> Result<Option<String>, MultipartError> {
+    let extended = format!("{}*", key);
+    if let Some((_, value)) = params.iter().find(|(name, _)| *name == extended) {
+        return decode_extended(value);
+    }
+
+    // continuation: key*0 / key*0* เป็นต้นไปจนกว่าจะขาดลำดับ
+    let mut sections = Vec::new();
+    for index in 0.. {
+        let plain = format!("{}*{}", key, index);
+        let encoded = format!("{}*{}*", key, index);
+        let Some((name, value)) = params.iter().find(|(name, _)| *name == plain || *name == encoded) else {
+            break;
+        };
+        sections.push((*name == encoded, value.as_str()));
+    }
+
+    match sections.first() {
+        None => Ok(None),
+        // charset อยู่ใน section แรกเท่านั้น ถ้าแรกไม่ได้ encode ก็ต่อเป็นข้อความธรรมดา
+        Some((false, _)) => Ok(Some(sections.iter().map(|(_, value)| *value).collect())),
+        Some((true, _)) => {
+            let joined: String = sections
+                .iter()
+                .map(|(encoded, value)| {
+                    if *encoded {
+                        value.to_string()
+                    } else {
+                        // section ที่ไม่ได้ encode ต้อง escape `%` ก่อน decode รวมกัน
+                        value.replace('%', "%25")
+                    }
+                })
+                .collect();
+            decode_extended(&joined)
+        }
+    }
+}
+
+/// decode `charset'language'percent-encoded` ของ RFC 5987
+fn decode_extended(value: &str) -> Result<Option<String>, MultipartError> {
+    let mut parts = value.splitn(3, '\'');
+    let (Some(charset), Some(_language), Some(encoded)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(malformed("extended parameter must be charset'language'value"));
+    };
+
+    let bytes = percent_decode(encoded)?;
+    if charset.eq_ignore_ascii_case("utf-8") {
+        String::from_utf8(bytes)
+            .map(Some)
+            .map_err(|_| malformed("extended parameter is not valid UTF-8"))
+    } else if charset.eq_ignore_ascii_case("iso-8859-1") {
+        Ok(Some(bytes.into_iter().map(char::from).collect()))
+    } else {
+        Ok(None)
+    }
+}
+
+fn percent_decode(value: &str) -> Result<Vec<u8>, MultipartError> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes
+                .get(i + 1..i + 3)
+                // from_str_radix รับ `+` นำหน้า (`%+5`) จึงต้องตรวจว่าเป็น hex ทั้งสองตัวเอง
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| malformed("invalid percent-encoding in extended parameter"))?;
+            decoded.push(hex);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    Ok(decoded)
+}
@@ -0,0 +1,156 @@
+//! Error ของการ parse multipart และการ map ไปเป็น HTTP status
+
+use std::fmt;
+use std::io;
+
+/// Error ที่เกิดระหว่าง parse หรือบันทึก multipart body
+#[derive(Debug)]
+pub enum MultipartError {
+    /// `Content-Type` ไม่มี `boundary=` หรือ boundary ว่าง
+    MissingBoundary,
+    /// boundary ไม่ตรงตาม RFC 2046 (ยาวเกิน 70 ตัว หรือมีตัวอักษรที่ใช้ไม่ได้)
+    InvalidBoundary(String),
+    /// `Content-Type` อ่านไม่ได้ (ไม่มี `/`, พารามิเตอร์ผิดรูปแบบ ฯลฯ)
+    InvalidMediaType(String),
+    /// body ไม่ใช่ `multipart/*` (ค่าว่างคือไม่มี `Content-Type`)
+    UnsupportedMediaType(String),
+    /// `Content-Length` ไม่ใช่ตัวเลข
+    InvalidContentLength(String),
+    /// headers ของ part อ่านไม่ได้ (เช่นไม่มี `Content-Disposition` หรือบรรทัดไม่มี `:`)
+    MalformedPartHeaders(String),
+    /// ชื่อไฟล์ที่ใช้ไม่ได้ (มี `..`, control character, ชื่อสงวนของ Windows ฯลฯ)
+    InvalidFilename(String),
+    /// digest ที่ client ส่งมา (`Content-MD5`, `Digest`, `Repr-Digest`) อ่านไม่ได้
+    InvalidDigest(String),
+    /// digest ของไฟล์ที่ได้รับไม่ตรงกับที่ client ส่งมา (ค่าเป็น hex)
+    DigestMismatch { algorithm: &'static str, expected: String, actual: String },
+    /// `Content-Transfer-Encoding` ของ part ที่ไม่รองรับ หรือข้อมูล base64/quoted-printable ผิดรูปแบบ
+    InvalidContentTransferEncoding(String),
+    /// body จบก่อนเจอ closing delimiter
+    UnexpectedEof,
+    /// chunked body ผิดรูปแบบ (chunk-size ไม่ใช่ hex, ไม่มี CRLF, trailer ผิด ฯลฯ)
+    InvalidChunkedEncoding(String),
+    /// `Transfer-Encoding` ที่ไม่รองรับ (รองรับแค่ `chunked`)
+    UnsupportedTransferEncoding(String),
+    /// request line หรือ headers ผิดรูปแบบ HTTP/1.1
+    MalformedRequest(String),
+    /// request head ใหญ่เกินหรือ header เยอะเกิน limit
+    HeaderFieldsTooLarge { limit: &'static str, max: usize },
+    /// method ที่ใช้ upload ไม่ได้ (รับแค่ `POST` และ `PUT`)
+    MethodNotAllowed(String),
+    /// path ไม่ตรงกับ path ที่รับ upload
+    NotFound(String),
+    /// ไม่มีหรือ token ใน `Authorization` ไม่ถูกต้อง
+    Unauthorized,
+    /// `Expect` ที่ไม่ใช่ `100-continue`
+    ExpectationFailed(String),
+    /// อ่าน/เขียนไฟล์หรือ socket ไม่สำเร็จ
+    Io(io::Error),
+    /// storage backend ภายนอก (เช่น S3) ตอบ error หรือคำตอบอ่านไม่ได้
+    Storage(String),
+    /// ข้อมูลเกิน limit ที่ตั้งไว้
+    LimitExceeded { limit: &'static str, max: usize },
+}
+
+impl MultipartError {
+    /// HTTP status code และ reason phrase ที่ควรตอบกลับ client
+    pub fn status(&self) -> (u16, &'static str) {
+        match self {
+            MultipartError::MissingBoundary
+            | MultipartError::InvalidBoundary(_)
+            | MultipartError::InvalidMediaType(_)
+            | MultipartError::InvalidContentLength(_)
+            | MultipartError::MalformedPartHeaders(_)
+            | MultipartError::InvalidFilename(_)
+            | MultipartError::InvalidDigest(_)
+            | MultipartError::DigestMismatch { .. }
+            | MultipartError::InvalidContentTransferEncoding(_)
+            | MultipartError::UnexpectedEof
+            | MultipartError::InvalidChunkedEncoding(_)
+            | MultipartError::MalformedRequest(_) => (400, "Bad Request"),
+            MultipartError::Unauthorized => (401, "Unauthorized"),
+            MultipartError::NotFound(_) => (404, "Not Found"),
+            MultipartError::MethodNotAllowed(_) => (405, "Method Not Allowed"),
+            MultipartError::LimitExceeded { .. } => (413, "Payload Too Large"),
+            MultipartError::UnsupportedMediaType(_) => (415, "Unsupported Media Type"),
+            MultipartError::ExpectationFailed(_) => (417, "Expectation Failed"),
+            MultipartError::HeaderFieldsTooLarge { .. } => (431, "Request Header Fields Too Large"),
+            MultipartError::UnsupportedTransferEncoding(_) => (501, "Not Implemented"),
+            MultipartError::Io(_) => (500, "Internal Server Error"),
+            MultipartError::Storage(_) => (502, "Bad Gateway"),
+        }
+    }
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultipartError::MissingBoundary => write!(f, "missing multipart boundary"),
+            MultipartError::InvalidBoundary(reason) => write!(f, "invalid boundary: {}", reason),
+            MultipartError::InvalidMediaType(reason) => write!(f, "invalid Content-Type: {}", reason),
+            MultipartError::UnsupportedMediaType(media_type) if media_type.is_empty() => {
+                write!(f, "missing Content-Type, expected multipart/*")
+            }
+            MultipartError::UnsupportedMediaType(media_type) => {
+                write!(f, "unsupported media type {}, expected multipart/*", media_type)
+            }
+            MultipartError::InvalidContentLength(value) => {
+                write!(f, "invalid Content-Length: {:?}", value)
+            }
+            MultipartError::MalformedPartHeaders(reason) => {
+                write!(f, "malformed part headers: {}", reason)
+            }
+            MultipartError::InvalidFilename(reason) => write!(f, "invalid filename: {}", reason),
+            MultipartError::InvalidDigest(reason) => write!(f, "invalid digest: {}", reason),
+            MultipartError::DigestMismatch {
+                algorithm,
+                expected,
+                actual,
+            } => write!(f, "{} digest mismatch: expected {}, got {}", algorithm, expected, actual),
+            MultipartError::InvalidContentTransferEncoding(reason) => {
+                write!(f, "invalid Content-Transfer-Encoding: {}", reason)
+            }
+            MultipartError::UnexpectedEof => {
+                write!(f, "unexpected end of body before closing delimiter")
+            }
+            MultipartError::InvalidChunkedEncoding(reason) => {
+                write!(f, "invalid chunked encoding: {}", reason)
+            }
+            MultipartError::UnsupportedTransferEncoding(value) => {
+                write!(f, "unsupported Transfer-Encoding: {:?}", value)
+            }
+            MultipartError::MalformedRequest(reason) => write!(f, "malformed request: {}", reason),
+            MultipartError::HeaderFieldsTooLarge { limit, max } => {
+                write!(f, "{} limit exceeded (max {})", limit, max)
+            }
+            MultipartError::MethodNotAllowed(method) => {
+                write!(f, "method {} not allowed for uploads", method)
+            }
+            MultipartError::NotFound(path) => write!(f, "no upload endpoint at {}", path),
+            MultipartError::Unauthorized => write!(f, "missing or invalid upload token"),
+            MultipartError::ExpectationFailed(value) => {
+                write!(f, "unsupported expectation: {:?}", value)
+            }
+            MultipartError::Io(e) => write!(f, "I/O error: {}", e),
+            MultipartError::Storage(reason) => write!(f, "storage error: {}", reason),
+            MultipartError::LimitExceeded { limit, max } => {
+                write!(f, "{} limit exceeded (max {})", limit, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MultipartError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MultipartError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MultipartError {
+    fn from(e: io::Error) -> Self {
+        MultipartError::Io(e)
+    }
+}
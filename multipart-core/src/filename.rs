@@ -0,0 +1,229 @@
+//! ทำชื่อไฟล์จาก client ให้ปลอดภัยก่อนเขียนลง upload directory และเลือกชื่อที่จะบันทึกจริง
+//!
+//! `filename` ใน `Content-Disposition` เป็นข้อมูลจาก client ทั้งหมด ห้ามเอาไปต่อ path ตรงๆ
+//! (`../../etc/cron.d/x` จะหลุดออกนอก directory) [`sanitize`] จะตัดเหลือแค่ชื่อไฟล์
+//! แล้ว [`NamingStrategy`] เป็นตัวกำหนดว่าจะบันทึกเป็นชื่ออะไร
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::digest::to_hex;
+use crate::error::MultipartError;
+
+/// ความยาวสูงสุดของชื่อไฟล์เป็น bytes (ข้อจำกัดของ filesystem ส่วนใหญ่)
+pub const MAX_FILENAME_LEN: usize = 255;
+
+/// ชื่อ device ของ Windows ที่ห้ามใช้เป็นชื่อไฟล์ ไม่ว่าจะมีนามสกุลหรือไม่
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1",
+    "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// วิธีตั้งชื่อไฟล์ที่บันทึกลง disk (ชื่อเดิมจาก client ยังเก็บไว้ใน `FileInfo` เสมอ)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum NamingStrategy {
+    /// ใช้ชื่อที่ sanitize แล้วตรงๆ ไฟล์ชื่อซ้ำจะถูกเขียนทับ
+    Original,
+    /// ชื่อสุ่มแบบ UUID v4 ตามด้วยนามสกุลเดิม
+    Uuid,
+    /// SHA-256 ของเนื้อหาไฟล์ตามด้วยนามสกุลเดิม ไฟล์เนื้อหาเดียวกันจะได้ชื่อเดียวกัน
+    ///
+    /// ชื่อรู้ได้หลังรับไฟล์ครบ จึงเขียนลงไฟล์ชั่วคราวก่อนแล้วค่อย rename
+    ContentHash,
+    /// ชื่อเดิม ถ้าชนกับไฟล์ที่มีอยู่แล้วเติม `-1`, `-2`, ... ก่อนนามสกุล
+    #[default]
+    SuffixOnCollision,
+}
+
+impl NamingStrategy {
+    /// อ่านจาก env `UPLOAD_NAMING` (`original`, `uuid`, `hash`, `suffix`) ค่าที่ไม่รู้จักใช้ default
+    pub fn from_env() -> Self {
+        std::env::var("UPLOAD_NAMING")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for NamingStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "original" => Ok(Self::Original),
+            "uuid" => Ok(Self::Uuid),
+            "hash" | "content-hash" => Ok(Self::ContentHash),
+            "suffix" => Ok(Self::SuffixOnCollision),
+            other => Err(format!("unknown naming strategy {:?}", other)),
+        }
+    }
+}
+
+/// ทำชื่อไฟล์จาก client ให้ใช้เป็นชื่อไฟล์ใน upload directory ได้อย่างปลอดภัย
+///
+/// - ปฏิเสธชื่อที่มี `..` เป็นส่วนหนึ่งของ path, control character หรือชื่อสงวนของ Windows
+/// - ตัด directory ทิ้ง (ทั้ง `/` และ `\` เพราะ browser บน Windows รุ่นเก่าส่ง path เต็มมา)
+/// - แทน `<>:"|?*` ด้วย `_` และตัด `.`/space ที่หัวและท้ายชื่อ (กันไฟล์ซ่อนอย่าง `.htaccess`)
+/// - รวมสระ/วรรณยุกต์ละตินที่แยกตัว (NFD ที่ macOS ส่งมา) เป็นตัวเดียวแบบ NFC
+/// - ตัดให้ยาวไม่เกิน [`MAX_FILENAME_LEN`] bytes โดยรักษานามสกุลไว้
+pub fn sanitize(filename: &str) -> Result<String, MultipartError> {
+    if filename.split(['/', '\\']).any(|component| component == "..") {
+        return Err(invalid("path traversal"));
+    }
+    if filename.chars().any(char::is_control) {
+        return Err(invalid("control character"));
+    }
+
+    let basename = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let replaced: String = compose(basename)
+        .chars()
+        .map(|c| if "<>:\"|?*".contains(c) { '_' } else { c })
+        .collect();
+    let name = replaced.trim_start_matches(['.', ' ']).trim_end_matches(['.', ' ']);
+    if name.is_empty() {
+        return Err(invalid("empty filename"));
+    }
+
+    let stem = name.split('.').next().unwrap_or_default().trim_end();
+    if RESERVED_NAMES.iter().any(|reserved| stem.eq_ignore_ascii_case(reserved)) {
+        return Err(invalid("reserved device name"));
+    }
+
+    Ok(truncate(name, MAX_FILENAME_LEN))
+}
+
+/// นามสกุลของไฟล์รวม `.` (เช่น `.jpg`) หรือ `""` ถ้าไม่มี
+pub fn extension(filename: &str) -> &str {
+    match filename.rfind('.') {
+        Some(dot) if dot > 0 => &filename[dot..],
+        _ => "",
+    }
+}
+
+/// ชื่อที่เติม suffix ครั้งที่ `n` ก่อนนามสกุล เช่น `photo.jpg` → `photo-2.jpg`
+pub fn with_suffix(filename: &str, n: usize) -> String {
+    let ext = extension(filename);
+    let stem = &filename[..filename.len() - ext.len()];
+    let suffix = format!("-{}", n);
+    // ตัด stem ให้พอใส่ suffix โดยชื่อรวมยังไม่เกิน limit
+    let stem = truncate(stem, MAX_FILENAME_LEN.saturating_sub(suffix.len() + ext.len()));
+    format!("{}{}{}", stem, suffix, ext)
+}
+
+/// UUID v4 แบบสุ่ม (RFC 9562) เช่น `3f2b8c1e-9a4d-4e7b-8c2f-1a2b3c4d5e6f`
+pub fn uuid_v4() -> String {
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&random_u64().to_be_bytes());
+    bytes[8..].copy_from_slice(&random_u64().to_be_bytes());
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let hex = to_hex(&bytes);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+/// เลขสุ่ม 64 bit จาก key สุ่มของ `RandomState` ผสมกับเวลาและ counter
+///
+/// ไม่ใช่ CSPRNG แต่เดาไม่ได้พอสำหรับตั้งชื่อไฟล์
+pub(crate) fn random_u64() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u128(SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos());
+    hasher.finish()
+}
+
+fn invalid(reason: &str) -> MultipartError {
+    MultipartError::InvalidFilename(reason.to_string())
+}
+
+/// ตัดให้ยาวไม่เกิน `max` bytes ที่ขอบตัวอักษร โดยรักษานามสกุลไว้ถ้าทำได้
+fn truncate(name: &str, max: usize) -> String {
+    if name.len() <= max {
+        return name.to_string();
+    }
+
+    let ext = extension(name);
+    let (stem, ext) = if ext.len() < max / 2 {
+        (&name[..name.len() - ext.len()], ext)
+    } else {
+        (name, "")
+    };
+    let mut end = max - ext.len();
+    while !stem.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &stem[..end], ext)
+}
+
+/// รวมตัวอักษรละตินกับเครื่องหมายที่ตามมาเป็นตัวเดียว (canonical composition ของ NFC)
+///
+/// ครอบคลุมตัวอักษรใน Latin-1 Supplement และ Latin Extended-A ซึ่งเป็นกรณีที่เจอบ่อย
+/// (macOS ส่งชื่อไฟล์แบบ NFD เช่น `e` + U+0301 แทน `é`) ภาษาไทยไม่มีการ compose จึงไม่กระทบ
+fn compose(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        let composed = out
+            .chars()
+            .next_back()
+            .and_then(|base| COMPOSITIONS.iter().find(|(b, mark, _)| *b == base && *mark == c));
+        match composed {
+            Some((_, _, composite)) => {
+                out.pop();
+                out.push(*composite);
+            }
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// (ตัวอักษร, combining mark, ตัวที่รวมแล้ว) สร้างจาก UnicodeData ช่วง U+00C0–U+017F
+#[rustfmt::skip]
+const COMPOSITIONS: &[(char, char, char)] = &[
+    ('A', '\u{300}', 'À'), ('A', '\u{301}', 'Á'), ('A', '\u{302}', 'Â'), ('A', '\u{303}', 'Ã'),
+    ('A', '\u{308}', 'Ä'), ('A', '\u{30A}', 'Å'), ('C', '\u{327}', 'Ç'), ('E', '\u{300}', 'È'),
+    ('E', '\u{301}', 'É'), ('E', '\u{302}', 'Ê'), ('E', '\u{308}', 'Ë'), ('I', '\u{300}', 'Ì'),
+    ('I', '\u{301}', 'Í'), ('I', '\u{302}', 'Î'), ('I', '\u{308}', 'Ï'), ('N', '\u{303}', 'Ñ'),
+    ('O', '\u{300}', 'Ò'), ('O', '\u{301}', 'Ó'), ('O', '\u{302}', 'Ô'), ('O', '\u{303}', 'Õ'),
+    ('O', '\u{308}', 'Ö'), ('U', '\u{300}', 'Ù'), ('U', '\u{301}', 'Ú'), ('U', '\u{302}', 'Û'),
+    ('U', '\u{308}', 'Ü'), ('Y', '\u{301}', 'Ý'), ('a', '\u{300}', 'à'), ('a', '\u{301}', 'á'),
+    ('a', '\u{302}', 'â'), ('a', '\u{303}', 'ã'), ('a', '\u{308}', 'ä'), ('a', '\u{30A}', 'å'),
+    ('c', '\u{327}', 'ç'), ('e', '\u{300}', 'è'), ('e', '\u{301}', 'é'), ('e', '\u{302}', 'ê'),
+    ('e', '\u{308}', 'ë'), ('i', '\u{300}', 'ì'), ('i', '\u{301}', 'í'), ('i', '\u{302}', 'î'),
+    ('i', '\u{308}', 'ï'), ('n', '\u{303}', 'ñ'), ('o', '\u{300}', 'ò'), ('o', '\u{301}', 'ó'),
+    ('o', '\u{302}', 'ô'), ('o', '\u{303}', 'õ'), ('o', '\u{308}', 'ö'), ('u', '\u{300}', 'ù'),
+    ('u', '\u{301}', 'ú'), ('u', '\u{302}', 'û'), ('u', '\u{308}', 'ü'), ('y', '\u{301}', 'ý'),
+    ('y', '\u{308}', 'ÿ'), ('A', '\u{304}', 'Ā'), ('a', '\u{304}', 'ā'), ('A', '\u{306}', 'Ă'),
+    ('a', '\u{306}', 'ă'), ('A', '\u{328}', 'Ą'), ('a', '\u{328}', 'ą'), ('C', '\u{301}', 'Ć'),
+    ('c', '\u{301}', 'ć'), ('C', '\u{302}', 'Ĉ'), ('c', '\u{302}', 'ĉ'), ('C', '\u{307}', 'Ċ'),
+    ('c', '\u{307}', 'ċ'), ('C', '\u{30C}', 'Č'), ('c', '\u{30C}', 'č'), ('D', '\u{30C}', 'Ď'),
+    ('d', '\u{30C}', 'ď'), ('E', '\u{304}', 'Ē'), ('e', '\u{304}', 'ē'), ('E', '\u{306}', 'Ĕ'),
+    ('e', '\u{306}', 'ĕ'), ('E', '\u{307}', 'Ė'), ('e', '\u{307}', 'ė'), ('E', '\u{328}', 'Ę'),
+    ('e', '\u{328}', 'ę'), ('E', '\u{30C}', 'Ě'), ('e', '\u{30C}', 'ě'), ('G', '\u{302}', 'Ĝ'),
+    ('g', '\u{302}', 'ĝ'), ('G', '\u{306}', 'Ğ'), ('g', '\u{306}', 'ğ'), ('G', '\u{307}', 'Ġ'),
+    ('g', '\u{307}', 'ġ'), ('G', '\u{327}', 'Ģ'), ('g', '\u{327}', 'ģ'), ('H', '\u{302}', 'Ĥ'),
+    ('h', '\u{302}', 'ĥ'), ('I', '\u{303}', 'Ĩ'), ('i', '\u{303}', 'ĩ'), ('I', '\u{304}', 'Ī'),
+    ('i', '\u{304}', 'ī'), ('I', '\u{306}', 'Ĭ'), ('i', '\u{306}', 'ĭ'), ('I', '\u{328}', 'Į'),
+    ('i', '\u{328}', 'į'), ('I', '\u{307}', 'İ'), ('J', '\u{302}', 'Ĵ'), ('j', '\u{302}', 'ĵ'),
+    ('K', '\u{327}', 'Ķ'), ('k', '\u{327}', 'ķ'), ('L', '\u{301}', 'Ĺ'), ('l', '\u{301}', 'ĺ'),
+    ('L', '\u{327}', 'Ļ'), ('l', '\u{327}', 'ļ'), ('L', '\u{30C}', 'Ľ'), ('l', '\u{30C}', 'ľ'),
+    ('N', '\u{301}', 'Ń'), ('n', '\u{301}', 'ń'), ('N', '\u{327}', 'Ņ'), ('n', '\u{327}', 'ņ'),
+    ('N', '\u{30C}', 'Ň'), ('n', '\u{30C}', 'ň'), ('O', '\u{304}', 'Ō'), ('o', '\u{304}', 'ō'),
+    ('O', '\u{306}', 'Ŏ'), ('o', '\u{306}', 'ŏ'), ('O', '\u{30B}', 'Ő'), ('o', '\u{30B}', 'ő'),
+    ('R', '\u{301}', 'Ŕ'), ('r', '\u{301}', 'ŕ'), ('R', '\u{327}', 'Ŗ'), ('r', '\u{327}', 'ŗ'),
+    ('R', '\u{30C}', 'Ř'), ('r', '\u{30C}', 'ř'), ('S', '\u{301}', 'Ś'), ('s', '\u{301}', 'ś'),
+    ('S', '\u{302}', 'Ŝ'), ('s', '\u{302}', 'ŝ'), ('S', '\u{327}', 'Ş'), ('s', '\u{327}', 'ş'),
+    ('S', '\u{30C}', 'Š'), ('s', '\u{30C}', 'š'), ('T', '\u{327}', 'Ţ'), ('t', '\u{327}', 'ţ'),
+    ('T', '\u{30C}', 'Ť'), ('t', '\u{30C}', 'ť'), ('U', '\u{303}', 'Ũ'), ('u', '\u{303}', 'ũ'),
+    ('U', '\u{304}', 'Ū'), ('u', '\u{304}', 'ū'), ('U', '\u{306}', 'Ŭ'), ('u', '\u{306}', 'ŭ'),
+    ('U', '\u{30A}', 'Ů'), ('u', '\u{30A}', 'ů'), ('U', '\u{30B}', 'Ű'), ('u', '\u{30B}', 'ű'),
+    ('U', '\u{328}', 'Ų'), ('u', '\u{328}', 'ų'), ('W', '\u{302}', 'Ŵ'), ('w', '\u{302}', 'ŵ'),
+    ('Y', '\u{302}', 'Ŷ'), ('y', '\u{302}', 'ŷ'), ('Y', '\u{308}', 'Ÿ'), ('Z', '\u{301}', 'Ź'),
+    ('z', '\u{301}', 'ź'), ('Z', '\u{307}', 'Ż'), ('z', '\u{307}', 'ż'), ('Z', '\u{30C}', 'Ž'),
+    ('z', '\u{30C}', 'ž'),
+];
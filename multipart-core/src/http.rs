@@ -0,0 +1,539 @@
+//! Parser ของ HTTP request head และ helper สำหรับดึง header ที่ multipart ต้องใช้
+
+use std::env;
+use std::io::{self, Read};
+use std::time::Duration;
+
+use crate::error::MultipartError;
+use crate::mime::{MediaType, is_token_char};
+
+/// เวลาที่รอ request ถัดไปบน connection เดิมก่อนปิด (keep-alive idle timeout)
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
+/// เวลาที่รอ bytes ถัดไปของ body ก่อนถือว่า client ค้าง (นับแต่ละครั้งที่อ่าน ไม่ใช่ทั้ง body)
+pub const DEFAULT_BODY_TIMEOUT: Duration = Duration::from_secs(30);
+/// ขนาดสูงสุดของ request head (request line + headers) default
+pub const DEFAULT_MAX_HEAD_SIZE: usize = 16 * 1024;
+/// จำนวน header สูงสุด default
+pub const DEFAULT_MAX_HEADERS: usize = 100;
+
+/// ขนาด buffer ที่ใช้อ่าน request head จาก stream
+const READ_CHUNK: usize = 4096;
+
+/// limit ของ request head เกินแล้วได้ `431 Request Header Fields Too Large`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadLimits {
+    /// ขนาดรวมของ request line และ headers (bytes)
+    pub max_size: usize,
+    /// จำนวน header field
+    pub max_headers: usize,
+}
+
+impl Default for HeadLimits {
+    fn default() -> Self {
+        Self {
+            max_size: DEFAULT_MAX_HEAD_SIZE,
+            max_headers: DEFAULT_MAX_HEADERS,
+        }
+    }
+}
+
+/// headers ของ request ค้นหาชื่อแบบไม่สนตัวพิมพ์เล็ก/ใหญ่
+///
+/// header ชื่อซ้ำจะถูกรวมเป็นค่าเดียวคั่นด้วย `, ` ตาม RFC 9110 §5.3
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeaderMap {
+    entries: Vec<(String, String)>,
+}
+
+impl HeaderMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// เพิ่ม header ถ้ามีชื่อนี้อยู่แล้วต่อท้ายค่าเดิม
+    pub fn append(&mut self, name: &str, value: &str) {
+        match self.entries.iter_mut().find(|(key, _)| key.eq_ignore_ascii_case(name)) {
+            Some((_, existing)) => {
+                existing.push_str(", ");
+                existing.push_str(value);
+            }
+            None => self.entries.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// ค่าของ header (trim แล้ว)
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// จำนวน header (นับชื่อซ้ำเป็นตัวเดียว)
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// header ทั้งหมดตามลำดับที่เจอครั้งแรก
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+}
+
+/// header ที่มีได้ค่าเดียว ถ้าส่งมาซ้ำด้วยค่าต่างกันจะตอบ 400 (กัน request smuggling)
+const SINGLETON_HEADERS: [&str; 4] = ["content-length", "content-type", "host", "authorization"];
+
+/// request line และ headers ที่ parse แล้ว
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestHead {
+    pub method: String,
+    /// request-target ตามที่ส่งมา (รวม query string)
+    pub target: String,
+    /// เช่น `HTTP/1.1`
+    pub version: String,
+    pub headers: HeaderMap,
+}
+
+impl RequestHead {
+    /// parse request head ที่จบด้วยบรรทัดว่าง (`\r\n\r\n`) ตาม RFC 9112
+    ///
+    /// บรรทัดที่ขึ้นต้นด้วย space/tab (obs-fold) จะถูกต่อเข้ากับ header ก่อนหน้าด้วย space
+    pub fn parse(bytes: &[u8], limits: &HeadLimits) -> Result<Self, MultipartError> {
+        if bytes.len() > limits.max_size {
+            return Err(MultipartError::HeaderFieldsTooLarge {
+                limit: "request head size",
+                max: limits.max_size,
+            });
+        }
+
+        let text = std::str::from_utf8(bytes).map_err(|_| malformed("request head is not valid UTF-8"))?;
+        let mut lines = text.strip_suffix("\r\n\r\n").unwrap_or(text).split("\r\n");
+
+        let request_line = lines.next().unwrap_or_default();
+        let mut parts = request_line.split(' ');
+        let (Some(method), Some(target), Some(version), None) = (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(malformed("request line must be `method target version`"));
+        };
+        if method.is_empty() || !method.bytes().all(is_token_char) {
+            return Err(malformed("invalid method"));
+        }
+        if target.is_empty() {
+            return Err(malformed("empty request target"));
+        }
+        if !matches!(version, "HTTP/1.0" | "HTTP/1.1") {
+            return Err(malformed("unsupported HTTP version"));
+        }
+
+        // รวม obs-fold ก่อนแล้วค่อยแยกชื่อกับค่า
+        let mut fields: Vec<String> = Vec::new();
+        for line in lines {
+            if line.starts_with([' ', '\t']) {
+                let Some(previous) = fields.last_mut() else {
+                    return Err(malformed("folded line before first header"));
+                };
+                previous.push(' ');
+                previous.push_str(line.trim());
+            } else {
+                fields.push(line.to_string());
+            }
+        }
+        if fields.len() > limits.max_headers {
+            return Err(MultipartError::HeaderFieldsTooLarge {
+                limit: "header count",
+                max: limits.max_headers,
+            });
+        }
+
+        let mut headers = HeaderMap::new();
+        for field in &fields {
+            let Some((name, value)) = field.split_once(':') else {
+                return Err(malformed("header line without ':'"));
+            };
+            // ห้ามมี whitespace ระหว่างชื่อกับ ':' (RFC 9112 §5.1)
+            if name.is_empty() || !name.bytes().all(is_token_char) {
+                return Err(malformed("invalid header name"));
+            }
+            let value = value.trim_matches([' ', '\t']);
+
+            if let Some(existing) = headers.get(name)
+                && SINGLETON_HEADERS.iter().any(|singleton| name.eq_ignore_ascii_case(singleton))
+            {
+                if existing == value {
+                    continue;
+                }
+                return Err(malformed(&format!("conflicting {} headers", name)));
+            }
+            headers.append(name, value);
+        }
+
+        Ok(Self {
+            method: method.to_string(),
+            target: target.to_string(),
+            version: version.to_string(),
+            headers,
+        })
+    }
+
+    /// path ของ target (ตัด query string ออก)
+    pub fn path(&self) -> &str {
+        self.target.split('?').next().unwrap_or_default()
+    }
+
+    /// ค่าของ header (ไม่สนตัวพิมพ์เล็ก/ใหญ่)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)
+    }
+
+    /// request line เช่น `POST /upload HTTP/1.1` ไว้ใช้ log
+    pub fn request_line(&self) -> String {
+        format!("{} {} {}", self.method, self.target, self.version)
+    }
+
+    fn is_http11(&self) -> bool {
+        self.version == "HTTP/1.1"
+    }
+}
+
+fn malformed(reason: &str) -> MultipartError {
+    MultipartError::MalformedRequest(reason.to_string())
+}
+
+/// stream ของ connection พร้อม bytes ที่อ่านเกินมาจาก request ก่อนหน้า
+///
+/// เช่น chunked decoder อาจอ่านเลย last-chunk ไปถึง request ถัดไปที่ pipeline มา
+/// bytes พวกนั้นคืนเข้ามาด้วย [`Connection::unread`] แล้วการอ่านครั้งถัดไปจะได้มันก่อนอ่านจาก stream
+pub struct Connection<S> {
+    stream: S,
+    buffer: Vec<u8>,
+}
+
+impl<S> Connection<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// stream ตัวจริง (ไว้เขียน response หรือตั้ง timeout)
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
+    /// คืน bytes ที่อ่านเกินมา ให้เป็นข้อมูลแรกของการอ่านครั้งถัดไป
+    pub fn unread(&mut self, data: &[u8]) {
+        self.buffer.splice(..0, data.iter().copied());
+    }
+
+    /// มี bytes ค้างอยู่ใน buffer หรือไม่ (เช่น request ที่ pipeline มา)
+    pub fn has_buffered(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// ถ้าใน buffer มี request head ครบแล้ว parse แล้วตัดออกจาก buffer
+    /// bytes ที่เหลือ (ส่วนต้นของ body หรือ request ถัดไป) ยังอยู่ใน buffer ให้อ่านต่อได้
+    pub(crate) fn take_head(&mut self, limits: &HeadLimits) -> Result<Option<RequestHead>, MultipartError> {
+        // RFC 9112 §2.2: ข้ามบรรทัดว่างก่อน request line (เช่น CRLF ที่ client ส่งเกินมาหลัง body)
+        let blank = self.buffer.iter().take_while(|&&b| b == b'\r' || b == b'\n').count();
+        self.buffer.drain(..blank);
+
+        match self.buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(end) => {
+                let head = RequestHead::parse(&self.buffer[..end + 4], limits)?;
+                self.buffer.drain(..end + 4);
+                Ok(Some(head))
+            }
+            None if self.buffer.len() > limits.max_size => Err(MultipartError::HeaderFieldsTooLarge {
+                limit: "request head size",
+                max: limits.max_size,
+            }),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn extend_buffer(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// ย้าย bytes ใน buffer ลง `buf` เท่าที่ใส่ได้ คืนจำนวนที่ย้าย
+    pub(crate) fn read_buffered(&mut self, buf: &mut [u8]) -> usize {
+        let n = self.buffer.len().min(buf.len());
+        buf[..n].copy_from_slice(&self.buffer[..n]);
+        self.buffer.drain(..n);
+        n
+    }
+}
+
+impl<S: Read> Connection<S> {
+    /// อ่าน request head ถัดไปทีละ block (ไม่ใช่ทีละ byte)
+    ///
+    /// คืน `Ok(None)` ถ้า connection ปิดก่อนมี request ใหม่ bytes ของ body ที่อ่านเกินมา
+    /// ยังอยู่ใน buffer และจะได้ก่อนเมื่อ [`Read::read`] จาก connection นี้
+    pub fn read_head(&mut self, limits: &HeadLimits) -> Result<Option<RequestHead>, MultipartError> {
+        let mut chunk = [0u8; READ_CHUNK];
+        loop {
+            if let Some(head) = self.take_head(limits)? {
+                return Ok(Some(head));
+            }
+            let n = self.stream.read(&mut chunk)?;
+            if n == 0 {
+                return if self.buffer.is_empty() {
+                    Ok(None)
+                } else {
+                    Err(malformed("connection closed in request head"))
+                };
+            }
+            self.extend_buffer(&chunk[..n]);
+        }
+    }
+}
+
+impl<S: Read> Read for Connection<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.buffer.is_empty() {
+            self.stream.read(buf)
+        } else {
+            Ok(self.read_buffered(buf))
+        }
+    }
+}
+
+/// `Content-Type` ของ request ที่ต้องเป็น `multipart/*` (ใช้กับ [`StreamingParser::for_media_type`])
+///
+/// body ที่ไม่ใช่ `multipart/*` หรือไม่มี `Content-Type` ได้ 415
+///
+/// [`StreamingParser::for_media_type`]: crate::StreamingParser::for_media_type
+pub fn media_type(head: &RequestHead) -> Result<MediaType, MultipartError> {
+    let content_type = head
+        .header("content-type")
+        .ok_or_else(|| MultipartError::UnsupportedMediaType(String::new()))?;
+    let media_type = MediaType::parse(content_type)?;
+    if !media_type.is_multipart() {
+        return Err(MultipartError::UnsupportedMediaType(media_type.essence()));
+    }
+    Ok(media_type)
+}
+
+/// ดึง boundary จาก `Content-Type` แล้วคืนในรูป delimiter (`--` + boundary)
+///
+/// body ที่ไม่ใช่ `multipart/*` ได้ 415 ส่วน boundary ที่ไม่มีหรือผิด RFC 2046 ได้ 400
+pub fn boundary_delimiter(head: &RequestHead) -> Result<String, MultipartError> {
+    Ok(format!("--{}", media_type(head)?.boundary()?))
+}
+
+/// ดึงค่า `Content-Length` คืน `Ok(None)` ถ้าไม่มี header นี้
+///
+/// ค่าต้องเป็นตัวเลขล้วนตาม RFC 9110 (`1*DIGIT`) จึงไม่รับ `+5` หรือ `5, 5`
+pub fn content_length(head: &RequestHead) -> Result<Option<usize>, MultipartError> {
+    match head.header("content-length") {
+        Some(value) if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| MultipartError::InvalidContentLength(value.to_string())),
+        Some(value) => Err(MultipartError::InvalidContentLength(value.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// วิธีหาจุดจบของ request body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BodyFraming {
+    /// อ่านครบตาม `Content-Length`
+    ContentLength(usize),
+    /// `Transfer-Encoding: chunked` ต้อง decode ด้วย [`ChunkedDecoder`](crate::chunked::ChunkedDecoder)
+    Chunked,
+    /// ไม่มีทั้งสอง header อ่านจน connection ปิด
+    UntilClose,
+}
+
+impl BodyFraming {
+    /// จำนวน bytes ที่อ่านจาก socket ได้ในครั้งถัดไป (ไม่เกิน `buffer_size`) หลังอ่าน body ไปแล้ว `bytes_read` bytes
+    ///
+    /// คืน `None` เมื่ออ่านครบ `Content-Length` แล้ว รวมถึง `Content-Length: 0` ที่ไม่ต้องอ่านเลย
+    /// (อ่านเกินจะกิน request ถัดไปที่ pipeline มา) ส่วน chunked ผู้เรียกต้องหยุดเองเมื่อเจอ last-chunk
+    pub fn next_read(self, bytes_read: usize, buffer_size: usize) -> Option<usize> {
+        match self {
+            BodyFraming::ContentLength(len) if bytes_read >= len => None,
+            BodyFraming::ContentLength(len) => Some((len - bytes_read).min(buffer_size)),
+            BodyFraming::Chunked | BodyFraming::UntilClose => Some(buffer_size),
+        }
+    }
+}
+
+/// เลือกวิธีอ่าน body จาก headers ตาม RFC 9112 §6.3
+///
+/// รองรับ `Transfer-Encoding` แค่ `chunked` อย่างเดียว (coding อื่นเช่น `gzip, chunked` ได้ 501)
+///
+/// request ที่มีทั้ง `Transfer-Encoding` และ `Content-Length` ได้ 400 เพราะ proxy ข้างหน้าอาจเลือก
+/// คนละตัวกับเรา ทำให้ body ส่วนหนึ่งกลายเป็น request ที่ถูกแอบส่งต่อ (request smuggling, RFC 9112 §6.1)
+pub fn body_framing(head: &RequestHead) -> Result<BodyFraming, MultipartError> {
+    if let Some(value) = head.header("transfer-encoding") {
+        if head.header("content-length").is_some() {
+            return Err(MultipartError::MalformedRequest(
+                "both Transfer-Encoding and Content-Length are present".to_string(),
+            ));
+        }
+        if value.eq_ignore_ascii_case("chunked") {
+            return Ok(BodyFraming::Chunked);
+        }
+        return Err(MultipartError::UnsupportedTransferEncoding(value.to_string()));
+    }
+
+    Ok(match content_length(head)? {
+        Some(len) => BodyFraming::ContentLength(len),
+        None => BodyFraming::UntilClose,
+    })
+}
+
+/// ควรเก็บ connection ไว้รับ request ถัดไปหรือไม่ ตาม `Connection` header และ HTTP version
+///
+/// HTTP/1.1 เก็บไว้เป็น default จนกว่าจะเจอ `close` ส่วน HTTP/1.0 ต้องขอ `keep-alive` เอง
+pub fn keep_alive(head: &RequestHead) -> bool {
+    let has_token = |token: &str| {
+        head.header("connection")
+            .is_some_and(|value| value.split(',').any(|t| t.trim().eq_ignore_ascii_case(token)))
+    };
+
+    if has_token("close") {
+        false
+    } else {
+        head.is_http11() || has_token("keep-alive")
+    }
+}
+
+/// interim response ที่บอก client ให้ส่ง body ต่อได้
+pub const CONTINUE_RESPONSE: &str = "HTTP/1.1 100 Continue\r\n\r\n";
+
+/// client ส่ง `Expect: 100-continue` มาและรอคำตอบก่อนส่ง body หรือไม่
+///
+/// HTTP/1.0 ไม่มี interim response จึงไม่ต้องตอบแม้จะส่ง header นี้มา
+pub fn expects_continue(head: &RequestHead) -> bool {
+    head.is_http11()
+        && head.header("expect").is_some_and(|value| value.eq_ignore_ascii_case("100-continue"))
+}
+
+/// เงื่อนไขที่ตรวจได้จาก request head ก่อนรับ body
+///
+/// ใช้คู่กับ [`check_head`] เพื่อปฏิเสธ upload ที่ยังไงก็ไม่ผ่านตั้งแต่ก่อน client ส่ง body
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UploadPolicy {
+    /// path ที่รับ upload (`None` = รับทุก path)
+    pub path: Option<String>,
+    /// ขนาด body สูงสุดตาม `Content-Length` (`None` = ไม่จำกัด, body แบบ chunked ตรวจล่วงหน้าไม่ได้)
+    pub max_body_size: Option<usize>,
+    /// token ที่ต้องส่งมาใน `Authorization: Bearer <token>` (`None` = ไม่ต้องมี)
+    pub auth_token: Option<String>,
+}
+
+impl UploadPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// รับ upload เฉพาะ path นี้
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    /// จำกัดขนาด body ที่ประกาศใน `Content-Length`
+    pub fn with_max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = Some(max_body_size);
+        self
+    }
+
+    /// บังคับให้ส่ง `Authorization: Bearer <token>`
+    pub fn with_auth_token(mut self, token: &str) -> Self {
+        self.auth_token = Some(token.to_string());
+        self
+    }
+
+    /// อ่านค่าจาก env `UPLOAD_PATH`, `UPLOAD_MAX_BODY` และ `UPLOAD_TOKEN` (ไม่มี = ไม่ตรวจ)
+    pub fn from_env() -> Self {
+        let read = |name: &str| env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        Self {
+            path: read("UPLOAD_PATH"),
+            max_body_size: read("UPLOAD_MAX_BODY").and_then(|value| value.trim().parse().ok()),
+            auth_token: read("UPLOAD_TOKEN"),
+        }
+    }
+}
+
+/// ตรวจ request head ตาม `policy` ก่อนอ่าน body
+///
+/// ตรวจ method, path, `Expect`, token, boundary, framing และ `Content-Length`
+/// ถ้าไม่ผ่านคืน error ที่ map เป็น 4xx/413 ให้ตอบกลับได้ทันทีโดยไม่ต้องรอ body
+pub fn check_head(head: &RequestHead, policy: &UploadPolicy) -> Result<(), MultipartError> {
+    if head.method != "POST" && head.method != "PUT" {
+        return Err(MultipartError::MethodNotAllowed(head.method.clone()));
+    }
+    if let Some(expected) = &policy.path
+        && head.path() != expected
+    {
+        return Err(MultipartError::NotFound(head.path().to_string()));
+    }
+    if let Some(expect) = head.header("expect")
+        && !expect.eq_ignore_ascii_case("100-continue")
+    {
+        return Err(MultipartError::ExpectationFailed(expect.to_string()));
+    }
+    if let Some(token) = &policy.auth_token {
+        let authorized = head
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|value| value.trim() == token);
+        if !authorized {
+            return Err(MultipartError::Unauthorized);
+        }
+    }
+
+    boundary_delimiter(head)?;
+    if let (BodyFraming::ContentLength(len), Some(max)) = (body_framing(head)?, policy.max_body_size)
+        && len > max
+    {
+        return Err(MultipartError::LimitExceeded { limit: "body size", max });
+    }
+
+    Ok(())
+}
+
+/// สร้าง HTTP response แบบง่ายที่มี body เป็น text
+pub fn simple_response(status: u16, reason: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+}
+
+/// เหมือน [`simple_response`] แต่บอก client ด้วยว่า connection จะถูกเก็บไว้หรือปิด
+pub fn keep_alive_response(status: u16, reason: &str, body: &str, keep_alive: bool) -> String {
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: {}\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        if keep_alive { "keep-alive" } else { "close" },
+        body
+    )
+}
+
+/// สร้าง response จาก error ตาม status ที่ error นั้นกำหนด
+///
+/// หลัง error ไม่รู้ว่า body เหลืออยู่เท่าไหร่ จึงปิด connection เสมอ
+pub fn error_response(error: &MultipartError) -> String {
+    let (status, reason) = error.status();
+    keep_alive_response(status, reason, &error.to_string(), false)
+}
@@ -0,0 +1,62 @@
+//! # multipart-core
+//!
+//! Streaming parser สำหรับ HTTP `multipart/form-data` ที่ใช้ร่วมกันระหว่าง sub_lab1 และ sub_lab2
+//!
+//! แนวคิดหลักคือรับ body ทีละ chunk (ขนาดเท่าไหร่ก็ได้) โดยเก็บไว้ใน memory
+//! แค่ส่วนท้ายของ chunk ที่อาจเป็น boundary ที่ถูกตัดขาด
+//!
+//! - [`StreamingParser`] เป็น parser แบบ sans-IO ที่ปล่อย [`Event`] ให้ผู้เรียกตัดสินใจเอง
+//! - [`Subtype`] บอกว่า body เป็น `multipart/form-data`, `mixed`, `related` หรือ `byteranges`
+//!   ตัวช่วยของแต่ละแบบอยู่ใน [`subtype`]
+//! - [`ContentDisposition`] parse `Content-Disposition` ของแต่ละ part (รวม `filename*` ของ RFC 5987)
+//! - [`ChunkedDecoder`] decode `Transfer-Encoding: chunked` ก่อนส่งเข้า parser
+//! - [`transfer`] decode `Content-Transfer-Encoding` (base64, quoted-printable) ของแต่ละ part
+//! - [`UploadProcessor`] เป็น consumer สำเร็จรูปที่ stream ไฟล์ลง disk โดยตรง
+//!   โดยตั้งชื่อไฟล์ที่ปลอดภัยตาม [`NamingStrategy`]
+//! - [`storage`] เปลี่ยนที่เก็บไฟล์ได้ตาม route หรือ field (disk, memory, S3 ผ่าน [`s3`])
+//! - [`MultipartWriter`] สร้าง body แบบ multipart (request ของ client หรือ response แบบ `byteranges`)
+//! - `async_io` (feature `tokio`) เป็น driver แบบ async ที่ใช้ parser ตัวเดียวกัน
+//!
+//! ```no_run
+//! use multipart_core::UploadProcessor;
+//!
+//! let mut upload = UploadProcessor::new("--boundary123", "./uploads")?;
+//! upload.process_chunk(b"--boundary123\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n")?;
+//! upload.process_chunk(b"--boundary123--\r\n")?;
+//! upload.finalize()?;
+//!
+//! println!("files: {}", upload.get_stats().files_count);
+//! # Ok::<(), multipart_core::MultipartError>(())
+//! ```
+
+#[cfg(feature = "tokio")]
+pub mod async_io;
+pub mod chunked;
+pub mod digest;
+pub mod disposition;
+pub mod error;
+pub mod filename;
+pub mod http;
+pub mod limits;
+pub mod mime;
+pub mod parser;
+pub mod pool;
+pub mod s3;
+pub mod search;
+pub mod storage;
+pub mod subtype;
+pub mod transfer;
+pub mod upload;
+pub mod writer;
+
+pub use chunked::{ChunkedDecoder, ChunkedEvent};
+pub use digest::DigestAlgorithm;
+pub use disposition::ContentDisposition;
+pub use error::MultipartError;
+pub use filename::NamingStrategy;
+pub use limits::Limits;
+pub use mime::MediaType;
+pub use parser::{Event, ParserState, PartHeaders, PartType, StreamingParser};
+pub use subtype::Subtype;
+pub use upload::{FileInfo, FsyncMode, Stats, UploadProcessor};
+pub use writer::MultipartWriter;
@@ -0,0 +1,79 @@
+//! ขอบเขตของ multipart body ที่ตรวจระหว่าง stream
+//!
+//! ทุก limit ที่เกินจะคืน [`MultipartError::LimitExceeded`](crate::MultipartError::LimitExceeded)
+//! (413 Payload Too Large) โดย `limit` เป็นชื่อของ limit นั้น:
+//! `"file size"`, `"field size"`, `"body size"`, `"part count"`, `"file count"`, `"part header size"`,
+//! `"nesting depth"`
+
+use std::env;
+
+/// ขนาดสูงสุด default ของไฟล์แต่ละไฟล์ (2GB)
+pub const DEFAULT_MAX_FILE_SIZE: usize = 2 * 1024 * 1024 * 1024;
+/// ขนาดสูงสุด default ของค่า text field แต่ละตัว (64KB)
+pub const DEFAULT_MAX_FIELD_SIZE: usize = 64 * 1024;
+/// ขนาดสูงสุด default ของ body ทั้งหมดหลัง decode chunked (4GB หรือ `usize::MAX` บน target 32-bit ที่นับถึง 4GB ไม่ได้)
+pub const DEFAULT_MAX_BODY_SIZE: usize = if usize::BITS > 32 { (4u64 * 1024 * 1024 * 1024) as usize } else { usize::MAX };
+/// จำนวน part สูงสุด default (รวม field และไฟล์)
+pub const DEFAULT_MAX_PARTS: usize = 1000;
+/// จำนวนไฟล์สูงสุด default
+pub const DEFAULT_MAX_FILES: usize = 100;
+/// ขนาดสูงสุด default ของ header block ของแต่ละ part (8KB)
+pub const DEFAULT_MAX_PART_HEADER_SIZE: usize = 8 * 1024;
+/// จำนวนชั้นสูงสุด default ของ `multipart/mixed` ที่ซ้อนอยู่ใน part (1 = ซ้อนได้ชั้นเดียวแบบ RFC 2388)
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 1;
+
+/// ขอบเขตของ multipart body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// bytes ของไฟล์แต่ละไฟล์
+    pub max_file_size: usize,
+    /// bytes ของค่า text field แต่ละตัว (ค่าถูกเก็บไว้ใน memory)
+    pub max_field_size: usize,
+    /// bytes ของ body ทั้งหมดที่ feed เข้า parser
+    pub max_body_size: usize,
+    /// จำนวน part ทั้งหมด
+    pub max_parts: usize,
+    /// จำนวน part ที่เป็นไฟล์
+    pub max_files: usize,
+    /// bytes ของ header block ของแต่ละ part (ไม่รวม CRLF CRLF ที่ปิดท้าย)
+    pub max_part_header_size: usize,
+    /// จำนวนชั้นของ `multipart/mixed` ที่ซ้อนกันได้ (0 = ไม่รับ body ที่ซ้อน)
+    pub max_nesting_depth: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            max_field_size: DEFAULT_MAX_FIELD_SIZE,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            max_parts: DEFAULT_MAX_PARTS,
+            max_files: DEFAULT_MAX_FILES,
+            max_part_header_size: DEFAULT_MAX_PART_HEADER_SIZE,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+        }
+    }
+}
+
+impl Limits {
+    /// อ่านค่าจาก env `UPLOAD_MAX_FILE`, `UPLOAD_MAX_FIELD`, `UPLOAD_MAX_TOTAL`, `UPLOAD_MAX_PARTS`,
+    /// `UPLOAD_MAX_FILES`, `UPLOAD_MAX_PART_HEADER` และ `UPLOAD_MAX_DEPTH` ถ้าไม่มีหรือผิดใช้ค่า default
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: usize| {
+            env::var(name)
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            max_file_size: read("UPLOAD_MAX_FILE", DEFAULT_MAX_FILE_SIZE),
+            max_field_size: read("UPLOAD_MAX_FIELD", DEFAULT_MAX_FIELD_SIZE),
+            max_body_size: read("UPLOAD_MAX_TOTAL", DEFAULT_MAX_BODY_SIZE),
+            max_parts: read("UPLOAD_MAX_PARTS", DEFAULT_MAX_PARTS),
+            max_files: read("UPLOAD_MAX_FILES", DEFAULT_MAX_FILES),
+            max_part_header_size: read("UPLOAD_MAX_PART_HEADER", DEFAULT_MAX_PART_HEADER_SIZE),
+            max_nesting_depth: read("UPLOAD_MAX_DEPTH", DEFAULT_MAX_NESTING_DEPTH),
+        }
+    }
+}
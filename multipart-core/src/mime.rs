@@ -0,0 +1,215 @@
+//! Parser ของ media type (`Content-Type`) ตาม RFC 9110 §8.3.1 และการตรวจ boundary ตาม RFC 2046 §5.1.1
+//!
+//! ```text
+//! multipart/form-data; charset=utf-8; Boundary="----abc\"def"
+//! └─type──┘ └subtype┘  └─ parameters (ชื่อไม่สนตัวพิมพ์, ค่าเป็น token หรือ quoted-string) ─┘
+//! ```
+
+use crate::error::MultipartError;
+
+/// ความยาวสูงสุดของ boundary ตาม RFC 2046
+pub const MAX_BOUNDARY_LEN: usize = 70;
+
+/// media type ที่ parse แล้ว `main_type` และ `subtype` เป็นตัวพิมพ์เล็กเสมอ
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaType {
+    pub main_type: String,
+    pub subtype: String,
+    params: Vec<(String, String)>,
+}
+
+impl MediaType {
+    /// parse ค่าของ `Content-Type` header
+    pub fn parse(input: &str) -> Result<Self, MultipartError> {
+        let input = input.trim_matches([' ', '\t']);
+        let essence_end = input.find(';').unwrap_or(input.len());
+        let essence = input[..essence_end].trim_end_matches([' ', '\t']);
+
+        let Some((main_type, subtype)) = essence.split_once('/') else {
+            return Err(invalid("missing '/' between type and subtype"));
+        };
+        if !is_token(main_type) || !is_token(subtype) {
+            return Err(invalid("type and subtype must be tokens"));
+        }
+
+        let params = parse_params(&input[essence_end..], ParamSyntax::Strict).map_err(invalid)?;
+        for (i, (name, _)) in params.iter().enumerate() {
+            // พารามิเตอร์ชื่อซ้ำ (เช่น boundary สองตัว) ตีความได้หลายแบบ จึงปฏิเสธไปเลย
+            if params[..i].iter().any(|(other, _)| other == name) {
+                return Err(invalid(&format!("duplicate parameter {:?}", name)));
+            }
+        }
+
+        Ok(Self {
+            main_type: main_type.to_ascii_lowercase(),
+            subtype: subtype.to_ascii_lowercase(),
+            params,
+        })
+    }
+
+    /// `type/subtype` เช่น `multipart/form-data`
+    pub fn essence(&self) -> String {
+        format!("{}/{}", self.main_type, self.subtype)
+    }
+
+    /// เป็น `multipart/*` หรือไม่
+    pub fn is_multipart(&self) -> bool {
+        self.main_type == "multipart"
+    }
+
+    /// ค่าของพารามิเตอร์ (ชื่อไม่สนตัวพิมพ์ ค่าถอด quote/escape แล้ว)
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// พารามิเตอร์ทั้งหมดตามลำดับที่ส่งมา (ชื่อเป็นตัวพิมพ์เล็ก)
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// boundary ที่ผ่านการตรวจตาม RFC 2046 แล้ว (ยังไม่มี `--` นำหน้า)
+    pub fn boundary(&self) -> Result<&str, MultipartError> {
+        let boundary = self.param("boundary").ok_or(MultipartError::MissingBoundary)?;
+        validate_boundary(boundary)?;
+        Ok(boundary)
+    }
+}
+
+/// ตรวจ boundary: ยาว 1–70 ตัว ใช้ได้แค่ `bchars` และห้ามจบด้วย space
+pub fn validate_boundary(boundary: &str) -> Result<(), MultipartError> {
+    let reason = if boundary.is_empty() {
+        "boundary is empty"
+    } else if boundary.len() > MAX_BOUNDARY_LEN {
+        "boundary is longer than 70 characters"
+    } else if !boundary.bytes().all(is_bchar) {
+        "boundary contains characters outside RFC 2046 bchars"
+    } else if boundary.ends_with(' ') {
+        "boundary ends with a space"
+    } else {
+        return Ok(());
+    };
+    Err(MultipartError::InvalidBoundary(reason.to_string()))
+}
+
+fn invalid(reason: &str) -> MultipartError {
+    MultipartError::InvalidMediaType(reason.to_string())
+}
+
+/// bchars ตาม RFC 2046 §5.1.1
+fn is_bchar(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"'()+_,-./:=? ".contains(&b)
+}
+
+/// tchar ตาม RFC 9110 §5.6.2
+pub(crate) fn is_token_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(is_token_char)
+}
+
+/// ความเคร่งของ [`parse_params`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ParamSyntax {
+    /// ตาม RFC 9110: value เป็น token หรือ quoted-string และ `\` escape ตัวถัดไปเสมอ
+    Strict,
+    /// แบบที่ browser ส่งมาจริง: `\` escape แค่ `"` กับ `\` (path ของ Windows ยังอยู่ครบ)
+    /// และ value ที่ไม่มี quote ยาวไปจนถึง `;`
+    Lenient,
+}
+
+/// แยก `*( OWS ";" OWS [ name "=" value ] )` โดย value เป็น token หรือ quoted-string
+///
+/// ชื่อพารามิเตอร์คืนเป็นตัวพิมพ์เล็ก ค่าคืนแบบถอด quote และ `\` escape แล้ว
+pub(crate) fn parse_params(input: &str, syntax: ParamSyntax) -> Result<Vec<(String, String)>, &'static str> {
+    let bytes = input.as_bytes();
+    let mut params = Vec::new();
+    let mut i = 0;
+
+    let skip_ws = |i: &mut usize| {
+        while *i < bytes.len() && matches!(bytes[*i], b' ' | b'\t') {
+            *i += 1;
+        }
+    };
+
+    loop {
+        skip_ws(&mut i);
+        if i == bytes.len() {
+            return Ok(params);
+        }
+        if bytes[i] != b';' {
+            return Err("expected ';' between parameters");
+        }
+        i += 1;
+        skip_ws(&mut i);
+        // ยอม `;` ว่างหรือ `;` ปิดท้าย
+        if i == bytes.len() || bytes[i] == b';' {
+            continue;
+        }
+
+        let name_start = i;
+        while i < bytes.len() && is_token_char(bytes[i]) {
+            i += 1;
+        }
+        if i == name_start {
+            return Err("parameter name must be a token");
+        }
+        let name = input[name_start..i].to_ascii_lowercase();
+
+        if i == bytes.len() || bytes[i] != b'=' {
+            return Err("expected '=' after parameter name");
+        }
+        i += 1;
+
+        let value = if bytes.get(i) == Some(&b'"') {
+            i += 1;
+            let mut value = Vec::new();
+            loop {
+                match bytes.get(i) {
+                    None => return Err("unterminated quoted-string"),
+                    Some(b'"') => {
+                        i += 1;
+                        break;
+                    }
+                    Some(b'\\')
+                        if i + 1 < bytes.len()
+                            && (syntax == ParamSyntax::Strict || matches!(bytes[i + 1], b'"' | b'\\')) =>
+                    {
+                        value.push(bytes[i + 1]);
+                        i += 2;
+                    }
+                    Some(&b) => {
+                        value.push(b);
+                        i += 1;
+                    }
+                }
+            }
+            String::from_utf8_lossy(&value).into_owned()
+        } else if syntax == ParamSyntax::Lenient {
+            let value_start = i;
+            while i < bytes.len() && bytes[i] != b';' {
+                i += 1;
+            }
+            let value = input[value_start..i].trim_end_matches([' ', '\t']);
+            if value.is_empty() {
+                return Err("empty parameter value");
+            }
+            value.to_string()
+        } else {
+            let value_start = i;
+            while i < bytes.len() && is_token_char(bytes[i]) {
+                i += 1;
+            }
+            if i == value_start {
+                return Err("parameter value must be a token or quoted-string");
+            }
+            input[value_start..i].to_string()
+        };
+
+        params.push((name, value));
+    }
+}
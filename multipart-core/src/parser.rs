@@ -0,0 +1,614 @@
+//! Sans-IO multipart parser แบบ pull
+//!
+//! Parser ไม่ได้เปิดไฟล์หรือ print อะไรเอง แค่รับ bytes ผ่าน [`StreamingParser::feed`]
+//! แล้วให้ผู้เรียกดึง [`Event`] ออกมาทีละตัวด้วย [`StreamingParser::next_event`]
+//! ว่าจะเก็บ, hash, ส่งต่อ หรือปฏิเสธ part นั้นก็แล้วแต่ผู้เรียก
+
+use crate::disposition::ContentDisposition;
+use crate::error::MultipartError;
+use crate::http::HeaderMap;
+use crate::limits::{DEFAULT_MAX_NESTING_DEPTH, DEFAULT_MAX_PART_HEADER_SIZE};
+use crate::mime::{MediaType, is_token_char};
+use crate::search::Finder;
+use crate::subtype::{self, ContentRange, Subtype};
+
+/// header ของ part ที่ห้ามส่งซ้ำ (ตีความได้หลายแบบ)
+const SINGLETON_HEADERS: [&str; 2] = ["content-disposition", "content-type"];
+
+/// ประเภทของ part ที่กำลังอ่านอยู่
+#[derive(Debug, Clone, PartialEq)]
+pub enum PartType {
+    /// field ธรรมดา (ไม่มี `filename`)
+    Field,
+    /// ไฟล์ที่ต้อง stream ลง disk, `content_type` ว่างถ้า part ไม่ได้ส่ง `Content-Type` มา
+    File { filename: String, content_type: String },
+}
+
+/// Headers ของ part ที่ parse แล้ว
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartHeaders {
+    /// ชื่อ field จาก `Content-Disposition: form-data; name="..."`
+    ///
+    /// part ที่อยู่ใน `multipart/mixed` ที่ซ้อนอยู่ใช้ path ตามลำดับแทน เช่น `attachments[0]`
+    /// (หรือ `attachments[0][1]` ถ้าซ้อนหลายชั้น) ไม่ว่า part นั้นจะมี `name` หรือไม่
+    /// ส่วน body ที่ไม่ใช่ `multipart/form-data` ใช้ลำดับของ part (`0`, `1`, ...)
+    pub name: String,
+    pub part_type: PartType,
+    /// headers ทั้งหมดของ part รวมถึง `Content-Disposition`, `Content-Type`,
+    /// `Content-Transfer-Encoding`, `Content-ID` และ `X-*`
+    pub headers: HeaderMap,
+}
+
+impl PartHeaders {
+    /// Parse header block ของ part (ไม่รวม `\r\n\r\n` ที่ปิดท้าย)
+    ///
+    /// ทุกบรรทัดต้องเป็น `Name: value` และต้องมี `Content-Disposition`
+    /// บรรทัดที่ขึ้นต้นด้วย space/tab (obs-fold) จะถูกต่อเข้ากับ header ก่อนหน้า
+    /// header ชื่อซ้ำรวมเป็นค่าเดียวคั่นด้วย `, ` ยกเว้น `Content-Disposition` และ `Content-Type`
+    /// ที่ต้องมีแค่ตัวเดียว
+    pub fn parse(headers: &str) -> Result<Self, MultipartError> {
+        Self::parse_with_path(headers, None)
+    }
+
+    /// Parse header block ของ part ใน `multipart/mixed` ที่ซ้อนอยู่ ชื่อของ part คือ `path`
+    /// และไม่บังคับให้มี `Content-Disposition` (part ของ `multipart/mixed` มักมีแค่ `Content-Type`
+    /// หรือ `Content-Disposition: file; filename="..."`)
+    pub fn parse_nested(headers: &str, path: &str) -> Result<Self, MultipartError> {
+        Self::parse_with_path(headers, Some(path))
+    }
+
+    fn parse_with_path(headers: &str, path: Option<&str>) -> Result<Self, MultipartError> {
+        let mut fields: Vec<String> = Vec::new();
+        for line in headers.lines() {
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with([' ', '\t']) {
+                let Some(previous) = fields.last_mut() else {
+                    return Err(malformed(format!("invalid header line {:?}", line)));
+                };
+                previous.push(' ');
+                previous.push_str(line.trim_matches([' ', '\t']));
+                continue;
+            }
+            fields.push(line.to_string());
+        }
+
+        let mut map = HeaderMap::new();
+        for field in &fields {
+            let Some((name, value)) = field.split_once(':') else {
+                return Err(malformed(format!("invalid header line {:?}", field)));
+            };
+            let name = name.trim_end_matches([' ', '\t']);
+            if name.is_empty() || !name.bytes().all(is_token_char) {
+                return Err(malformed(format!("invalid header name {:?}", name)));
+            }
+            if map.contains(name) && SINGLETON_HEADERS.iter().any(|singleton| name.eq_ignore_ascii_case(singleton)) {
+                return Err(malformed(format!("duplicate {} header", name)));
+            }
+            map.append(name, value.trim_matches([' ', '\t']));
+        }
+
+        let disposition = map.get("content-disposition").map(ContentDisposition::parse).transpose()?;
+        let name = match path {
+            Some(path) => path.to_string(),
+            None => match disposition.as_ref().and_then(|disposition| disposition.name.clone()) {
+                Some(name) => name,
+                None => return Err(malformed("missing Content-Disposition name".to_string())),
+            },
+        };
+
+        // มี `filename` (หรือ `filename*`) แปลว่าเป็นไฟล์ ถึงจะเป็นชื่อว่างก็ตาม
+        let part_type = match disposition.as_ref().and_then(ContentDisposition::resolved_filename) {
+            Some(filename) => PartType::File {
+                filename: filename.to_string(),
+                content_type: map.get("content-type").unwrap_or_default().to_string(),
+            },
+            None => PartType::Field,
+        };
+
+        Ok(Self {
+            name,
+            part_type,
+            headers: map,
+        })
+    }
+
+    /// ค่าของ header ของ part (ชื่อไม่สนตัวพิมพ์)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)
+    }
+
+    /// ค่าของ `Content-Type` ของ part ถ้าส่งมา
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers.get("content-type")
+    }
+
+    /// `Content-ID` ของ part โดยถอด `<>` ออกแล้ว (ใช้กับ `multipart/related`)
+    pub fn content_id(&self) -> Option<&str> {
+        self.headers.get("content-id").map(subtype::strip_angle_brackets)
+    }
+
+    /// `Content-Range` ของ part ใน `multipart/byteranges` (`None` ถ้าไม่ได้ส่งมา)
+    pub fn content_range(&self) -> Option<Result<ContentRange, MultipartError>> {
+        self.headers.get("content-range").map(ContentRange::parse)
+    }
+
+    /// ชื่อไฟล์ ถ้า part นี้เป็นไฟล์
+    pub fn filename(&self) -> Option<&str> {
+        match &self.part_type {
+            PartType::File { filename, .. } => Some(filename),
+            PartType::Field => None,
+        }
+    }
+
+    /// boundary ของ body ที่ซ้อนอยู่ ถ้า part นี้เป็น field ที่มี `Content-Type: multipart/mixed`
+    ///
+    /// part ที่มี `filename` ถือเป็นไฟล์ธรรมดาเสมอ ถึงเนื้อหาจะเป็น multipart ก็ตาม (เช่นไฟล์ `.eml`)
+    /// `Content-Type` ที่ parse ไม่ได้ถือว่าไม่ซ้อน ส่วน `multipart/mixed` ที่ไม่มีหรือมี boundary ผิดเป็น error
+    pub fn nested_boundary(&self) -> Result<Option<String>, MultipartError> {
+        if self.part_type != PartType::Field {
+            return Ok(None);
+        }
+        let Some(media_type) = self.content_type().and_then(|value| MediaType::parse(value).ok()) else {
+            return Ok(None);
+        };
+        if media_type.essence() != "multipart/mixed" {
+            return Ok(None);
+        }
+        Ok(Some(media_type.boundary()?.to_string()))
+    }
+}
+
+/// Event ที่ parser ปล่อยออกมาระหว่าง parse body
+#[derive(Debug, PartialEq)]
+pub enum Event<'a> {
+    /// เริ่ม part ใหม่ พร้อม headers ของ part
+    PartStart { headers: PartHeaders },
+    /// ข้อมูลของ part ปัจจุบัน (อาจมาหลายครั้งต่อ part)
+    PartData(&'a [u8]),
+    /// จบ part ปัจจุบัน
+    PartEnd,
+    /// เจอ closing delimiter (`--boundary--`) แล้ว จะไม่มี event อื่นตามมาอีก
+    Finished,
+}
+
+/// สถานะของ state machine ใน [`StreamingParser`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParserState {
+    /// กำลังข้าม preamble เพื่อหา delimiter แรก
+    SearchingBoundary,
+    /// เจอ delimiter แล้ว กำลังอ่าน headers ของ part
+    ReadingHeaders,
+    /// กำลังอ่าน data ของ part จนกว่าจะเจอ delimiter ถัดไป
+    ReadingData,
+    /// เจอ closing delimiter แล้ว ข้อมูลหลังจากนี้ (epilogue) จะถูกทิ้ง
+    Done,
+}
+
+/// body หนึ่งชั้น: body หลักหรือ `multipart/mixed` ที่ซ้อนอยู่ใน part
+struct Level {
+    /// ค้นหา `CRLF--boundary` ส่วน dash-boundary คือ `needle()[2..]`
+    delimiter: Finder,
+    /// ชื่อ (path) ของ part ที่เป็น container ว่างสำหรับ body หลัก
+    path: String,
+    /// จำนวน part ที่เจอแล้วในชั้นนี้ ใช้เป็น index ของ path ของ part ถัดไป
+    parts: usize,
+}
+
+impl Level {
+    /// `boundary` มี `--` นำหน้าแล้ว
+    fn new(boundary: &str, path: String) -> Self {
+        let mut delimiter = b"\r\n".to_vec();
+        delimiter.extend_from_slice(boundary.as_bytes());
+        Self {
+            delimiter: Finder::new(&delimiter),
+            path,
+            parts: 0,
+        }
+    }
+}
+
+/// ผลของการตรวจ bytes ที่ตามหลัง `--boundary`
+enum DelimiterTail {
+    /// `--` แปลว่าเป็น closing delimiter
+    Close,
+    /// transport padding + CRLF, ค่าคือจำนวน bytes ที่ต้องข้าม
+    Open(usize),
+    /// ข้อมูลยังมาไม่พอจะตัดสิน
+    NeedMore,
+    /// ไม่ใช่ delimiter (เช่น `--boundaryXYZ`)
+    NotDelimiter,
+}
+
+/// Parser ที่รับ body ทีละ chunk แล้วปล่อย [`Event`] ออกมา
+///
+/// เก็บใน memory แค่ข้อมูลที่ยังไม่ได้ปล่อยออกไป ซึ่งส่วนใหญ่คือท้าย chunk
+/// ที่อาจเป็น boundary ที่ยังมาไม่ครบ
+///
+/// การแยก part เป็นไปตาม RFC 2046 section 5.1.1
+/// - delimiter คือ `CRLF--boundary` ยกเว้นตัวแรกที่อยู่ต้น body พอดีไม่ต้องมี CRLF นำหน้า
+///   ดังนั้น `--boundary` ที่อยู่กลางบรรทัด หรือนำหน้าด้วย LF อย่างเดียว ถือเป็นข้อมูลธรรมดา
+/// - หลัง `--boundary` มี space/tab (transport padding) ก่อน CRLF ได้
+/// - ข้อมูลก่อน delimiter แรก (preamble) และหลัง closing delimiter (epilogue) จะถูกทิ้ง
+///
+/// part ที่เป็น field และมี `Content-Type: multipart/mixed; boundary=...` (แบบ RFC 2388) จะถูก parse ต่อเข้าไปข้างใน
+/// โดยไม่ปล่อย event ของตัว container เอง แต่ปล่อย part ข้างในแทนโดยตั้งชื่อเป็น path เช่น `attachments[0]`
+/// ซ้อนได้ลึกสุด [`with_max_nesting_depth`](StreamingParser::with_max_nesting_depth) ชั้น
+///
+/// body แบบ `multipart/mixed`, `related`, `byteranges` ฯลฯ ใช้ [`for_media_type`](StreamingParser::for_media_type)
+/// หรือ [`with_subtype`](StreamingParser::with_subtype) part ของ body เหล่านี้ไม่ต้องมี `Content-Disposition`
+/// และได้ชื่อตามลำดับ (`0`, `1`, ...) ตัวช่วยของแต่ละ subtype อยู่ใน [`subtype`](crate::subtype)
+///
+/// body จะถือว่าครบก็ต่อเมื่อเจอ closing delimiter (`--boundary--`) ถ้า input จบก่อน
+/// [`next_event`](StreamingParser::next_event) จะคืน [`MultipartError::UnexpectedEof`]
+///
+/// ```
+/// use multipart_core::{Event, StreamingParser};
+///
+/// let mut parser = StreamingParser::new("--xyz")?;
+/// parser.feed(b"--xyz\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nhello\r\n--xyz--\r\n");
+/// parser.end_of_input();
+///
+/// let mut data = Vec::new();
+/// while let Some(event) = parser.next_event()? {
+///     match event {
+///         Event::PartStart { headers } => assert_eq!(headers.name, "a"),
+///         Event::PartData(bytes) => data.extend_from_slice(bytes),
+///         Event::PartEnd | Event::Finished => {}
+///     }
+/// }
+/// assert_eq!(data, b"hello");
+/// # Ok::<(), multipart_core::MultipartError>(())
+/// ```
+pub struct StreamingParser {
+    /// body ที่กำลังอ่าน ตัวแรกคือ body หลัก ตัวท้ายคือ `multipart/mixed` ชั้นในสุด
+    levels: Vec<Level>,
+    max_nesting_depth: usize,
+    subtype: Subtype,
+    /// ค้นหา `CRLF CRLF` ที่ปิดท้าย headers ของ part
+    header_end: Finder,
+    /// ขนาดสูงสุดของ header block ของแต่ละ part (ไม่งั้น header ที่ไม่มีวันจบจะกิน memory ไม่สิ้นสุด)
+    max_header_size: usize,
+    /// ข้อมูลที่ได้รับแต่ยังไม่ได้ปล่อยออกไปคือ `buffer[pos..]`
+    buffer: Vec<u8>,
+    pos: usize,
+    state: ParserState,
+    at_body_start: bool,
+    eof: bool,
+    finished: bool,
+}
+
+impl StreamingParser {
+    /// สร้าง parser จาก delimiter (`--` + boundary)
+    ///
+    /// คืน [`MultipartError::MissingBoundary`] ถ้า boundary ว่าง
+    /// (ไม่งั้นจะ match ได้ทุกตำแหน่งใน body)
+    pub fn new(boundary: &str) -> Result<Self, MultipartError> {
+        if boundary.trim_start_matches("--").is_empty() {
+            return Err(MultipartError::MissingBoundary);
+        }
+
+        Ok(Self {
+            levels: vec![Level::new(boundary, String::new())],
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            subtype: Subtype::FormData,
+            header_end: Finder::new(b"\r\n\r\n"),
+            max_header_size: DEFAULT_MAX_PART_HEADER_SIZE,
+            buffer: Vec::new(),
+            pos: 0,
+            state: ParserState::SearchingBoundary,
+            at_body_start: true,
+            eof: false,
+            finished: false,
+        })
+    }
+
+    /// สร้าง parser จาก `Content-Type` ของ body ใช้ boundary และ subtype ของมัน
+    ///
+    /// คืน [`MultipartError::UnsupportedMediaType`] ถ้าไม่ใช่ `multipart/*`
+    pub fn for_media_type(media_type: &MediaType) -> Result<Self, MultipartError> {
+        let Some(subtype) = Subtype::of(media_type) else {
+            return Err(MultipartError::UnsupportedMediaType(media_type.essence()));
+        };
+        Ok(Self::new(&format!("--{}", media_type.boundary()?))?.with_subtype(subtype))
+    }
+
+    /// กำหนด subtype ของ body (default `multipart/form-data`)
+    pub fn with_subtype(mut self, subtype: Subtype) -> Self {
+        self.subtype = subtype;
+        self
+    }
+
+    /// subtype ของ body ที่กำลัง parse
+    pub fn subtype(&self) -> &Subtype {
+        &self.subtype
+    }
+
+    /// กำหนดขนาดสูงสุดของ header block ของแต่ละ part
+    ///
+    /// header ที่ยาวเกินจะทำให้ [`next_event`](Self::next_event) คืน
+    /// [`MultipartError::LimitExceeded`] ทันทีโดยไม่ต้องรอให้ header จบ
+    pub fn with_max_header_size(mut self, max_header_size: usize) -> Self {
+        self.max_header_size = max_header_size;
+        self
+    }
+
+    /// กำหนดจำนวนชั้นของ `multipart/mixed` ที่ซ้อนกันได้ (default 1, 0 = ไม่รับ body ที่ซ้อน)
+    ///
+    /// container ที่ลึกเกินจะทำให้ [`next_event`](Self::next_event) คืน [`MultipartError::LimitExceeded`]
+    pub fn with_max_nesting_depth(mut self, max_nesting_depth: usize) -> Self {
+        self.max_nesting_depth = max_nesting_depth;
+        self
+    }
+
+    /// จำนวนชั้นของ `multipart/mixed` ที่กำลังอยู่ข้างใน (0 = body หลัก)
+    pub fn nesting_depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// สถานะปัจจุบันของ state machine
+    pub fn state(&self) -> ParserState {
+        self.state
+    }
+
+    /// เจอ closing delimiter แล้วหรือยัง (ถ้ายังแล้ว input จบ แปลว่า body ถูกตัด)
+    pub fn is_complete(&self) -> bool {
+        self.state == ParserState::Done
+    }
+
+    /// ป้อน chunk ถัดไปของ body เข้า parser
+    ///
+    /// ควรดึง event ด้วย [`next_event`](Self::next_event) จนได้ `None` ก่อน feed chunk ถัดไป
+    /// เพื่อไม่ให้ข้อมูลค้างใน buffer
+    pub fn feed(&mut self, chunk: &[u8]) {
+        // หลัง closing delimiter เป็น epilogue ไม่ต้องเก็บ
+        if self.state == ParserState::Done {
+            return;
+        }
+
+        // ทิ้งส่วนที่ปล่อยออกไปแล้วก็ต่อเมื่อจำเป็น: ถ้าปล่อยหมดแล้วแค่ reset,
+        // ถ้าส่วนที่ปล่อยไปแล้วยังน้อยกว่าครึ่ง buffer ก็ต่อท้ายไปเลยไม่ต้องย้ายข้อมูล
+        if self.pos == self.buffer.len() {
+            self.buffer.clear();
+            self.pos = 0;
+        } else if self.pos > 0 && self.pos >= self.buffer.len() / 2 {
+            self.buffer.drain(..self.pos);
+            self.pos = 0;
+        }
+        self.buffer.extend_from_slice(chunk);
+    }
+
+    /// บอก parser ว่าไม่มีข้อมูลเข้ามาอีกแล้ว (connection ปิดหรืออ่านครบ Content-Length)
+    pub fn end_of_input(&mut self) {
+        self.eof = true;
+    }
+
+    /// ดึง event ถัดไป คืน `Ok(None)` เมื่อต้องการข้อมูลเพิ่ม (หรือจบไปแล้ว)
+    ///
+    /// หลังคืน error แล้ว parser จะไม่ปล่อย event อื่นอีก
+    pub fn next_event(&mut self) -> Result<Option<Event<'_>>, MultipartError> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        loop {
+            let data = &self.buffer[self.pos..];
+
+            match self.state {
+                ParserState::SearchingBoundary => {
+                    let delimiter = &self.levels[self.levels.len() - 1].delimiter;
+                    let dash_boundary = &delimiter.needle()[2..];
+
+                    // delimiter แรกอยู่ต้น body ได้โดยไม่ต้องมี CRLF นำหน้า
+                    if self.at_body_start {
+                        if data.len() < dash_boundary.len() && dash_boundary.starts_with(data) {
+                            return self.wait_or_eof();
+                        }
+                        self.at_body_start = false;
+                        if data.starts_with(dash_boundary) {
+                            let len = dash_boundary.len();
+                            match delimiter_tail(&data[len..]) {
+                                DelimiterTail::Close => self.close_level(len + 2),
+                                DelimiterTail::Open(skip) => {
+                                    self.pos += len + skip;
+                                    self.state = ParserState::ReadingHeaders;
+                                }
+                                DelimiterTail::NeedMore => {
+                                    self.at_body_start = true;
+                                    return self.wait_or_eof();
+                                }
+                                DelimiterTail::NotDelimiter => {}
+                            }
+                            continue;
+                        }
+                    }
+
+                    let len = delimiter.len();
+                    match delimiter.find(data) {
+                        Some(found) => {
+                            match delimiter_tail(&data[found + len..]) {
+                                DelimiterTail::Close => self.close_level(found + len + 2),
+                                DelimiterTail::Open(skip) => {
+                                    self.pos += found + len + skip;
+                                    self.state = ParserState::ReadingHeaders;
+                                }
+                                DelimiterTail::NeedMore => {
+                                    self.pos += found;
+                                    return self.wait_or_eof();
+                                }
+                                // ไม่ใช่ delimiter จริง ข้ามไปหาตัวถัดไป
+                                DelimiterTail::NotDelimiter => self.pos += found + 1,
+                            }
+                        }
+                        None => {
+                            // ทิ้ง preamble เก็บไว้แค่ท้ายที่อาจเป็น delimiter ที่ยังมาไม่ครบ
+                            self.pos += data.len().saturating_sub(len - 1);
+                            return self.wait_or_eof();
+                        }
+                    }
+                }
+
+                ParserState::ReadingHeaders => {
+                    // part ที่ไม่มี header เลยจะขึ้นต้นด้วย CRLF ทันที
+                    let (header_end, skip) = if data.starts_with(b"\r\n") {
+                        (0, 2)
+                    } else if let Some(header_end) = self.header_end.find(data) {
+                        (header_end, 4)
+                    } else if data.len() > self.max_header_size + 3 {
+                        // ยังไม่เจอ CRLF CRLF แต่ยาวเกิน limit แล้ว ไม่ต้องรอให้จบ
+                        return self.header_too_large();
+                    } else {
+                        return self.wait_or_eof();
+                    };
+                    if header_end > self.max_header_size {
+                        return self.header_too_large();
+                    }
+
+                    let text = String::from_utf8_lossy(&data[..header_end]);
+                    let headers = if self.levels.len() == 1 && self.subtype == Subtype::FormData {
+                        PartHeaders::parse(&text)
+                    } else {
+                        let level = self.levels.last_mut().expect("body level");
+                        let path = match level.path.as_str() {
+                            "" => level.parts.to_string(),
+                            parent => format!("{}[{}]", parent, level.parts),
+                        };
+                        level.parts += 1;
+                        PartHeaders::parse_nested(&text, &path)
+                    };
+                    let headers = headers.inspect_err(|_| self.finished = true)?;
+                    let nested = headers.nested_boundary().inspect_err(|_| self.finished = true)?;
+                    self.pos += header_end + skip;
+
+                    if let Some(boundary) = nested {
+                        // container ของ multipart/mixed: อ่าน part ข้างในแทนการปล่อย data ของมัน
+                        if self.levels.len() > self.max_nesting_depth {
+                            self.finished = true;
+                            return Err(MultipartError::LimitExceeded {
+                                limit: "nesting depth",
+                                max: self.max_nesting_depth,
+                            });
+                        }
+                        self.levels.push(Level::new(&format!("--{}", boundary), headers.name));
+                        self.state = ParserState::SearchingBoundary;
+                        self.at_body_start = true;
+                        continue;
+                    }
+                    self.state = ParserState::ReadingData;
+
+                    return Ok(Some(Event::PartStart { headers }));
+                }
+
+                ParserState::ReadingData => {
+                    let delimiter = &self.levels[self.levels.len() - 1].delimiter;
+                    let len = delimiter.len();
+
+                    // หา CRLF--boundary ถัดไป
+                    if let Some(found) = delimiter.find(data) {
+                        if found > 0 {
+                            // ปล่อยข้อมูลก่อน delimiter ก่อน รอบถัดไปจะเจอ delimiter ที่ต้น buffer
+                            let start = self.pos;
+                            self.pos += found;
+                            return Ok(Some(Event::PartData(&self.buffer[start..self.pos])));
+                        }
+
+                        match delimiter_tail(&data[len..]) {
+                            DelimiterTail::Close => {
+                                self.close_level(len + 2);
+                                return Ok(Some(Event::PartEnd));
+                            }
+                            DelimiterTail::Open(skip) => {
+                                self.pos += len + skip;
+                                self.state = ParserState::ReadingHeaders;
+                                return Ok(Some(Event::PartEnd));
+                            }
+                            DelimiterTail::NeedMore => return self.wait_or_eof(),
+                            DelimiterTail::NotDelimiter => {
+                                // เป็นข้อมูลที่บังเอิญหน้าตาเหมือน delimiter ปล่อยออกไป 1 byte แล้วหาต่อ
+                                let start = self.pos;
+                                self.pos += 1;
+                                return Ok(Some(Event::PartData(&self.buffer[start..self.pos])));
+                            }
+                        }
+                    }
+
+                    if self.eof {
+                        // body จบกลาง part โดยไม่เจอ delimiter ปิด
+                        return self.wait_or_eof();
+                    }
+
+                    // ยังไม่เจอ delimiter ถัดไป, ปล่อยข้อมูลที่มี
+                    // (ยกเว้นท้าย buffer ที่อาจเป็น delimiter ที่ยังมาไม่ครบ)
+                    let safe_len = data.len().saturating_sub(len - 1);
+                    if safe_len == 0 {
+                        return Ok(None);
+                    }
+                    let start = self.pos;
+                    self.pos += safe_len;
+                    return Ok(Some(Event::PartData(&self.buffer[start..self.pos])));
+                }
+
+                ParserState::Done => {
+                    // closing delimiter: ทิ้ง epilogue ทั้งหมด
+                    self.buffer.clear();
+                    self.pos = 0;
+                    self.finished = true;
+                    return Ok(Some(Event::Finished));
+                }
+            }
+        }
+    }
+
+    /// เจอ closing delimiter ของ body ชั้นในสุด `consumed` คือจำนวน bytes ถึงท้าย `--`
+    fn close_level(&mut self, consumed: usize) {
+        if self.levels.len() == 1 {
+            self.state = ParserState::Done;
+            return;
+        }
+        // จบ multipart/mixed ที่ซ้อนอยู่: ข้าม epilogue ของมันไปหา delimiter ถัดไปของชั้นนอก
+        self.pos += consumed;
+        self.levels.pop();
+        self.state = ParserState::SearchingBoundary;
+    }
+
+    fn header_too_large(&mut self) -> Result<Option<Event<'_>>, MultipartError> {
+        self.finished = true;
+        Err(MultipartError::LimitExceeded {
+            limit: "part header size",
+            max: self.max_header_size,
+        })
+    }
+
+    /// รอข้อมูลเพิ่ม หรือถ้า input จบแล้วแปลว่า body ถูกตัดก่อน closing delimiter
+    fn wait_or_eof(&mut self) -> Result<Option<Event<'_>>, MultipartError> {
+        if self.eof {
+            self.finished = true;
+            Err(MultipartError::UnexpectedEof)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// ตรวจ bytes ที่ตามหลัง `--boundary` ว่าเป็น delimiter แบบไหน
+fn delimiter_tail(rest: &[u8]) -> DelimiterTail {
+    if rest.starts_with(b"--") {
+        return DelimiterTail::Close;
+    }
+    if rest == b"-" {
+        return DelimiterTail::NeedMore;
+    }
+
+    // transport padding (space/tab) ก่อน CRLF
+    let padding = rest.iter().take_while(|&&b| b == b' ' || b == b'\t').count();
+    match &rest[padding..] {
+        [] | [b'\r'] => DelimiterTail::NeedMore,
+        [b'\r', b'\n', ..] => DelimiterTail::Open(padding + 2),
+        _ => DelimiterTail::NotDelimiter,
+    }
+}
+
+fn malformed(reason: String) -> MultipartError {
+    MultipartError::MalformedPartHeaders(reason)
+}
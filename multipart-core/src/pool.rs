@@ -0,0 +1,130 @@
+//! Thread pool แบบจำกัดจำนวน สำหรับ server แบบ sync
+//!
+//! มี worker จำนวนคงที่และคิวรอที่จำกัดความยาว ถ้าคิวเต็ม [`ThreadPool::try_submit`]
+//! จะคืนงานกลับมาให้ผู้เรียกตอบ `503 Service Unavailable` เองแทนที่จะรอ
+
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError, sync_channel};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// จำนวน worker default
+pub const DEFAULT_WORKERS: usize = 4;
+/// ความยาวคิวรอ default
+pub const DEFAULT_QUEUE_DEPTH: usize = 16;
+
+/// ค่าตั้งของ pool
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolConfig {
+    /// จำนวน worker thread
+    pub workers: usize,
+    /// จำนวนงานที่รอในคิวได้ (ไม่นับงานที่ worker กำลังทำ)
+    pub queue_depth: usize,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            workers: DEFAULT_WORKERS,
+            queue_depth: DEFAULT_QUEUE_DEPTH,
+        }
+    }
+}
+
+impl PoolConfig {
+    /// อ่านค่าจาก env `UPLOAD_WORKERS` และ `UPLOAD_QUEUE_DEPTH` ถ้าไม่มีหรือผิดใช้ค่า default
+    pub fn from_env() -> Self {
+        let read = |name: &str, default: usize| {
+            env::var(name)
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            workers: read("UPLOAD_WORKERS", DEFAULT_WORKERS).max(1),
+            queue_depth: read("UPLOAD_QUEUE_DEPTH", DEFAULT_QUEUE_DEPTH),
+        }
+    }
+}
+
+/// Pool ของ worker ที่เรียก handler เดียวกันกับทุกงาน (เช่น `TcpStream` แต่ละ connection)
+///
+/// handler ได้รับหมายเลข worker (เริ่มที่ 1) ไว้ใช้ log ว่า worker ไหนจัดการงานไหน
+pub struct ThreadPool<T: Send + 'static> {
+    sender: Option<SyncSender<T>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl<T: Send + 'static> ThreadPool<T> {
+    /// สร้าง pool และ spawn worker ทั้งหมดทันที
+    pub fn new<F>(config: PoolConfig, handler: F) -> Self
+    where
+        F: Fn(T, usize) + Send + Sync + 'static,
+    {
+        let (sender, receiver) = sync_channel(config.queue_depth);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let handler = Arc::new(handler);
+
+        let workers = (1..=config.workers.max(1))
+            .map(|id| {
+                let receiver = Arc::clone(&receiver);
+                let handler = Arc::clone(&handler);
+                thread::Builder::new()
+                    .name(format!("upload-worker-{}", id))
+                    .spawn(move || worker_loop(id, &receiver, &*handler))
+                    .expect("Cannot spawn worker thread")
+            })
+            .collect();
+
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// จำนวน worker ใน pool
+    pub fn size(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// ส่งงานเข้าคิว ถ้าทุก worker ไม่ว่างและคิวเต็มจะคืนงานกลับมาเป็น `Err`
+    pub fn try_submit(&self, job: T) -> Result<(), T> {
+        let sender = self.sender.as_ref().expect("pool is shutting down");
+        match sender.try_send(job) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(job)) | Err(TrySendError::Disconnected(job)) => Err(job),
+        }
+    }
+}
+
+impl<T: Send + 'static> Drop for ThreadPool<T> {
+    fn drop(&mut self) {
+        // ปิด channel ให้ worker ออกจาก loop แล้วรอให้งานที่ค้างอยู่เสร็จ
+        self.sender.take();
+        for worker in self.workers.drain(..) {
+            worker.join().ok();
+        }
+    }
+}
+
+fn worker_loop<T>(id: usize, receiver: &Mutex<Receiver<T>>, handler: &dyn Fn(T, usize)) {
+    loop {
+        // ถือ lock แค่ตอนรับงาน ไม่ถือไว้ระหว่างทำงาน
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+
+        match job {
+            // handler panic ไม่ควรทำให้ pool เสีย worker ไปหนึ่งตัว
+            Ok(job) => {
+                if panic::catch_unwind(AssertUnwindSafe(|| handler(job, id))).is_err() {
+                    eprintln!("❌ worker #{} panicked while handling a job", id);
+                }
+            }
+            Err(_) => return,
+        }
+    }
+}
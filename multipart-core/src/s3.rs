@@ -0,0 +1,685 @@
+//! [`StorageBackend`] ที่ส่งไฟล์ขึ้น object storage ผ่าน S3 API (AWS S3, MinIO ฯลฯ)
+//!
+//! คุย HTTP/1.1 ผ่าน `TcpStream` ตรงๆ และเซ็น request ด้วย AWS Signature Version 4
+//! (HMAC-SHA256 จาก [`digest`](crate::digest)) จึงไม่ต้องพึ่ง crate ภายนอก
+//! รองรับแค่ endpoint แบบ `http://` ถ้าต้องใช้ TLS ให้ผ่าน proxy ที่ terminate TLS
+//!
+//! ข้อมูลของ part ถูก buffer ใน memory ทีละ [`S3Config::part_size`] bytes
+//!
+//! - ไฟล์ที่เล็กกว่า part เดียว: `PutObject` ไปที่ชื่อจริงตอน commit
+//! - ไฟล์ใหญ่: multipart upload ไปที่ key ชั่วคราว แล้วตอน commit ค่อย `CopyObject`
+//!   ไปชื่อจริงและลบ key ชั่วคราว (เหมือน rename ของ [`DiskStorage`](crate::storage::DiskStorage))
+//! - ไฟล์ที่ใหญ่เกิน [`S3Config::max_copy_size`] (`CopyObject` รับได้ไม่เกิน 5GB): copy ไปชื่อจริง
+//!   ทีละช่วงด้วย multipart upload + `UploadPartCopy`
+//!
+//! ชื่อที่ต้องไม่เขียนทับใช้ `If-None-Match: *` ถ้า server ตอบ `412` จะลองชื่อที่เติม suffix ถัดไป
+//! (ไม่เกิน [`MAX_NAME_ATTEMPTS`] ชื่อ เท่ากับ [`DiskStorage`](crate::storage::DiskStorage))
+
+use std::env;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::chunked::{ChunkedDecoder, ChunkedEvent};
+use crate::digest::{Sha256, hmac_sha256, to_hex};
+use crate::error::MultipartError;
+use crate::filename;
+use crate::http::HeaderMap;
+use crate::storage::{self, MAX_NAME_ATTEMPTS, NewFile, PartSink, StorageBackend, StoredFile};
+
+/// ขนาด part default ของ multipart upload (S3 กำหนดขั้นต่ำ 5MB ยกเว้น part สุดท้าย)
+pub const DEFAULT_PART_SIZE: usize = 8 * 1024 * 1024;
+/// ขนาดสูงสุดที่ `CopyObject` copy ได้ในครั้งเดียว (5GB) และขนาดสูงสุดของแต่ละ part ของ `UploadPartCopy`
+pub const DEFAULT_MAX_COPY_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+/// เวลาที่รอ connect/อ่าน/เขียนกับ S3 endpoint
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// ค่าที่ใช้เชื่อมต่อ bucket
+#[derive(Clone)]
+pub struct S3Config {
+    /// เช่น `http://127.0.0.1:9000` (ใช้ path-style: `/{bucket}/{key}`)
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub access_key: String,
+    pub secret_key: String,
+    /// ต่อหน้าชื่อไฟล์เป็น key เช่น `uploads/`
+    pub prefix: String,
+    /// ขนาดของแต่ละ part ใน multipart upload
+    pub part_size: usize,
+    /// ไฟล์ที่ใหญ่กว่านี้ copy ไปชื่อจริงทีละช่วงขนาดนี้แทน `CopyObject` ครั้งเดียว
+    pub max_copy_size: u64,
+}
+
+impl fmt::Debug for S3Config {
+    /// ไม่แสดง secret key ใน log
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3Config")
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .field("bucket", &self.bucket)
+            .field("access_key", &self.access_key)
+            .field("prefix", &self.prefix)
+            .field("part_size", &self.part_size)
+            .field("max_copy_size", &self.max_copy_size)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Config {
+    pub fn new(endpoint: &str, bucket: &str, access_key: &str, secret_key: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            region: "us-east-1".to_string(),
+            bucket: bucket.to_string(),
+            access_key: access_key.to_string(),
+            secret_key: secret_key.to_string(),
+            prefix: String::new(),
+            part_size: DEFAULT_PART_SIZE,
+            max_copy_size: DEFAULT_MAX_COPY_SIZE,
+        }
+    }
+
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = region.to_string();
+        self
+    }
+
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    pub fn with_part_size(mut self, part_size: usize) -> Self {
+        self.part_size = part_size.max(1);
+        self
+    }
+
+    /// ขนาดที่ copy ได้ในครั้งเดียว (default 5GB ตามที่ AWS กำหนด)
+    pub fn with_max_copy_size(mut self, max_copy_size: u64) -> Self {
+        self.max_copy_size = max_copy_size.max(1);
+        self
+    }
+
+    /// อ่านจาก env `S3_ENDPOINT`, `S3_BUCKET`, `S3_ACCESS_KEY`, `S3_SECRET_KEY` (ต้องมีครบ)
+    /// และ `S3_REGION`, `S3_PREFIX`, `S3_PART_SIZE` (ไม่บังคับ) คืน `None` ถ้าไม่ได้ตั้งไว้
+    pub fn from_env() -> Option<Self> {
+        let read = |name: &str| env::var(name).ok().filter(|value| !value.trim().is_empty());
+
+        let mut config = Self::new(
+            &read("S3_ENDPOINT")?,
+            &read("S3_BUCKET")?,
+            &read("S3_ACCESS_KEY")?,
+            &read("S3_SECRET_KEY")?,
+        );
+        if let Some(region) = read("S3_REGION") {
+            config = config.with_region(&region);
+        }
+        if let Some(prefix) = read("S3_PREFIX") {
+            config = config.with_prefix(&prefix);
+        }
+        if let Some(part_size) = read("S3_PART_SIZE").and_then(|value| value.trim().parse().ok()) {
+            config = config.with_part_size(part_size);
+        }
+        Some(config)
+    }
+}
+
+/// backend ที่เก็บไฟล์ใน bucket ของ S3-compatible object storage
+#[derive(Debug, Clone)]
+pub struct S3Storage {
+    config: Arc<S3Config>,
+    /// `host:port` ของ endpoint
+    host: String,
+}
+
+/// คำตอบจาก S3 endpoint
+struct Response {
+    status: u16,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl S3Storage {
+    pub fn new(config: S3Config) -> Result<Self, MultipartError> {
+        let Some(rest) = config.endpoint.strip_prefix("http://") else {
+            return Err(MultipartError::Storage(format!(
+                "unsupported endpoint {:?}, expected http://host[:port]",
+                config.endpoint
+            )));
+        };
+        let host = rest.trim_end_matches('/');
+        if host.is_empty() || host.contains('/') {
+            return Err(MultipartError::Storage(format!("invalid endpoint {:?}", config.endpoint)));
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            config: Arc::new(config),
+        })
+    }
+
+    pub fn config(&self) -> &S3Config {
+        &self.config
+    }
+
+    fn key_of(&self, name: &str) -> String {
+        format!("{}{}", self.config.prefix, name)
+    }
+
+    /// ส่ง request ที่เซ็นแล้วหนึ่งครั้ง (connection ละ request)
+    fn send(
+        &self,
+        method: &str,
+        key: &str,
+        query: &[(&str, &str)],
+        extra_headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> Result<Response, MultipartError> {
+        let path = format!("/{}/{}", self.config.bucket, key);
+        let amz_date = amz_date(SystemTime::now());
+        let payload_hash = to_hex(&Sha256::digest(body));
+
+        let mut headers = vec![
+            ("host", self.host.as_str()),
+            ("x-amz-content-sha256", payload_hash.as_str()),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+        headers.extend_from_slice(extra_headers);
+        let authorization = authorization(&self.config, method, &path, query, &headers, &amz_date);
+
+        let mut target = uri_encode(&path, false);
+        for (i, (name, value)) in query.iter().enumerate() {
+            target.push(if i == 0 { '?' } else { '&' });
+            target.push_str(&uri_encode(name, true));
+            if !value.is_empty() {
+                target.push('=');
+                target.push_str(&uri_encode(value, true));
+            }
+        }
+
+        let mut request = format!("{} {} HTTP/1.1\r\n", method, target);
+        for (name, value) in &headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        request.push_str(&format!(
+            "Authorization: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            authorization,
+            body.len()
+        ));
+
+        // ต่อ endpoint ไม่ได้เป็นปัญหาของ upstream (502) ไม่ใช่ของ server เอง
+        let exchange = || -> io::Result<Vec<u8>> {
+            let mut stream = connect(&self.host)?;
+            stream.set_read_timeout(Some(IO_TIMEOUT))?;
+            stream.set_write_timeout(Some(IO_TIMEOUT))?;
+            stream.write_all(request.as_bytes())?;
+            stream.write_all(body)?;
+
+            let mut raw = Vec::new();
+            stream.read_to_end(&mut raw)?;
+            Ok(raw)
+        };
+        let raw = exchange().map_err(|e| MultipartError::Storage(format!("{} {}: {}", method, self.host, e)))?;
+        parse_response(&raw)
+    }
+
+    /// ส่ง request แล้วคืน error ถ้า status ไม่ใช่ 2xx หรือ body เป็น `<Error>`
+    /// (`CompleteMultipartUpload` และ `CopyObject` ตอบ 200 พร้อม error ได้)
+    fn send_ok(
+        &self,
+        method: &str,
+        key: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> Result<Response, MultipartError> {
+        let response = self.send(method, key, query, headers, body)?;
+        check_response(method, key, response)
+    }
+
+    /// `PutObject` หรือ `CopyObject` (ถ้ามี `x-amz-copy-source` ใน `headers`) ไปที่ `name`
+    /// ถ้า `no_clobber` และชื่อถูกใช้แล้ว (412) ลองชื่อที่เติม suffix ถัดไป
+    fn put_no_clobber(
+        &self,
+        name: &str,
+        no_clobber: bool,
+        headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> Result<StoredFile, MultipartError> {
+        let mut headers = headers.to_vec();
+        if no_clobber {
+            headers.push(("if-none-match", "*"));
+        }
+        self.store_no_clobber(name, |key| {
+            let response = self.send("PUT", key, &[], &headers, body)?;
+            if no_clobber && response.status == 412 {
+                return Ok(false);
+            }
+            check_response("PUT", key, response)?;
+            Ok(true)
+        })
+    }
+
+    /// เปิด multipart upload ที่ `key` คืน upload id
+    fn create_multipart_upload(&self, key: &str, content_type: &str) -> Result<String, MultipartError> {
+        let response = self.send_ok("POST", key, &[("uploads", "")], &[("content-type", content_type)], &[])?;
+        let body = String::from_utf8_lossy(&response.body);
+        xml_value(&body, "UploadId")
+            .map(str::to_string)
+            .ok_or_else(|| MultipartError::Storage("CreateMultipartUpload without UploadId".to_string()))
+    }
+
+    /// `CompleteMultipartUpload` จาก ETag ของทุก part ตามลำดับ (ผู้เรียกตรวจ status เอง)
+    fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+        headers: &[(&str, &str)],
+    ) -> Result<Response, MultipartError> {
+        let mut body = String::from("<CompleteMultipartUpload>");
+        for (i, etag) in etags.iter().enumerate() {
+            body.push_str(&format!("<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>", i + 1, etag));
+        }
+        body.push_str("</CompleteMultipartUpload>");
+
+        let mut headers = headers.to_vec();
+        headers.push(("content-type", "application/xml"));
+        self.send("POST", key, &[("uploadId", upload_id)], &headers, body.as_bytes())
+    }
+
+    /// copy object `copy_source` (encode แล้ว) ขนาด `size` ไปที่ `key` ทีละ [`S3Config::max_copy_size`] bytes
+    /// ด้วย `UploadPartCopy` คืน `Ok(false)` ถ้า `no_clobber` และ `key` มีอยู่แล้ว
+    fn copy_in_parts(
+        &self,
+        copy_source: &str,
+        size: u64,
+        key: &str,
+        content_type: &str,
+        no_clobber: bool,
+    ) -> Result<bool, MultipartError> {
+        let upload_id = self.create_multipart_upload(key, content_type)?;
+        let copy = || -> Result<bool, MultipartError> {
+            let mut etags = Vec::new();
+            let mut first = 0;
+            while first < size {
+                let last = size.min(first + self.config.max_copy_size) - 1;
+                let range = format!("bytes={}-{}", first, last);
+                let part_number = (etags.len() + 1).to_string();
+                let response = self.send_ok(
+                    "PUT",
+                    key,
+                    &[("partNumber", &part_number), ("uploadId", &upload_id)],
+                    &[("x-amz-copy-source", copy_source), ("x-amz-copy-source-range", &range)],
+                    &[],
+                )?;
+                let body = String::from_utf8_lossy(&response.body);
+                let etag = xml_value(&body, "ETag")
+                    .ok_or_else(|| MultipartError::Storage(format!("UploadPartCopy {} without ETag", part_number)))?;
+                etags.push(etag.to_string());
+                first = last + 1;
+            }
+
+            // ชื่อจริงถูกจองตอน complete เท่านั้น จึงใช้ If-None-Match ตรงนี้แทนตอน PutObject
+            let headers: &[(&str, &str)] = if no_clobber { &[("if-none-match", "*")] } else { &[] };
+            let response = self.complete_multipart_upload(key, &upload_id, &etags, headers)?;
+            if no_clobber && response.status == 412 {
+                return Ok(false);
+            }
+            check_response("POST", key, response)?;
+            Ok(true)
+        };
+
+        let stored = copy();
+        if !matches!(stored, Ok(true)) {
+            let _ = self.send("DELETE", key, &[("uploadId", &upload_id)], &[], &[]);
+        }
+        stored
+    }
+
+    /// เรียก `store` กับ key ของ `name` แล้วของชื่อที่เติม suffix ไปเรื่อยๆ จนกว่าจะคืน `Ok(true)`
+    /// (`Ok(false)` = ชื่อนั้นถูกใช้แล้ว) ลองไม่เกิน [`MAX_NAME_ATTEMPTS`] ชื่อ
+    fn store_no_clobber(
+        &self,
+        name: &str,
+        mut store: impl FnMut(&str) -> Result<bool, MultipartError>,
+    ) -> Result<StoredFile, MultipartError> {
+        for n in 0..MAX_NAME_ATTEMPTS {
+            let stored_name = match n {
+                0 => name.to_string(),
+                n => filename::with_suffix(name, n),
+            };
+            let key = self.key_of(&stored_name);
+            if store(&key)? {
+                return Ok(StoredFile {
+                    location: format!("s3://{}/{}", self.config.bucket, key),
+                    name: stored_name,
+                });
+            }
+        }
+        Err(storage::no_free_name(name))
+    }
+}
+
+impl StorageBackend for S3Storage {
+    fn create(&self, file: &NewFile<'_>) -> Result<Box<dyn PartSink>, MultipartError> {
+        let content_type = match file.content_type {
+            "" => "application/octet-stream",
+            content_type => content_type,
+        };
+
+        Ok(Box::new(S3PartSink {
+            storage: self.clone(),
+            temp_key: self.key_of(&storage::temp_file_name()),
+            content_type: content_type.to_string(),
+            buffer: Vec::new(),
+            size: 0,
+            upload_id: None,
+            etags: Vec::new(),
+            temp_object: false,
+        }))
+    }
+}
+
+/// part ที่กำลังส่งขึ้น S3 ถ้า abort หรือ commit ไม่สำเร็จจะยกเลิก multipart upload และลบ key ชั่วคราว
+struct S3PartSink {
+    storage: S3Storage,
+    temp_key: String,
+    content_type: String,
+    /// ข้อมูลที่ยังไม่ครบหนึ่ง part
+    buffer: Vec<u8>,
+    /// bytes ทั้งหมดที่เขียนมา
+    size: u64,
+    /// id ของ multipart upload ที่เปิดไว้ที่ `temp_key`
+    upload_id: Option<String>,
+    /// ETag ของ part ที่ส่งแล้ว ตามลำดับ part number
+    etags: Vec<String>,
+    /// multipart upload complete แล้ว มี object ที่ `temp_key` ที่ต้องลบ
+    temp_object: bool,
+}
+
+impl S3PartSink {
+    fn upload_part(&mut self, data: &[u8]) -> Result<(), MultipartError> {
+        let storage = self.storage.clone();
+        let upload_id = match &self.upload_id {
+            Some(upload_id) => upload_id.clone(),
+            None => {
+                let upload_id = storage.create_multipart_upload(&self.temp_key, &self.content_type)?;
+                self.upload_id = Some(upload_id.clone());
+                upload_id
+            }
+        };
+
+        let part_number = (self.etags.len() + 1).to_string();
+        let response = storage.send_ok(
+            "PUT",
+            &self.temp_key,
+            &[("partNumber", &part_number), ("uploadId", &upload_id)],
+            &[],
+            data,
+        )?;
+        let etag = response
+            .headers
+            .get("etag")
+            .ok_or_else(|| MultipartError::Storage(format!("UploadPart {} without ETag", part_number)))?;
+        self.etags.push(etag.to_string());
+        Ok(())
+    }
+
+    fn complete(&mut self) -> Result<(), MultipartError> {
+        let Some(upload_id) = self.upload_id.clone() else {
+            return Ok(());
+        };
+        let response = self.storage.complete_multipart_upload(&self.temp_key, &upload_id, &self.etags, &[])?;
+        check_response("POST", &self.temp_key, response)?;
+        self.upload_id = None;
+        self.temp_object = true;
+        Ok(())
+    }
+
+    fn commit_to(&mut self, name: &str, no_clobber: bool) -> Result<StoredFile, MultipartError> {
+        let storage = self.storage.clone();
+        if self.upload_id.is_none() {
+            let buffer = std::mem::take(&mut self.buffer);
+            return storage.put_no_clobber(name, no_clobber, &[("content-type", &self.content_type)], &buffer);
+        }
+
+        if !self.buffer.is_empty() {
+            let rest = std::mem::take(&mut self.buffer);
+            self.upload_part(&rest)?;
+        }
+        self.complete()?;
+
+        let copy_source = uri_encode(&format!("/{}/{}", storage.config.bucket, self.temp_key), false);
+        if self.size <= storage.config.max_copy_size {
+            return storage.put_no_clobber(name, no_clobber, &[("x-amz-copy-source", &copy_source)], &[]);
+        }
+        storage.store_no_clobber(name, |key| {
+            storage.copy_in_parts(&copy_source, self.size, key, &self.content_type, no_clobber)
+        })
+    }
+
+    /// ยกเลิก multipart upload และลบ key ชั่วคราวที่ค้างอยู่ (พลาดก็ปล่อยให้ lifecycle rule ของ bucket เก็บ)
+    fn cleanup(&mut self) {
+        if let Some(upload_id) = self.upload_id.take() {
+            let _ = self.storage.send("DELETE", &self.temp_key, &[("uploadId", &upload_id)], &[], &[]);
+        }
+        if std::mem::take(&mut self.temp_object) {
+            let _ = self.storage.send("DELETE", &self.temp_key, &[], &[], &[]);
+        }
+    }
+}
+
+impl PartSink for S3PartSink {
+    fn write(&mut self, data: &[u8]) -> Result<(), MultipartError> {
+        self.buffer.extend_from_slice(data);
+        self.size += data.len() as u64;
+        let part_size = self.storage.config.part_size;
+        while self.buffer.len() >= part_size {
+            let part: Vec<u8> = self.buffer.drain(..part_size).collect();
+            self.upload_part(&part)?;
+        }
+        Ok(())
+    }
+
+    fn commit(mut self: Box<Self>, name: &str, no_clobber: bool) -> Result<StoredFile, MultipartError> {
+        let stored = self.commit_to(name, no_clobber);
+        // สำเร็จแล้วไฟล์อยู่ในชื่อจริง ไม่สำเร็จก็ไม่ต้องเก็บไว้ ลบ key ชั่วคราวไม่ได้ก็ไม่ต้องล้มทั้ง part
+        self.cleanup();
+        stored
+    }
+
+    fn abort(mut self: Box<Self>) {
+        self.cleanup();
+    }
+}
+
+impl Drop for S3PartSink {
+    /// กันไว้เผื่อ sink ถูกทิ้งโดยไม่ผ่าน commit หรือ abort (ปกติเก็บกวาดไปแล้ว จึงไม่มี request)
+    fn drop(&mut self) {
+        self.cleanup();
+    }
+}
+
+/// ต่อ `host` (`host:port`) โดยลองทุก address ที่ resolve ได้ ไม่รอแต่ละ address นานเกิน [`IO_TIMEOUT`]
+fn connect(host: &str) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for addr in host.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, IO_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| io::Error::new(io::ErrorKind::NotFound, "host has no address")))
+}
+
+fn check_response(method: &str, key: &str, response: Response) -> Result<Response, MultipartError> {
+    let body = String::from_utf8_lossy(&response.body);
+    if (200..300).contains(&response.status) && !body.contains("<Error>") {
+        return Ok(response);
+    }
+    let code = xml_value(&body, "Code").unwrap_or_default();
+    Err(MultipartError::Storage(format!(
+        "{} {} returned {} {}",
+        method, key, response.status, code
+    )))
+}
+
+/// parse คำตอบ HTTP/1.1 ทั้งก้อน (อ่านจนปิด connection แล้ว)
+fn parse_response(raw: &[u8]) -> Result<Response, MultipartError> {
+    let invalid = |reason: &str| MultipartError::Storage(format!("invalid response: {}", reason));
+
+    let head_end = raw
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| invalid("no end of headers"))?;
+    let head = std::str::from_utf8(&raw[..head_end]).map_err(|_| invalid("head is not UTF-8"))?;
+    let mut lines = head.split("\r\n");
+
+    let status = lines
+        .next()
+        .and_then(|line| line.split(' ').nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| invalid("bad status line"))?;
+    let mut headers = HeaderMap::new();
+    for line in lines {
+        let (name, value) = line.split_once(':').ok_or_else(|| invalid("header line without ':'"))?;
+        headers.append(name.trim(), value.trim());
+    }
+
+    let mut body = raw[head_end + 4..].to_vec();
+    if headers
+        .get("transfer-encoding")
+        .is_some_and(|value| value.eq_ignore_ascii_case("chunked"))
+    {
+        let mut decoder = ChunkedDecoder::new();
+        decoder.feed(&body);
+        decoder.end_of_input();
+        body.clear();
+        while let Some(event) = decoder.next_event()? {
+            if let ChunkedEvent::Data(data) = event {
+                body.extend_from_slice(data);
+            }
+        }
+    } else if let Some(len) = headers.get("content-length").and_then(|value| value.parse().ok()) {
+        body.truncate(len);
+    }
+
+    Ok(Response { status, headers, body })
+}
+
+/// ค่าใน `<tag>...</tag>` ตัวแรกของ XML ที่ S3 ตอบมา
+fn xml_value<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = xml[start..].find(&format!("</{}>", tag))?;
+    Some(&xml[start..start + end])
+}
+
+/// ค่า `Authorization` ของ AWS Signature Version 4 สำหรับ service `s3`
+///
+/// `path` และ `query` ยังไม่ encode ส่วน `headers` ต้องเป็นทุก header ที่จะเซ็น
+/// (อย่างน้อย `host`, `x-amz-date` และ `x-amz-content-sha256` ที่เป็น hash ของ payload)
+pub fn authorization(
+    config: &S3Config,
+    method: &str,
+    path: &str,
+    query: &[(&str, &str)],
+    headers: &[(&str, &str)],
+    amz_date: &str,
+) -> String {
+    let mut headers: Vec<(String, String)> = headers
+        .iter()
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    headers.sort();
+    let signed_headers = headers.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(";");
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+
+    let mut query: Vec<(String, String)> = query
+        .iter()
+        .map(|(name, value)| (uri_encode(name, true), uri_encode(value, true)))
+        .collect();
+    query.sort();
+    let canonical_query = query
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let payload_hash = headers
+        .iter()
+        .find(|(name, _)| name == "x-amz-content-sha256")
+        .map_or("UNSIGNED-PAYLOAD", |(_, value)| value.as_str());
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        method,
+        uri_encode(path, false),
+        canonical_query,
+        canonical_headers,
+        signed_headers,
+        payload_hash
+    );
+
+    let date = &amz_date[..8.min(amz_date.len())];
+    let scope = format!("{}/{}/s3/aws4_request", date, config.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        to_hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let mut key = hmac_sha256(format!("AWS4{}", config.secret_key).as_bytes(), date.as_bytes());
+    for part in [config.region.as_str(), "s3", "aws4_request"] {
+        key = hmac_sha256(&key, part.as_bytes());
+    }
+    let signature = to_hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        config.access_key, scope, signed_headers, signature
+    )
+}
+
+/// percent-encode ตามกฎของ SigV4: เว้นแค่ `A-Z a-z 0-9 - _ . ~` (และ `/` ถ้า `encode_slash` เป็น false)
+fn uri_encode(value: &str, encode_slash: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') || (byte == b'/' && !encode_slash) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// เวลาในรูปแบบ `x-amz-date` (`YYYYMMDD'T'HHMMSS'Z'`, UTC)
+pub fn amz_date(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let (days, rem) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // days → วันที่แบบ proleptic Gregorian (algorithm civil_from_days ของ Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
@@ -0,0 +1,127 @@
+//! การค้นหา pattern ใน bytes แบบ Boyer–Moore–Horspool
+//!
+//! ตาราง shift ถูกคำนวณครั้งเดียวตอนสร้าง [`Finder`] แล้วใช้ซ้ำได้ทุก chunk
+//! ทำให้ข้ามข้อมูลได้ทีละเกือบเท่าความยาว pattern แทนที่จะเทียบทุก offset
+
+/// ตัวค้นหา pattern ที่ precompute ตาราง bad-character shift ไว้แล้ว
+#[derive(Debug, Clone)]
+pub struct Finder {
+    needle: Vec<u8>,
+    shift: [usize; 256],
+}
+
+impl Finder {
+    /// สร้าง finder สำหรับ `needle` (ต้องไม่ว่าง)
+    pub fn new(needle: &[u8]) -> Self {
+        assert!(!needle.is_empty(), "needle must not be empty");
+
+        // byte ที่ไม่อยู่ใน needle (ยกเว้นตัวสุดท้าย) เลื่อนได้เต็มความยาว
+        let mut shift = [needle.len(); 256];
+        let last = needle.len() - 1;
+        for (i, &b) in needle[..last].iter().enumerate() {
+            shift[b as usize] = last - i;
+        }
+
+        Self {
+            needle: needle.to_vec(),
+            shift,
+        }
+    }
+
+    /// pattern ที่ใช้ค้นหา
+    pub fn needle(&self) -> &[u8] {
+        &self.needle
+    }
+
+    /// ความยาวของ pattern
+    pub fn len(&self) -> usize {
+        self.needle.len()
+    }
+
+    /// pattern ว่างหรือไม่ (ไม่มีทางเป็นจริงเพราะ [`new`](Self::new) ไม่รับ pattern ว่าง)
+    pub fn is_empty(&self) -> bool {
+        self.needle.is_empty()
+    }
+
+    /// หาตำแหน่งแรกของ pattern ใน `haystack`
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        let n = self.needle.len();
+        let last = n - 1;
+        let last_byte = self.needle[last];
+        let mut i = 0;
+
+        while i + n <= haystack.len() {
+            let b = haystack[i + last];
+            if b == last_byte && haystack[i..i + last] == self.needle[..last] {
+                return Some(i);
+            }
+            i += self.shift[b as usize];
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Finder;
+
+    /// ค้นหาแบบเทียบทุก offset ใช้เป็นคำตอบที่ถูกต้อง
+    fn naive(needle: &[u8], haystack: &[u8]) -> Option<usize> {
+        haystack.windows(needle.len()).position(|window| window == needle)
+    }
+
+    fn check(needle: &[u8], haystack: &[u8]) -> Option<usize> {
+        let found = Finder::new(needle).find(haystack);
+        assert_eq!(found, naive(needle, haystack), "needle {:?} in {:?}", needle, haystack);
+        found
+    }
+
+    #[test]
+    fn finds_needle_at_the_edges() {
+        assert_eq!(check(b"--b", b"--b\r\ndata"), Some(0));
+        assert_eq!(check(b"--b", b"data\r\n--b"), Some(6));
+        assert_eq!(check(b"--b", b"--b"), Some(0));
+        assert_eq!(check(b"\r\n--b", b"x\r\n--b\r\n--b"), Some(1));
+    }
+
+    #[test]
+    fn finds_one_byte_needles() {
+        assert_eq!(check(b"x", b"x"), Some(0));
+        assert_eq!(check(b"x", b"abcx"), Some(3));
+        assert_eq!(check(b"x", b"abc"), None);
+        assert_eq!(check(b"x", b""), None);
+    }
+
+    #[test]
+    fn handles_repeated_prefixes() {
+        assert_eq!(check(b"aab", b"aaaaab"), Some(3));
+        assert_eq!(check(b"abab", b"abaabab"), Some(3));
+        assert_eq!(check(b"----b", b"-------b"), Some(3));
+        assert_eq!(check(b"\r\n\r\n", b"\r\n\r\r\n\r\n"), Some(3));
+        assert_eq!(check(b"aaa", b"aaaa"), Some(0));
+    }
+
+    #[test]
+    fn reports_no_match() {
+        assert_eq!(check(b"--b", b"--a--c-b"), None);
+        assert_eq!(check(b"--boundary", b"--bound"), None);
+        assert_eq!(check(b"--boundary", b""), None);
+        assert_eq!(check(b"abc", b"ab"), None);
+    }
+
+    #[test]
+    fn matches_naive_search_on_every_small_input() {
+        // ทุก needle ยาว 1-3 และทุก haystack ยาว 0-7 จากตัวอักษร 2 ตัว (ซ้ำกันเยอะ เป็นกรณียากของ Horspool)
+        let expand = |len: usize, n: usize| -> Vec<u8> { (0..len).map(|i| if n >> i & 1 == 1 { b'-' } else { b'b' }).collect() };
+        for needle_len in 1..=3 {
+            for needle in 0..1 << needle_len {
+                let needle = expand(needle_len, needle);
+                for haystack_len in 0..=7 {
+                    for haystack in 0..1 << haystack_len {
+                        check(&needle, &expand(haystack_len, haystack));
+                    }
+                }
+            }
+        }
+    }
+}
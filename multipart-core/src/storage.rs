@@ -0,0 +1,305 @@
+//! ที่เก็บไฟล์ของแต่ละ part
+//!
+//! [`UploadProcessor`](crate::UploadProcessor) ไม่ได้เขียนไฟล์เอง แต่ส่งข้อมูลของทุก part ที่เป็นไฟล์
+//! ให้ [`StorageBackend`] ที่เลือกตาม route หรือชื่อ field ผ่าน [`StorageRoutes`]
+//!
+//! - [`DiskStorage`] เขียนลง directory (ไฟล์ชั่วคราวแล้ว rename เมื่อ part จบ)
+//! - [`MemoryStorage`] เก็บไว้ใน memory ใช้ใน test
+//! - [`S3Storage`](crate::s3::S3Storage) ส่งขึ้น object storage ที่คุย S3 API ได้โดยไม่แตะ disk
+//!
+//! backend ต้องรับประกันแบบเดียวกับ [`DiskStorage`]: ข้อมูลจะมองเห็นได้ในชื่อจริงหลัง
+//! [`PartSink::commit`] เท่านั้น ถ้า part ไม่จบ (error, body ขาด, client หลุด) sink จะถูก
+//! [`abort`](PartSink::abort) หรือ drop และต้องไม่เหลือข้อมูลครึ่งๆ กลางๆ
+
+use std::collections::BTreeMap;
+use std::fs::{File, create_dir_all};
+use std::io::{self, BufWriter, Write};
+use std::sync::{Arc, Mutex};
+
+use crate::error::MultipartError;
+use crate::filename;
+use crate::upload::FsyncMode;
+
+/// ข้อมูลของไฟล์ที่กำลังจะเริ่มเก็บ
+#[derive(Debug, Clone, Copy)]
+pub struct NewFile<'a> {
+    pub field_name: &'a str,
+    /// ชื่อไฟล์ที่ sanitize แล้ว (ชื่อจริงตอน commit อาจต่างจากนี้ตาม naming strategy)
+    pub filename: &'a str,
+    /// `Content-Type` ของ part (ว่างถ้า client ไม่ได้ส่งมา)
+    pub content_type: &'a str,
+}
+
+/// ผลของการ commit: ชื่อที่ใช้จริง และที่อยู่ของข้อมูล (path, `memory:` หรือ `s3://`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredFile {
+    pub name: String,
+    pub location: String,
+}
+
+/// ที่เก็บไฟล์ ใช้ร่วมกันได้หลาย connection พร้อมกัน
+pub trait StorageBackend: Send + Sync {
+    /// เริ่มเก็บไฟล์ใหม่ คืน sink ที่รับข้อมูลของ part นั้น
+    fn create(&self, file: &NewFile<'_>) -> Result<Box<dyn PartSink>, MultipartError>;
+}
+
+/// ปลายทางของข้อมูลของ part เดียว
+pub trait PartSink: Send {
+    /// เขียนข้อมูลช่วงถัดไปของ part
+    fn write(&mut self, data: &[u8]) -> Result<(), MultipartError>;
+
+    /// part จบครบแล้ว ทำให้ข้อมูลมองเห็นได้ในชื่อ `name`
+    ///
+    /// ถ้า `no_clobber` และชื่อนี้มีอยู่แล้ว ให้ลอง [`filename::with_suffix`] แทนการเขียนทับ
+    /// ไม่เกิน [`MAX_NAME_ATTEMPTS`] ชื่อ แล้วคืน error จาก [`no_free_name`]
+    /// ถ้า commit ไม่สำเร็จ sink ต้องเก็บกวาดข้อมูลที่เขียนไปแล้วเอง
+    fn commit(self: Box<Self>, name: &str, no_clobber: bool) -> Result<StoredFile, MultipartError>;
+
+    /// ทิ้งข้อมูลที่เขียนไปแล้ว
+    fn abort(self: Box<Self>);
+}
+
+/// เลือก backend ของไฟล์แต่ละไฟล์: ตามชื่อ field ก่อน แล้วตาม route (path ของ request)
+/// ถ้าไม่ตรงอะไรเลยใช้ default
+#[derive(Clone)]
+pub struct StorageRoutes {
+    default: Arc<dyn StorageBackend>,
+    routes: Vec<(String, Arc<dyn StorageBackend>)>,
+    fields: Vec<(String, Arc<dyn StorageBackend>)>,
+}
+
+impl StorageRoutes {
+    pub fn new(default: Arc<dyn StorageBackend>) -> Self {
+        Self {
+            default,
+            routes: Vec::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// ไฟล์ที่ upload มาที่ `path` (ตรงตัว ไม่รวม query string) ใช้ `backend`
+    pub fn with_route(mut self, path: &str, backend: Arc<dyn StorageBackend>) -> Self {
+        self.routes.push((path.to_string(), backend));
+        self
+    }
+
+    /// ไฟล์ใน field ชื่อ `field_name` ใช้ `backend` ไม่ว่าจะมาจาก route ไหน
+    pub fn with_field(mut self, field_name: &str, backend: Arc<dyn StorageBackend>) -> Self {
+        self.fields.push((field_name.to_string(), backend));
+        self
+    }
+
+    /// backend ของไฟล์ใน field `field_name` ของ request ที่ path `route`
+    pub fn select(&self, route: &str, field_name: &str) -> &Arc<dyn StorageBackend> {
+        let by_field = self.fields.iter().find(|(name, _)| name == field_name);
+        let by_route = || self.routes.iter().find(|(path, _)| path == route);
+        by_field.or_else(by_route).map_or(&self.default, |(_, backend)| backend)
+    }
+}
+
+/// จำนวนชื่อที่ backend ลองเมื่อชื่อจริงถูกใช้แล้ว (ชื่อเดิม + suffix) ก่อนยอมแพ้ด้วย [`MultipartError::Storage`]
+pub const MAX_NAME_ATTEMPTS: usize = 100;
+
+/// error เมื่อลองครบ [`MAX_NAME_ATTEMPTS`] ชื่อแล้วยังไม่มีชื่อว่าง
+pub(crate) fn no_free_name(name: &str) -> MultipartError {
+    MultipartError::Storage(format!("no free name for {:?} after {} attempts", name, MAX_NAME_ATTEMPTS))
+}
+
+/// ชื่อไฟล์ชั่วคราวที่ยังไม่ได้ย้ายไปชื่อจริง (ขึ้นต้นด้วย `.` ให้ซ่อนจาก `ls` ปกติ)
+pub(crate) fn temp_file_name() -> String {
+    format!(".upload-{}.tmp", filename::uuid_v4())
+}
+
+/// เขียนไฟล์ลง directory บน disk
+#[derive(Debug, Clone)]
+pub struct DiskStorage {
+    dir: String,
+    fsync: FsyncMode,
+}
+
+impl DiskStorage {
+    pub fn new(dir: &str) -> Self {
+        Self {
+            dir: dir.to_string(),
+            fsync: FsyncMode::default(),
+        }
+    }
+
+    /// กำหนดการ fsync ไฟล์ที่บันทึก (default [`FsyncMode::Never`])
+    pub fn with_fsync(mut self, fsync: FsyncMode) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    fn path_of(&self, name: &str) -> String {
+        format!("{}/{}", self.dir, name)
+    }
+}
+
+impl StorageBackend for DiskStorage {
+    fn create(&self, _file: &NewFile<'_>) -> Result<Box<dyn PartSink>, MultipartError> {
+        create_dir_all(&self.dir)?;
+        let temp_path = self.path_of(&temp_file_name());
+        let file = File::options().write(true).create_new(true).open(&temp_path)?;
+
+        Ok(Box::new(DiskPartSink {
+            storage: self.clone(),
+            temp_path: Some(temp_path),
+            writer: Some(BufWriter::new(file)),
+        }))
+    }
+}
+
+/// ไฟล์ชั่วคราวของ part ที่กำลังเขียน ถูกลบเมื่อ drop ถ้ายังไม่ได้ commit
+struct DiskPartSink {
+    storage: DiskStorage,
+    temp_path: Option<String>,
+    writer: Option<BufWriter<File>>,
+}
+
+impl DiskPartSink {
+    /// hard link ไฟล์ชั่วคราวไปที่ `name` หรือชื่อที่เติม suffix ตัวแรกที่ยังว่าง (ไม่เกิน [`MAX_NAME_ATTEMPTS`] ชื่อ)
+    fn link_no_clobber(&self, temp_path: &str, name: &str) -> Result<StoredFile, MultipartError> {
+        for n in 0..MAX_NAME_ATTEMPTS {
+            let stored_name = match n {
+                0 => name.to_string(),
+                n => filename::with_suffix(name, n),
+            };
+            let location = self.storage.path_of(&stored_name);
+            match std::fs::hard_link(temp_path, &location) {
+                Ok(()) => {
+                    return Ok(StoredFile {
+                        name: stored_name,
+                        location,
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(no_free_name(name))
+    }
+}
+
+impl PartSink for DiskPartSink {
+    fn write(&mut self, data: &[u8]) -> Result<(), MultipartError> {
+        if let Some(writer) = &mut self.writer {
+            // Stream ไปที่ไฟล์โดยตรง (ไม่เก็บใน memory)
+            writer.write_all(data)?;
+        }
+        Ok(())
+    }
+
+    fn commit(mut self: Box<Self>, name: &str, no_clobber: bool) -> Result<StoredFile, MultipartError> {
+        if let Some(writer) = self.writer.take() {
+            let file = writer.into_inner().map_err(|e| e.into_error())?;
+            if self.storage.fsync != FsyncMode::Never {
+                file.sync_all()?;
+            }
+        }
+        let temp_path = self.temp_path.clone().unwrap_or_default();
+
+        let stored = if no_clobber {
+            // hard link ไม่สำเร็จถ้าชื่อปลายทางมีอยู่แล้ว จึงไม่เขียนทับไฟล์ที่ connection อื่นเพิ่งบันทึก
+            let stored = self.link_no_clobber(&temp_path, name)?;
+            std::fs::remove_file(&temp_path)?;
+            stored
+        } else {
+            let stored = StoredFile {
+                name: name.to_string(),
+                location: self.storage.path_of(name),
+            };
+            std::fs::rename(&temp_path, &stored.location)?;
+            stored
+        };
+        self.temp_path = None;
+
+        if self.storage.fsync == FsyncMode::FileAndDirectory {
+            sync_dir(&self.storage.dir)?;
+        }
+        Ok(stored)
+    }
+
+    fn abort(self: Box<Self>) {}
+}
+
+impl Drop for DiskPartSink {
+    fn drop(&mut self) {
+        self.writer = None;
+        if let Some(path) = self.temp_path.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// fsync directory เพื่อให้การ rename ถูกบันทึกลง disk (มีผลเฉพาะ unix)
+fn sync_dir(dir: &str) -> io::Result<()> {
+    if cfg!(unix) {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// เก็บไฟล์ไว้ใน memory (name → bytes) clone แล้วยังเห็นข้อมูลชุดเดียวกัน
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    files: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// เนื้อหาของไฟล์ที่ commit แล้ว
+    pub fn get(&self, name: &str) -> Option<Vec<u8>> {
+        self.files.lock().unwrap().get(name).cloned()
+    }
+
+    /// ชื่อไฟล์ที่ commit แล้วทั้งหมด เรียงตามชื่อ
+    pub fn names(&self) -> Vec<String> {
+        self.files.lock().unwrap().keys().cloned().collect()
+    }
+}
+
+impl StorageBackend for MemoryStorage {
+    fn create(&self, _file: &NewFile<'_>) -> Result<Box<dyn PartSink>, MultipartError> {
+        Ok(Box::new(MemoryPartSink {
+            files: Arc::clone(&self.files),
+            data: Vec::new(),
+        }))
+    }
+}
+
+struct MemoryPartSink {
+    files: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+    data: Vec<u8>,
+}
+
+impl PartSink for MemoryPartSink {
+    fn write(&mut self, data: &[u8]) -> Result<(), MultipartError> {
+        self.data.extend_from_slice(data);
+        Ok(())
+    }
+
+    fn commit(self: Box<Self>, name: &str, no_clobber: bool) -> Result<StoredFile, MultipartError> {
+        let mut files = self.files.lock().unwrap();
+        let mut stored_name = name.to_string();
+        let mut n = 0;
+        while no_clobber && files.contains_key(&stored_name) {
+            n += 1;
+            if n == MAX_NAME_ATTEMPTS {
+                return Err(no_free_name(name));
+            }
+            stored_name = filename::with_suffix(name, n);
+        }
+
+        files.insert(stored_name.clone(), self.data);
+        Ok(StoredFile {
+            location: format!("memory:{}", stored_name),
+            name: stored_name,
+        })
+    }
+
+    fn abort(self: Box<Self>) {}
+}
@@ -0,0 +1,184 @@
+//! subtype ของ `multipart/*` และตัวช่วยของแต่ละแบบ
+//!
+//! [`StreamingParser`](crate::StreamingParser) แยก part ด้วย boundary แบบเดียวกันทุก subtype
+//! ต่างกันแค่ว่า part มีความหมายอย่างไร
+//!
+//! - `multipart/form-data` (RFC 7578): ทุก part ต้องมี `Content-Disposition: form-data; name="..."`
+//! - `multipart/mixed` (RFC 2046): part ไม่มีชื่อ ใช้ลำดับเป็นชื่อแทน (`0`, `1`, ...)
+//! - `multipart/related` (RFC 2387): part แรก (หรือ part ที่ `Content-ID` ตรงกับ `start`) เป็น root
+//!   ส่วน part อื่นถูกอ้างถึงด้วย `cid:` URL เช่น SOAP/MTOM หรือ upload แบบ metadata + media ดู [`Related`]
+//! - `multipart/byteranges` (RFC 9110 §14.6): แต่ละ part เป็นช่วงหนึ่งของ resource ดู [`ContentRange`]
+
+use std::fmt;
+
+use crate::error::MultipartError;
+use crate::mime::MediaType;
+use crate::parser::PartHeaders;
+
+/// subtype ของ `multipart/*` (ตัวพิมพ์เล็ก)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subtype {
+    FormData,
+    Mixed,
+    Related,
+    ByteRanges,
+    Alternative,
+    /// subtype อื่น เช่น `digest`, `parallel` parse แบบเดียวกับ `multipart/mixed`
+    Other(String),
+}
+
+impl Subtype {
+    /// จากชื่อ subtype (ไม่สนตัวพิมพ์)
+    pub fn parse(subtype: &str) -> Self {
+        match subtype.to_ascii_lowercase().as_str() {
+            "form-data" => Self::FormData,
+            "mixed" => Self::Mixed,
+            "related" => Self::Related,
+            "byteranges" => Self::ByteRanges,
+            "alternative" => Self::Alternative,
+            other => Self::Other(other.to_string()),
+        }
+    }
+
+    /// subtype ของ media type ที่เป็น `multipart/*` (`None` ถ้าไม่ใช่ multipart)
+    pub fn of(media_type: &MediaType) -> Option<Self> {
+        media_type.is_multipart().then(|| Self::parse(&media_type.subtype))
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::FormData => "form-data",
+            Self::Mixed => "mixed",
+            Self::Related => "related",
+            Self::ByteRanges => "byteranges",
+            Self::Alternative => "alternative",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Subtype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "multipart/{}", self.name())
+    }
+}
+
+/// พารามิเตอร์ของ `multipart/related` (RFC 2387 §3)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Related {
+    /// `type`: media type ของ root part เช่น `application/xop+xml`
+    pub root_type: Option<String>,
+    /// `start`: `Content-ID` ของ root part (ถอด `<>` แล้ว) ถ้าไม่มี root คือ part แรก
+    pub start: Option<String>,
+    /// `start-info`: ข้อมูลเพิ่มเติมสำหรับ root part ตามที่ส่งมา
+    pub start_info: Option<String>,
+}
+
+impl Related {
+    /// อ่านจาก `Content-Type: multipart/related; type="..."; start="<...>"`
+    ///
+    /// คืน [`MultipartError::UnsupportedMediaType`] ถ้าไม่ใช่ `multipart/related`
+    pub fn from_media_type(media_type: &MediaType) -> Result<Self, MultipartError> {
+        if Subtype::of(media_type) != Some(Subtype::Related) {
+            return Err(MultipartError::UnsupportedMediaType(media_type.essence()));
+        }
+        Ok(Self {
+            root_type: media_type.param("type").map(str::to_string),
+            start: media_type.param("start").map(|start| strip_angle_brackets(start).to_string()),
+            start_info: media_type.param("start-info").map(str::to_string),
+        })
+    }
+
+    /// part ลำดับที่ `index` (เริ่มจาก 0) ที่มี `headers` นี้เป็น root part หรือไม่
+    pub fn is_root(&self, index: usize, headers: &PartHeaders) -> bool {
+        match &self.start {
+            Some(start) => headers.content_id() == Some(start.as_str()),
+            None => index == 0,
+        }
+    }
+}
+
+/// `Content-ID` ที่ `cid:` URL อ้างถึง (RFC 2392) เช่น `cid:part1%40example.com` → `part1@example.com`
+///
+/// คืน `None` ถ้าไม่ใช่ `cid:` URL หรือ percent-encoding ผิด
+pub fn cid_reference(url: &str) -> Option<String> {
+    let scheme = url.get(..4)?;
+    if !scheme.eq_ignore_ascii_case("cid:") {
+        return None;
+    }
+
+    let encoded = &url.as_bytes()[4..];
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        if encoded[i] == b'%' {
+            // from_str_radix รับ `+` นำหน้า จึงต้องตรวจว่าเป็น hex ทั้งสองตัวเอง
+            let hex = encoded.get(i + 1..i + 3).filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+            let hex = std::str::from_utf8(hex).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(encoded[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// ถอด `<` `>` รอบ msg-id ของ `Content-ID` และ `start`
+pub(crate) fn strip_angle_brackets(value: &str) -> &str {
+    let value = value.trim_matches([' ', '\t']);
+    value
+        .strip_prefix('<')
+        .and_then(|value| value.strip_suffix('>'))
+        .unwrap_or(value)
+}
+
+/// `Content-Range: bytes first-last/complete` ของ part ใน `multipart/byteranges`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContentRange {
+    /// offset ของ byte แรก
+    pub first: u64,
+    /// offset ของ byte สุดท้าย (รวม)
+    pub last: u64,
+    /// ขนาดทั้งหมดของ resource (`None` ถ้าเป็น `*`)
+    pub complete_length: Option<u64>,
+}
+
+impl ContentRange {
+    /// parse ค่าของ header (รับแค่หน่วย `bytes` และต้องมีช่วง ไม่ใช่ `bytes */length`)
+    pub fn parse(value: &str) -> Result<Self, MultipartError> {
+        let invalid = || MultipartError::MalformedPartHeaders(format!("invalid Content-Range {:?}", value));
+
+        let (unit, rest) = value.trim_matches([' ', '\t']).split_once(' ').ok_or_else(invalid)?;
+        if !unit.eq_ignore_ascii_case("bytes") {
+            return Err(invalid());
+        }
+        let (range, complete_length) = rest.trim_start().split_once('/').ok_or_else(invalid)?;
+        let (first, last) = range.split_once('-').ok_or_else(invalid)?;
+        let number = |digits: &str| -> Result<u64, MultipartError> {
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            digits.parse().map_err(|_| invalid())
+        };
+
+        let range = Self {
+            first: number(first)?,
+            last: number(last)?,
+            complete_length: match complete_length {
+                "*" => None,
+                length => Some(number(length)?),
+            },
+        };
+        if range.last < range.first || range.complete_length.is_some_and(|length| range.last >= length) {
+            return Err(invalid());
+        }
+        Ok(range)
+    }
+
+    /// จำนวน bytes ในช่วงนี้ (อย่างน้อย 1)
+    pub fn length(&self) -> u64 {
+        self.last - self.first + 1
+    }
+}
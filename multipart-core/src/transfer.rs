@@ -0,0 +1,249 @@
+//! Decoder ของ `Content-Transfer-Encoding` ของแต่ละ part แบบ streaming
+//!
+//! client รุ่นเก่าและ payload ที่มาจาก email ส่ง part เป็น `base64` หรือ `quoted-printable` (RFC 2045 §6)
+//! decoder รับข้อมูลทีละช่วงตามที่ parser ปล่อยออกมา ช่วงจะถูกตัดตรงไหนก็ได้ (กลาง quantum ของ base64
+//! หรือกลาง `=XX`) โดยเก็บไว้แค่ไม่กี่ bytes ที่ยังตัดสินไม่ได้
+//!
+//! ```
+//! use multipart_core::transfer::{TransferDecoder, TransferEncoding};
+//!
+//! let mut decoder = TransferDecoder::new(TransferEncoding::QuotedPrintable);
+//! let mut out = Vec::new();
+//! decoder.decode(b"caf=C3=", &mut out)?;
+//! decoder.decode(b"A9 =\r\nau lait", &mut out)?;
+//! decoder.finish()?;
+//! assert_eq!(String::from_utf8(out).unwrap(), "café au lait");
+//! # Ok::<(), multipart_core::MultipartError>(())
+//! ```
+
+use crate::digest::BASE64_ALPHABET;
+use crate::error::MultipartError;
+use crate::parser::PartHeaders;
+
+/// ค่าของ `Content-Transfer-Encoding`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransferEncoding {
+    /// `7bit`, `8bit`, `binary` หรือไม่ได้ส่ง header มา: ข้อมูลตามที่ส่งมา
+    Identity,
+    Base64,
+    QuotedPrintable,
+}
+
+impl TransferEncoding {
+    /// parse ค่าของ header (ไม่สนตัวพิมพ์) ค่าที่ไม่รู้จักคืน
+    /// [`MultipartError::InvalidContentTransferEncoding`]
+    pub fn parse(value: &str) -> Result<Self, MultipartError> {
+        match value.trim_matches([' ', '\t']).to_ascii_lowercase().as_str() {
+            "7bit" | "8bit" | "binary" => Ok(Self::Identity),
+            "base64" => Ok(Self::Base64),
+            "quoted-printable" => Ok(Self::QuotedPrintable),
+            other => Err(invalid(format!("unsupported encoding {:?}", other))),
+        }
+    }
+
+    /// encoding ของ part ตาม header `Content-Transfer-Encoding` (ไม่มี header = [`Identity`](Self::Identity))
+    pub fn of(headers: &PartHeaders) -> Result<Self, MultipartError> {
+        headers
+            .header("content-transfer-encoding")
+            .map_or(Ok(Self::Identity), Self::parse)
+    }
+}
+
+/// ตาราง byte → ค่า 6 bits ของ base64 (`0xff` = ไม่ใช่ตัวอักษรของ base64)
+const BASE64_VALUES: [u8; 256] = {
+    let mut table = [0xff; 256];
+    let mut i = 0;
+    while i < BASE64_ALPHABET.len() {
+        table[BASE64_ALPHABET[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+};
+
+/// state ของ decoder แต่ละแบบ
+#[derive(Debug)]
+enum State {
+    Identity,
+    Base64 {
+        /// ค่า 6 bits ที่ยังไม่ครบ quantum (4 ตัว)
+        quantum: [u8; 4],
+        len: usize,
+        /// จำนวน `=` ที่เจอใน quantum สุดท้าย
+        padding: usize,
+        /// เจอ padding ครบแล้ว หลังจากนี้มีได้แค่ whitespace
+        ended: bool,
+    },
+    QuotedPrintable {
+        /// bytes ท้ายช่วงก่อนที่ยังตัดสินไม่ได้ (`=`, `=X`, whitespace ท้ายบรรทัด)
+        pending: Vec<u8>,
+    },
+}
+
+/// decoder ของ part เดียว สร้างใหม่ทุก part
+#[derive(Debug)]
+pub struct TransferDecoder {
+    state: State,
+}
+
+impl TransferDecoder {
+    pub fn new(encoding: TransferEncoding) -> Self {
+        let state = match encoding {
+            TransferEncoding::Identity => State::Identity,
+            TransferEncoding::Base64 => State::Base64 {
+                quantum: [0; 4],
+                len: 0,
+                padding: 0,
+                ended: false,
+            },
+            TransferEncoding::QuotedPrintable => State::QuotedPrintable { pending: Vec::new() },
+        };
+        Self { state }
+    }
+
+    /// decode ช่วงถัดไปของข้อมูล ต่อท้ายผลลงใน `out`
+    pub fn decode(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<(), MultipartError> {
+        match &mut self.state {
+            State::Identity => out.extend_from_slice(input),
+            State::Base64 {
+                quantum,
+                len,
+                padding,
+                ended,
+            } => {
+                out.reserve(input.len() / 4 * 3 + 3);
+                for &byte in input {
+                    match byte {
+                        // base64 ใน MIME ถูกตัดบรรทัดทุก 76 ตัว
+                        b'\r' | b'\n' | b' ' | b'\t' => {}
+                        b'=' if !*ended && *len >= 2 => {
+                            *padding += 1;
+                            if *len + *padding == 4 {
+                                let bits = quantum_bits(quantum);
+                                out.extend_from_slice(&bits.to_be_bytes()[1..*len]);
+                                *ended = true;
+                            }
+                        }
+                        _ if *ended || *padding > 0 => {
+                            return Err(invalid("base64 data after padding".to_string()));
+                        }
+                        _ => {
+                            let value = BASE64_VALUES[byte as usize];
+                            if value == 0xff {
+                                return Err(invalid(format!("invalid base64 character {:?}", byte as char)));
+                            }
+                            quantum[*len] = value;
+                            *len += 1;
+                            if *len == 4 {
+                                out.extend_from_slice(&quantum_bits(quantum).to_be_bytes()[1..]);
+                                *len = 0;
+                            }
+                        }
+                    }
+                }
+            }
+            State::QuotedPrintable { pending } => {
+                pending.extend_from_slice(input);
+                let used = decode_quoted_printable(pending, out)?;
+                pending.drain(..used);
+            }
+        }
+        Ok(())
+    }
+
+    /// part จบแล้ว ตรวจว่าไม่มีข้อมูลค้างที่ decode ไม่ครบ
+    ///
+    /// base64 ต้องจบครบ quantum (พร้อม padding) ส่วน quoted-printable ที่จบด้วย `=` ถือเป็น soft line break
+    /// และ whitespace ท้ายข้อมูลถูกทิ้งเหมือนท้ายบรรทัด
+    pub fn finish(&mut self) -> Result<(), MultipartError> {
+        match &self.state {
+            State::Identity => Ok(()),
+            State::Base64 { len, ended, .. } if *len > 0 && !*ended => {
+                Err(invalid("truncated base64 data".to_string()))
+            }
+            State::Base64 { .. } => Ok(()),
+            State::QuotedPrintable { pending } => {
+                if let Some(escape) = pending.iter().position(|&b| b == b'=')
+                    && pending[escape + 1..].iter().any(|&b| b != b' ' && b != b'\t')
+                {
+                    return Err(invalid("truncated quoted-printable escape".to_string()));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// รวมค่า 6 bits 4 ตัวเป็น 24 bits (ตัวที่ยังไม่มาเป็น 0)
+fn quantum_bits(quantum: &[u8; 4]) -> u32 {
+    quantum.iter().fold(0, |bits, &value| bits << 6 | value as u32)
+}
+
+/// decode quoted-printable ใน `input` เท่าที่ตัดสินได้ คืนจำนวน bytes ที่ใช้ไป
+/// (ที่เหลือคือ `=` หรือ whitespace ท้ายข้อมูลที่ต้องรอ bytes ถัดไปก่อน)
+fn decode_quoted_printable(input: &[u8], out: &mut Vec<u8>) -> Result<usize, MultipartError> {
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'=' => {
+                let Some(&next) = input.get(i + 1) else {
+                    return Ok(i);
+                };
+                if next.is_ascii_hexdigit() {
+                    let Some(&low) = input.get(i + 2) else {
+                        return Ok(i);
+                    };
+                    if !low.is_ascii_hexdigit() {
+                        return Err(invalid(format!("invalid quoted-printable escape {:?}", &input[i..i + 3])));
+                    }
+                    out.push(hex_value(next) << 4 | hex_value(low));
+                    i += 3;
+                    continue;
+                }
+
+                // soft line break: `=` ตามด้วย whitespace (ถ้ามี) แล้ว CRLF (หรือ LF)
+                let end = skip_whitespace(input, i + 1);
+                match &input[end..] {
+                    [] | [b'\r'] => return Ok(i),
+                    [b'\r', b'\n', ..] => i = end + 2,
+                    [b'\n', ..] => i = end + 1,
+                    _ => return Err(invalid(format!("invalid quoted-printable escape {:?}", &input[i..=end]))),
+                }
+            }
+            b' ' | b'\t' => {
+                // whitespace ท้ายบรรทัดถูกเติมระหว่างส่ง ต้องทิ้ง (RFC 2045 §6.7 rule 3)
+                let end = skip_whitespace(input, i);
+                match input.get(end) {
+                    None => return Ok(i),
+                    Some(b'\r' | b'\n') => {}
+                    Some(_) => out.extend_from_slice(&input[i..end]),
+                }
+                i = end;
+            }
+            _ => {
+                let end = input[i..]
+                    .iter()
+                    .position(|&b| matches!(b, b'=' | b' ' | b'\t'))
+                    .map_or(input.len(), |offset| i + offset);
+                out.extend_from_slice(&input[i..end]);
+                i = end;
+            }
+        }
+    }
+    Ok(i)
+}
+
+fn skip_whitespace(input: &[u8], from: usize) -> usize {
+    from + input[from..].iter().take_while(|&&b| b == b' ' || b == b'\t').count()
+}
+
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+fn invalid(reason: String) -> MultipartError {
+    MultipartError::InvalidContentTransferEncoding(reason)
+}
@@ -0,0 +1,637 @@
+//! Consumer ของ [`StreamingParser`] ที่ stream ไฟล์ลง disk ระหว่างที่รับข้อมูล
+//!
+//! ไฟล์แต่ละ part ถูกเขียนลงไฟล์ชั่วคราว (`.upload-<uuid>.tmp`) ใน upload directory เดียวกัน
+//! แล้วค่อยย้ายไปชื่อจริงเมื่อเจอ delimiter ที่ปิด part นั้น ไฟล์ที่เห็นในชื่อจริงจึงครบเสมอ
+//! ถ้า error, body ถูกตัด หรือ client หลุดกลางคัน ไฟล์ชั่วคราวจะถูกลบทิ้ง
+//!
+//! ที่เก็บไฟล์เปลี่ยนได้ด้วย [`UploadProcessor::with_storage`] (เช่น memory หรือ S3)
+//! ดู [`storage`](crate::storage)
+
+use std::fs::create_dir_all;
+use std::str::FromStr;
+
+use crate::digest::{self, DigestAlgorithm, Hasher, to_hex};
+use crate::error::MultipartError;
+use crate::filename::{self, NamingStrategy};
+use crate::http::HeaderMap;
+use crate::limits::Limits;
+use crate::parser::{Event, PartHeaders, PartType, StreamingParser};
+use crate::storage::{DiskStorage, NewFile, PartSink, StorageBackend, StorageRoutes, StoredFile};
+use crate::transfer::{TransferDecoder, TransferEncoding};
+
+/// จะ fsync ไฟล์ที่บันทึกแค่ไหน (แลกความเร็วกับความทนทานเมื่อเครื่องดับ)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum FsyncMode {
+    /// ไม่ fsync ปล่อยให้ OS flush เอง
+    #[default]
+    Never,
+    /// fsync ไฟล์ก่อนย้ายไปชื่อจริง: ไฟล์ในชื่อจริงมีข้อมูลครบแม้เครื่องดับ
+    File,
+    /// fsync ไฟล์ และ directory หลังย้าย: ไฟล์ที่ตอบ 200 ไปแล้วไม่หายแม้เครื่องดับ
+    FileAndDirectory,
+}
+
+impl FsyncMode {
+    /// อ่านจาก env `UPLOAD_FSYNC` (`never`, `file`, `full`) ค่าที่ไม่รู้จักใช้ default
+    pub fn from_env() -> Self {
+        std::env::var("UPLOAD_FSYNC")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+impl FromStr for FsyncMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "never" | "off" => Ok(Self::Never),
+            "file" => Ok(Self::File),
+            "full" | "dir" => Ok(Self::FileAndDirectory),
+            other => Err(format!("unknown fsync mode {:?}", other)),
+        }
+    }
+}
+
+/// สถิติของ request ที่ parse ไปแล้ว
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    pub total_chunks: usize,
+    pub total_bytes: usize,
+    pub fields_count: usize,
+    pub files_count: usize,
+    pub files_saved: Vec<FileInfo>,
+    /// ค่าของ text field ทั้งหมด (name, value) ตามลำดับที่ส่งมา ชื่อซ้ำได้ (เช่น checkbox หลายตัว)
+    pub fields: Vec<(String, String)>,
+}
+
+impl Stats {
+    /// ค่าแรกของ field `name`
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(candidate, _)| candidate == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// ทุกค่าของ field `name` ตามลำดับที่ส่งมา
+    pub fn field_values(&self, name: &str) -> Vec<&str> {
+        self.fields
+            .iter()
+            .filter(|(candidate, _)| candidate == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+}
+
+/// ข้อมูลของไฟล์ที่บันทึกลง disk เสร็จแล้ว
+#[derive(Debug, Clone)]
+pub struct FileInfo {
+    pub field_name: String,
+    /// ชื่อที่บันทึกจริงใน upload directory (ตาม [`NamingStrategy`])
+    pub filename: String,
+    /// ชื่อเดิมที่ client ส่งมา (ยังไม่ได้ sanitize ห้ามเอาไปใช้เป็น path)
+    pub original_filename: String,
+    /// `Content-Type` ของ part (ว่างถ้า client ไม่ได้ส่งมา)
+    pub content_type: String,
+    pub size: usize,
+    /// ที่อยู่ของไฟล์ตาม backend ที่เก็บ (path บน disk, `memory:<name>` หรือ `s3://bucket/key`)
+    pub path: String,
+    /// headers ทั้งหมดของ part เผื่อให้ downstream เลือกทางตาม header อื่นๆ
+    pub headers: HeaderMap,
+    /// digest ที่คำนวณระหว่าง stream (ตาม [`UploadProcessor::with_digests`]
+    /// รวมถึง algorithm ที่ต้องใช้ตรวจกับค่าที่ client ส่งมาหรือใช้ตั้งชื่อไฟล์)
+    pub digests: Vec<(DigestAlgorithm, Vec<u8>)>,
+}
+
+impl FileInfo {
+    /// digest ของ `algorithm` ถ้าคำนวณไว้
+    pub fn digest(&self, algorithm: DigestAlgorithm) -> Option<&[u8]> {
+        self.digests
+            .iter()
+            .find(|(candidate, _)| *candidate == algorithm)
+            .map(|(_, bytes)| bytes.as_slice())
+    }
+}
+
+/// รับ body ทีละ chunk แล้วเขียนทุก part ที่เป็นไฟล์ลง `upload_dir` โดยตรง
+///
+/// เป็นแค่ consumer ตัวหนึ่งของ event จาก [`StreamingParser`]
+/// ถ้าต้องการจัดการ part เองให้ใช้ parser ตรงๆ
+pub struct UploadProcessor {
+    parser: StreamingParser,
+    sink: FileSink,
+    /// ค่าจาก [`with_max_field_size`](Self::with_max_field_size) ที่ [`with_limits`](Self::with_limits) ต้องไม่เขียนทับ
+    max_field_size: Option<usize>,
+}
+
+/// ส่วนที่จัดการไฟล์ แยกออกจาก parser เพื่อให้ยืม event จาก parser ได้พร้อมกับเขียนไฟล์
+struct FileSink {
+    tracker: PartTracker,
+    /// backend ที่เลือกเอง (`None` = [`DiskStorage`] ของ `upload_dir`)
+    storage: Option<StorageRoutes>,
+    /// path ของ request ใช้เลือก backend ตาม route
+    route: String,
+    part: Option<Box<dyn PartSink>>,
+    /// ข้อมูลของ part ที่ decode `Content-Transfer-Encoding` แล้ว (ใช้ซ้ำทุก chunk)
+    decoded: Vec<u8>,
+}
+
+/// state ของ part ที่ไม่เกี่ยวกับ I/O (นับสถิติ, เก็บค่า field, ตั้งชื่อไฟล์)
+///
+/// แยกจาก I/O เพื่อให้ logic เดียวกันใช้ได้กับทุก [`StorageBackend`]
+pub(crate) struct PartTracker {
+    pub(crate) upload_dir: String,
+    pub(crate) naming: NamingStrategy,
+    pub(crate) fsync: FsyncMode,
+    current_part: Option<PartHeaders>,
+    /// ชื่อไฟล์ของ part ปัจจุบันที่ sanitize แล้ว
+    current_name: String,
+    /// algorithm ที่ต้องคำนวณกับทุกไฟล์
+    pub(crate) digests: Vec<DigestAlgorithm>,
+    /// ตรวจ digest ที่ client ส่งมาหรือไม่
+    pub(crate) verify_digests: bool,
+    /// hasher ของไฟล์ปัจจุบัน (ที่เลือกไว้ + ที่ต้องใช้ตรวจหรือตั้งชื่อ)
+    hashers: Vec<Hasher>,
+    /// digest ที่ client บอกไว้สำหรับไฟล์ปัจจุบัน
+    expected_digests: Vec<(DigestAlgorithm, Vec<u8>)>,
+    /// digest จาก form field (`Content-MD5`, `Digest`, `Repr-Digest`) ที่รอใช้กับไฟล์ถัดไป
+    field_digests: Vec<(DigestAlgorithm, Vec<u8>)>,
+    /// decode `Content-Transfer-Encoding` ของแต่ละ part หรือไม่
+    pub(crate) decode_transfer: bool,
+    /// decoder ของ part ปัจจุบัน (`None` ถ้าไม่ต้อง decode)
+    decoder: Option<TransferDecoder>,
+    part_size: usize,
+    field_value: Vec<u8>,
+    /// จำนวน part ที่เจอแล้ว รวม part ที่ถูกข้าม
+    parts_seen: usize,
+    pub(crate) limits: Limits,
+    pub(crate) stats: Stats,
+}
+
+/// ไฟล์ที่เขียนครบแล้ว รอ commit ไปชื่อจริง
+pub(crate) struct PendingFile {
+    /// ชื่อที่ตั้งตาม [`NamingStrategy`] แล้ว
+    pub(crate) name: String,
+    /// ถ้าชื่อจริงมีไฟล์อยู่แล้วให้เติม suffix แทนการเขียนทับ
+    pub(crate) no_clobber: bool,
+    /// `filename` และ `path` จะถูกเติมตอน [`PartTracker::committed`]
+    pub(crate) info: FileInfo,
+}
+
+impl UploadProcessor {
+    /// สร้าง processor จาก delimiter (`--` + boundary) และ directory ที่จะเก็บไฟล์
+    pub fn new(boundary: &str, upload_dir: &str) -> Result<Self, MultipartError> {
+        let parser = StreamingParser::new(boundary)?;
+        create_dir_all(upload_dir)?;
+
+        Ok(Self {
+            parser,
+            sink: FileSink {
+                tracker: PartTracker::new(upload_dir),
+                storage: None,
+                route: String::new(),
+                part: None,
+                decoded: Vec::new(),
+            },
+            max_field_size: None,
+        })
+    }
+
+    /// กำหนดขนาดสูงสุดของค่า text field แต่ละตัว
+    ///
+    /// field ที่ยาวเกินจะทำให้ [`process_chunk`](Self::process_chunk) คืน
+    /// [`MultipartError::LimitExceeded`]
+    ///
+    /// ค่านี้ชนะ `max_field_size` ของ [`with_limits`](Self::with_limits) ไม่ว่าจะเรียกก่อนหรือหลัง
+    pub fn with_max_field_size(mut self, max_field_size: usize) -> Self {
+        self.sink.tracker.limits.max_field_size = max_field_size;
+        self.max_field_size = Some(max_field_size);
+        self
+    }
+
+    /// กำหนดขอบเขตทั้งหมดของ body (default [`Limits::default`])
+    ///
+    /// ถ้าเกิน limit ใด [`process_chunk`](Self::process_chunk) จะคืน
+    /// [`MultipartError::LimitExceeded`] ที่บอกชื่อ limit นั้น และลบไฟล์ชั่วคราวที่ค้างอยู่
+    ///
+    /// ถ้าเคยเรียก [`with_max_field_size`](Self::with_max_field_size) แล้ว `limits.max_field_size` จะถูกข้าม
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.parser = self
+            .parser
+            .with_max_header_size(limits.max_part_header_size)
+            .with_max_nesting_depth(limits.max_nesting_depth);
+        self.sink.tracker.limits = Limits {
+            max_field_size: self.max_field_size.unwrap_or(limits.max_field_size),
+            ..limits
+        };
+        self
+    }
+
+    /// กำหนดวิธีตั้งชื่อไฟล์ที่บันทึก (default [`NamingStrategy::SuffixOnCollision`])
+    pub fn with_naming(mut self, naming: NamingStrategy) -> Self {
+        self.sink.tracker.naming = naming;
+        self
+    }
+
+    /// กำหนดการ fsync ไฟล์ที่บันทึก (default [`FsyncMode::Never`])
+    ///
+    /// มีผลกับ upload directory ตัว default เท่านั้น [`DiskStorage`] ที่ส่งผ่าน
+    /// [`with_storage`](Self::with_storage) ตั้งค่าเองด้วย [`DiskStorage::with_fsync`]
+    pub fn with_fsync(mut self, fsync: FsyncMode) -> Self {
+        self.sink.tracker.fsync = fsync;
+        self
+    }
+
+    /// เก็บไฟล์ผ่าน backend ที่ `routes` เลือกให้จาก `route` (path ของ request) และชื่อ field
+    /// แทนการเขียนลง `upload_dir`
+    pub fn with_storage(mut self, routes: StorageRoutes, route: &str) -> Self {
+        self.sink.storage = Some(routes);
+        self.sink.route = route.to_string();
+        self
+    }
+
+    /// กำหนด digest ที่คำนวณระหว่าง stream ให้ทุกไฟล์ เก็บไว้ใน [`FileInfo::digests`]
+    pub fn with_digests(mut self, digests: &[DigestAlgorithm]) -> Self {
+        self.sink.tracker.digests = digests.to_vec();
+        self
+    }
+
+    /// ตรวจ digest ที่ client ส่งมาหรือไม่ (default เปิด)
+    ///
+    /// digest อ่านได้จาก header ของ part หรือจาก field ชื่อเดียวกันที่ส่งมาก่อนไฟล์
+    /// (`Content-MD5`, `Digest`, `Repr-Digest`) ถ้าไม่ตรงกัน part นั้นจะล้มด้วย
+    /// [`MultipartError::DigestMismatch`] และไฟล์ชั่วคราวถูกลบ
+    pub fn with_digest_verification(mut self, verify: bool) -> Self {
+        self.sink.tracker.verify_digests = verify;
+        self
+    }
+
+    /// decode `Content-Transfer-Encoding: base64` และ `quoted-printable` ของแต่ละ part หรือไม่ (default เปิด)
+    ///
+    /// เมื่อเปิด ไฟล์ที่บันทึก, ค่าของ field, ขนาด, limit และ digest เป็นของข้อมูลที่ decode แล้ว
+    /// (header ใน [`FileInfo::headers`] ยังเป็นค่าเดิม) encoding ที่ไม่รู้จักหรือข้อมูลผิดรูปแบบคืน
+    /// [`MultipartError::InvalidContentTransferEncoding`] ถ้าปิด ข้อมูลถูกเก็บตามที่ส่งมา
+    pub fn with_transfer_decoding(mut self, decode: bool) -> Self {
+        self.sink.tracker.decode_transfer = decode;
+        self
+    }
+
+    /// ป้อน chunk ถัดไปของ body เข้า processor
+    ///
+    /// ถ้าคืน error ไฟล์ชั่วคราวของ part ที่ค้างอยู่จะถูกลบทิ้งแล้ว
+    pub fn process_chunk(&mut self, chunk: &[u8]) -> Result<(), MultipartError> {
+        self.sink.tracker.record_chunk(chunk.len()).inspect_err(|_| self.sink.abort())?;
+
+        self.parser.feed(chunk);
+        self.drain_events().inspect_err(|_| self.sink.abort())
+    }
+
+    /// เรียกหลังอ่าน body หมดแล้ว เพื่อตรวจว่า body จบครบ
+    ///
+    /// ถ้า body ถูกตัดก่อน closing delimiter จะคืน [`MultipartError::UnexpectedEof`]
+    /// และลบไฟล์ชั่วคราวของ part สุดท้ายที่ยังไม่จบ (ไม่มีการ flush ข้อมูลที่ค้างลงไฟล์)
+    pub fn finalize(&mut self) -> Result<(), MultipartError> {
+        self.parser.end_of_input();
+        self.drain_events().inspect_err(|_| self.sink.abort())
+    }
+
+    /// เจอ closing delimiter (`--boundary--`) แล้วหรือยัง
+    pub fn is_complete(&self) -> bool {
+        self.parser.is_complete()
+    }
+
+    /// สถิติที่สะสมมาตั้งแต่เริ่ม parse
+    pub fn get_stats(&self) -> &Stats {
+        &self.sink.tracker.stats
+    }
+
+    fn drain_events(&mut self) -> Result<(), MultipartError> {
+        while let Some(event) = self.parser.next_event()? {
+            self.sink.handle(event)?;
+        }
+        Ok(())
+    }
+}
+
+impl FileSink {
+    fn handle(&mut self, event: Event<'_>) -> Result<(), MultipartError> {
+        match event {
+            Event::PartStart { headers } => {
+                // ถ้าเป็นไฟล์ ให้เปิด sink ของ backend ไม่งั้นนับเป็น field
+                if self.tracker.start_part(headers)? {
+                    self.open_part()?;
+                }
+            }
+            Event::PartData(data) => self.write_data(data)?,
+            Event::PartEnd => self.close_part()?,
+            Event::Finished => {}
+        }
+        Ok(())
+    }
+
+    fn open_part(&mut self) -> Result<(), MultipartError> {
+        let Some(file) = self.tracker.current_file() else {
+            return Ok(());
+        };
+        let part = match &self.storage {
+            Some(routes) => routes.select(&self.route, file.field_name).create(&file)?,
+            None => DiskStorage::new(&self.tracker.upload_dir)
+                .with_fsync(self.tracker.fsync)
+                .create(&file)?,
+        };
+        self.part = Some(part);
+        Ok(())
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> Result<(), MultipartError> {
+        let data = self.tracker.part_data(data, &mut self.decoded)?;
+
+        if let Some(part) = &mut self.part {
+            part.write(data)?;
+        }
+        Ok(())
+    }
+
+    /// part จบครบแล้ว (เจอ delimiter ถัดไป) commit ไฟล์ไปชื่อจริง
+    fn close_part(&mut self) -> Result<(), MultipartError> {
+        let part = self.part.take();
+        let pending = match self.tracker.end_part() {
+            Ok(pending) => pending,
+            Err(e) => {
+                if let Some(part) = part {
+                    part.abort();
+                }
+                return Err(e);
+            }
+        };
+
+        if let (Some(part), Some(pending)) = (part, pending) {
+            let stored = part.commit(&pending.name, pending.no_clobber)?;
+            self.tracker.committed(pending.info, stored);
+        }
+        Ok(())
+    }
+
+    /// ทิ้ง part ที่ยังไม่จบ ให้ backend ลบข้อมูลที่เขียนไปแล้ว
+    fn abort(&mut self) {
+        if let Some(part) = self.part.take() {
+            part.abort();
+        }
+        self.tracker.abort_part();
+    }
+}
+
+impl Drop for FileSink {
+    /// processor ถูกทิ้งกลางคัน (เช่น client หลุดแล้วผู้เรียก return error) ต้องไม่เหลือไฟล์ชั่วคราว
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+/// คืน [`MultipartError::LimitExceeded`] ถ้า `value` เกิน `max`
+fn check_limit(value: usize, max: usize, limit: &'static str) -> Result<(), MultipartError> {
+    if value > max {
+        return Err(MultipartError::LimitExceeded { limit, max });
+    }
+    Ok(())
+}
+
+/// เทียบ digest ที่คำนวณได้กับทุกค่าที่ client ส่งมา
+fn verify_digests(
+    expected: &[(DigestAlgorithm, Vec<u8>)],
+    actual: &[(DigestAlgorithm, Vec<u8>)],
+) -> Result<(), MultipartError> {
+    for (algorithm, expected) in expected {
+        let Some((_, actual)) = actual.iter().find(|(candidate, _)| candidate == algorithm) else {
+            continue;
+        };
+        if actual != expected {
+            return Err(MultipartError::DigestMismatch {
+                algorithm: algorithm.name(),
+                expected: to_hex(expected),
+                actual: to_hex(actual),
+            });
+        }
+    }
+    Ok(())
+}
+
+impl PartTracker {
+    pub(crate) fn new(upload_dir: &str) -> Self {
+        Self {
+            upload_dir: upload_dir.to_string(),
+            naming: NamingStrategy::default(),
+            fsync: FsyncMode::default(),
+            current_part: None,
+            current_name: String::new(),
+            digests: Vec::new(),
+            verify_digests: true,
+            hashers: Vec::new(),
+            expected_digests: Vec::new(),
+            field_digests: Vec::new(),
+            decode_transfer: true,
+            decoder: None,
+            part_size: 0,
+            field_value: Vec::new(),
+            parts_seen: 0,
+            limits: Limits::default(),
+            stats: Stats::default(),
+        }
+    }
+
+    /// นับ chunk ที่ได้รับ และตรวจขนาด body รวม
+    pub(crate) fn record_chunk(&mut self, len: usize) -> Result<(), MultipartError> {
+        self.stats.total_chunks += 1;
+        self.stats.total_bytes += len;
+        check_limit(self.stats.total_bytes, self.limits.max_body_size, "body size")
+    }
+
+    /// เริ่ม part ใหม่ คืน `true` ถ้า part นี้เป็นไฟล์ที่ต้องเก็บ (ดูรายละเอียดด้วย [`current_file`](Self::current_file))
+    ///
+    /// ชื่อไฟล์จาก client ผ่าน [`filename::sanitize`] ก่อนเสมอ ถ้าใช้ไม่ได้คืน
+    /// [`MultipartError::InvalidFilename`] ส่วน `filename=""` (browser ส่ง input file
+    /// ที่ไม่ได้เลือกไฟล์มา) จะข้ามทั้ง part ไม่สร้างไฟล์
+    pub(crate) fn start_part(&mut self, headers: PartHeaders) -> Result<bool, MultipartError> {
+        self.part_size = 0;
+        self.field_value.clear();
+        self.hashers.clear();
+        self.expected_digests.clear();
+        self.decoder = None;
+
+        self.parts_seen += 1;
+        check_limit(self.parts_seen, self.limits.max_parts, "part count")?;
+
+        if self.decode_transfer {
+            let encoding = TransferEncoding::of(&headers)?;
+            if encoding != TransferEncoding::Identity {
+                self.decoder = Some(TransferDecoder::new(encoding));
+            }
+        }
+
+        let Some(original) = headers.filename() else {
+            self.stats.fields_count += 1;
+            self.current_part = Some(headers);
+            return Ok(false);
+        };
+        if original.is_empty() {
+            self.current_part = None;
+            return Ok(false);
+        }
+
+        check_limit(self.stats.files_count + 1, self.limits.max_files, "file count")?;
+        self.current_name = filename::sanitize(original)?;
+        self.start_hashers(&headers)?;
+
+        self.stats.files_count += 1;
+        self.current_part = Some(headers);
+        Ok(true)
+    }
+
+    /// ไฟล์ของ part ปัจจุบัน (`None` ถ้าเป็น field หรือ part ที่ข้าม)
+    pub(crate) fn current_file(&self) -> Option<NewFile<'_>> {
+        let part = self.current_part.as_ref()?;
+        let PartType::File { content_type, .. } = &part.part_type else {
+            return None;
+        };
+        Some(NewFile {
+            field_name: &part.name,
+            filename: &self.current_name,
+            content_type,
+        })
+    }
+
+    /// decode `Content-Transfer-Encoding` (ลงใน `buffer` ถ้าต้อง decode) แล้วนับขนาด part
+    /// (ไม่เกิน limit ของไฟล์หรือ field) และเก็บค่าถ้าเป็น field ธรรมดา คืนข้อมูลที่ต้องเขียน
+    pub(crate) fn part_data<'a>(&mut self, data: &'a [u8], buffer: &'a mut Vec<u8>) -> Result<&'a [u8], MultipartError> {
+        let Some(part) = &self.current_part else {
+            return Ok(data);
+        };
+        let data = match &mut self.decoder {
+            Some(decoder) => {
+                buffer.clear();
+                decoder.decode(data, buffer)?;
+                buffer.as_slice()
+            }
+            None => data,
+        };
+        self.part_size += data.len();
+
+        if part.filename().is_some() {
+            check_limit(self.part_size, self.limits.max_file_size, "file size")?;
+            for hasher in &mut self.hashers {
+                hasher.update(data);
+            }
+        } else {
+            check_limit(self.part_size, self.limits.max_field_size, "field size")?;
+            self.field_value.extend_from_slice(data);
+        }
+        Ok(data)
+    }
+
+    /// จบ part ปัจจุบัน: ถ้าเป็น field เก็บค่าไว้ใน stats ถ้าเป็นไฟล์คืน [`PendingFile`]
+    /// ที่ตั้งชื่อตาม [`NamingStrategy`] แล้ว ให้ผู้เรียก commit แล้วเรียก [`committed`](Self::committed)
+    ///
+    /// ถ้า digest ไม่ตรงกับที่ client ส่งมาคืน [`MultipartError::DigestMismatch`]
+    /// ผู้เรียกต้องทิ้งข้อมูลที่เขียนไปแล้วเอง
+    pub(crate) fn end_part(&mut self) -> Result<Option<PendingFile>, MultipartError> {
+        let Some(part) = self.current_part.take() else {
+            return Ok(None);
+        };
+        if let Some(mut decoder) = self.decoder.take() {
+            decoder.finish()?;
+        }
+
+        match part.part_type {
+            PartType::File { filename, content_type } => {
+                let digests: Vec<_> = self
+                    .hashers
+                    .drain(..)
+                    .map(|hasher| (hasher.algorithm(), hasher.finalize()))
+                    .collect();
+                verify_digests(&self.expected_digests, &digests)?;
+
+                let ext = filename::extension(&self.current_name);
+                let name = match self.naming {
+                    NamingStrategy::Original | NamingStrategy::SuffixOnCollision => self.current_name.clone(),
+                    NamingStrategy::Uuid => format!("{}{}", filename::uuid_v4(), ext),
+                    NamingStrategy::ContentHash => {
+                        let (_, hash) = digests
+                            .iter()
+                            .find(|(algorithm, _)| *algorithm == DigestAlgorithm::Sha256)
+                            .expect("sha-256 is always computed for content-hash naming");
+                        format!("{}{}", to_hex(hash), ext)
+                    }
+                };
+
+                Ok(Some(PendingFile {
+                    no_clobber: self.naming == NamingStrategy::SuffixOnCollision,
+                    info: FileInfo {
+                        field_name: part.name,
+                        filename: String::new(),
+                        path: String::new(),
+                        original_filename: filename,
+                        content_type,
+                        size: self.part_size,
+                        headers: part.headers,
+                        digests,
+                    },
+                    name,
+                }))
+            }
+            PartType::Field => {
+                let value = String::from_utf8_lossy(&self.field_value).into_owned();
+                // field แบบ S3 POST: `Content-MD5` ที่ส่งมาก่อนไฟล์ใช้ตรวจไฟล์ถัดไป
+                // ค่าที่อ่านไม่ได้ถือเป็น field ธรรมดา (form อาจมี field ชื่อ `digest` ของตัวเอง)
+                if self.verify_digests
+                    && let Ok(digests) = digest::parse_digest_header(&part.name, &value)
+                {
+                    self.field_digests.extend(digests);
+                }
+                self.stats.fields.push((part.name, value));
+                self.field_value.clear();
+                Ok(None)
+            }
+        }
+    }
+
+    /// เตรียม hasher ของไฟล์ที่กำลังจะเริ่ม: algorithm ที่เลือกไว้ ที่ client ให้ digest มา
+    /// (จาก header ของ part หรือ field ก่อนหน้า) และ SHA-256 ถ้าตั้งชื่อตามเนื้อหา
+    fn start_hashers(&mut self, headers: &PartHeaders) -> Result<(), MultipartError> {
+        if self.verify_digests {
+            self.expected_digests = std::mem::take(&mut self.field_digests);
+            for name in ["content-md5", "digest", "repr-digest"] {
+                if let Some(value) = headers.header(name) {
+                    self.expected_digests.extend(digest::parse_digest_header(name, value)?);
+                }
+            }
+        }
+
+        let mut algorithms = self.digests.clone();
+        algorithms.extend(self.expected_digests.iter().map(|(algorithm, _)| *algorithm));
+        if self.naming == NamingStrategy::ContentHash {
+            algorithms.push(DigestAlgorithm::Sha256);
+        }
+        for algorithm in algorithms {
+            if !self.hashers.iter().any(|hasher| hasher.algorithm() == algorithm) {
+                self.hashers.push(Hasher::new(algorithm));
+            }
+        }
+        Ok(())
+    }
+
+    /// ไฟล์ถูก commit แล้ว บันทึก [`FileInfo`] พร้อมชื่อและที่อยู่ที่ใช้จริง
+    pub(crate) fn committed(&mut self, mut info: FileInfo, stored: StoredFile) {
+        info.filename = stored.name;
+        info.path = stored.location;
+        self.stats.files_saved.push(info);
+    }
+
+    /// ทิ้ง part ปัจจุบัน
+    pub(crate) fn abort_part(&mut self) {
+        self.current_part = None;
+        self.hashers.clear();
+        self.decoder = None;
+    }
+}
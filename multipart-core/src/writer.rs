@@ -0,0 +1,307 @@
+//! สร้าง body แบบ `multipart/*` (ฝั่งตรงข้ามของ [`StreamingParser`](crate::StreamingParser))
+//!
+//! ใช้ได้ทั้งเป็น client ที่ส่ง `multipart/form-data` (เช่นใน test) และเป็น response แบบ
+//! `multipart/byteranges` ของ download ที่ขอหลายช่วง
+//!
+//! - boundary สุ่มใหม่ทุกตัว (RFC 2046: ไม่เกิน 70 ตัว ใช้แค่ตัวอักษร ตัวเลข และ `-`) จึงไม่ต้อง
+//!   scan เนื้อหาก่อนว่ามี boundary ซ้ำหรือไม่
+//! - `name` และ `filename` ใส่ quote แบบ browser (`"` → `%22`, CR/LF → `%0D`/`%0A`) ชื่อไฟล์ที่ไม่ใช่ ASCII
+//!   ส่ง `filename*=UTF-8''...` (RFC 5987) คู่กับ `filename` ที่เป็น ASCII ล้วนสำหรับ server รุ่นเก่า
+//! - ข้อมูลของแต่ละ part อ่านจาก [`Read`] ตอนเขียนจริง ไม่ต้องโหลดไฟล์ทั้งไฟล์เข้า memory
+//! - รู้ขนาดของทุก part → [`content_length`](MultipartWriter::content_length) คำนวณ `Content-Length` ได้ก่อนเขียน
+//!   ไม่งั้นผู้เรียกต้องส่งแบบ `Transfer-Encoding: chunked`
+//!
+//! ```
+//! use multipart_core::MultipartWriter;
+//!
+//! let mut writer = MultipartWriter::new();
+//! writer.add_field("title", "สวัสดี");
+//! writer.add_file("doc", "รายงาน.txt", "text/plain", &b"hello"[..], Some(5))?;
+//!
+//! let content_length = writer.content_length();
+//! let mut body = Vec::new();
+//! writer.write_to(&mut body)?;
+//! assert_eq!(content_length, Some(body.len() as u64));
+//! # Ok::<(), multipart_core::MultipartError>(())
+//! ```
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use crate::error::MultipartError;
+use crate::filename::random_u64;
+use crate::mime::{is_token_char, validate_boundary};
+use crate::subtype::{ContentRange, Subtype};
+
+/// ขนาดของ buffer ตอน copy ข้อมูลจาก source
+const COPY_BUFFER_SIZE: usize = 64 * 1024;
+
+/// source ของข้อมูลใน part ที่อ่านตอนเขียน
+type Source = Box<dyn Read + Send>;
+
+/// part ที่รอเขียน
+struct Part {
+    /// header ทุกบรรทัดของ part (ลงท้ายด้วย CRLF ทุกบรรทัด)
+    headers: String,
+    source: Source,
+    /// ขนาดของข้อมูลถ้ารู้ล่วงหน้า
+    len: Option<u64>,
+}
+
+/// ตัวสร้าง body แบบ multipart เพิ่ม part ด้วย `add_*` แล้วเขียนออกด้วย [`write_to`](Self::write_to)
+pub struct MultipartWriter {
+    boundary: String,
+    subtype: Subtype,
+    parts: Vec<Part>,
+}
+
+impl Default for MultipartWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MultipartWriter {
+    /// `multipart/form-data` กับ boundary สุ่มใหม่
+    pub fn new() -> Self {
+        Self {
+            boundary: format!("----MultipartCoreBoundary{:016x}{:016x}", random_u64(), random_u64()),
+            subtype: Subtype::FormData,
+            parts: Vec::new(),
+        }
+    }
+
+    /// เปลี่ยน subtype เช่น [`Subtype::ByteRanges`] สำหรับ response ที่ตอบหลายช่วง
+    pub fn with_subtype(mut self, subtype: Subtype) -> Self {
+        self.subtype = subtype;
+        self
+    }
+
+    /// ใช้ boundary ที่กำหนดเอง (ตรวจตาม RFC 2046)
+    ///
+    /// ผู้เรียกต้องรับประกันเองว่า boundary ไม่อยู่ในข้อมูลของ part ใด
+    pub fn with_boundary(mut self, boundary: &str) -> Result<Self, MultipartError> {
+        validate_boundary(boundary)?;
+        self.boundary = boundary.to_string();
+        Ok(self)
+    }
+
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    pub fn subtype(&self) -> &Subtype {
+        &self.subtype
+    }
+
+    /// ค่าของ header `Content-Type` เช่น `multipart/form-data; boundary=...`
+    pub fn content_type(&self) -> String {
+        // boundary ที่มี tspecials (RFC 2045) เช่น `:` `/` `=` หรือ space ต้องอยู่ใน quote
+        if self.boundary.bytes().all(|b| b.is_ascii_alphanumeric() || b"'+-._".contains(&b)) {
+            format!("{}; boundary={}", self.subtype, self.boundary)
+        } else {
+            format!("{}; boundary=\"{}\"", self.subtype, self.boundary)
+        }
+    }
+
+    /// จำนวน part ที่เพิ่มไว้
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// เพิ่ม text field (`Content-Disposition: form-data; name="..."`)
+    pub fn add_field(&mut self, name: &str, value: &str) -> &mut Self {
+        let headers = format!("Content-Disposition: form-data; name={}\r\n", quote(name));
+        let value = value.as_bytes().to_vec();
+        let len = value.len() as u64;
+        self.push(headers, Box::new(io::Cursor::new(value)), Some(len));
+        self
+    }
+
+    /// เพิ่มไฟล์ที่อ่านจาก `source` ตอนเขียน
+    ///
+    /// `len` คือขนาดของข้อมูลถ้ารู้ (`None` ทำให้ [`content_length`](Self::content_length) เป็น `None`)
+    /// ถ้าให้มาแล้ว source อ่านได้ไม่ตรงกับขนาดนี้ [`write_to`](Self::write_to) จะ error
+    pub fn add_file<R: Read + Send + 'static>(
+        &mut self,
+        name: &str,
+        filename: &str,
+        content_type: &str,
+        source: R,
+        len: Option<u64>,
+    ) -> Result<&mut Self, MultipartError> {
+        check_header_value(content_type)?;
+        let headers = format!(
+            "Content-Disposition: form-data; name={}; {}\r\nContent-Type: {}\r\n",
+            quote(name),
+            filename_params(filename),
+            content_type
+        );
+        self.push(headers, Box::new(source), len);
+        Ok(self)
+    }
+
+    /// เพิ่มไฟล์จาก path (ใช้ชื่อไฟล์ของ path และขนาดจาก metadata)
+    ///
+    /// เปิดไฟล์ทันทีเพื่อให้ error ตั้งแต่ตอนเพิ่ม ไม่ใช่กลาง body
+    pub fn add_file_path(
+        &mut self,
+        name: &str,
+        path: impl AsRef<Path>,
+        content_type: &str,
+    ) -> Result<&mut Self, MultipartError> {
+        let path = path.as_ref();
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| MultipartError::InvalidFilename(format!("{} has no file name", path.display())))?;
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        self.add_file(name, &filename, content_type, file, Some(len))
+    }
+
+    /// เพิ่ม part ที่กำหนด header เองทั้งหมด เช่น part ของ `multipart/mixed` หรือ `related`
+    ///
+    /// ชื่อ header ต้องเป็น token และค่าต้องไม่มี CR/LF (กัน header injection)
+    pub fn add_part<R: Read + Send + 'static>(
+        &mut self,
+        headers: &[(&str, &str)],
+        source: R,
+        len: Option<u64>,
+    ) -> Result<&mut Self, MultipartError> {
+        let mut lines = String::new();
+        for (name, value) in headers {
+            if name.is_empty() || !name.bytes().all(is_token_char) {
+                return Err(MultipartError::MalformedPartHeaders(format!("invalid header name {:?}", name)));
+            }
+            check_header_value(value)?;
+            lines.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        self.push(lines, Box::new(source), len);
+        Ok(self)
+    }
+
+    /// เพิ่มช่วงหนึ่งของ resource สำหรับ `multipart/byteranges` (RFC 9110 §14.6)
+    ///
+    /// `source` ต้องอ่านได้ [`ContentRange::length`] bytes พอดี (เช่นไฟล์ที่ seek ไปที่ `first` แล้ว `take`)
+    pub fn add_range<R: Read + Send + 'static>(
+        &mut self,
+        content_type: &str,
+        range: ContentRange,
+        source: R,
+    ) -> Result<&mut Self, MultipartError> {
+        let complete_length = range.complete_length.map_or("*".to_string(), |length| length.to_string());
+        let content_range = format!("bytes {}-{}/{}", range.first, range.last, complete_length);
+        self.add_part(
+            &[("Content-Type", content_type), ("Content-Range", &content_range)],
+            source,
+            Some(range.length()),
+        )
+    }
+
+    /// ขนาดของ body ทั้งหมด (`None` ถ้ามี part ที่ไม่รู้ขนาด)
+    pub fn content_length(&self) -> Option<u64> {
+        let mut total = close_delimiter(&self.boundary, self.parts.is_empty()).len() as u64;
+        for (index, part) in self.parts.iter().enumerate() {
+            total += part_head(&self.boundary, index, &part.headers).len() as u64 + part.len?;
+        }
+        Some(total)
+    }
+
+    /// เขียน body ทั้งหมดลง `out` แบบ stream คืนจำนวน bytes ที่เขียน
+    ///
+    /// source ที่อ่านได้ไม่ตรงกับขนาดที่บอกไว้ทำให้ error เพราะ `Content-Length` ที่ส่งไปแล้วจะผิด
+    pub fn write_to<W: Write>(self, out: &mut W) -> Result<u64, MultipartError> {
+        let close = close_delimiter(&self.boundary, self.parts.is_empty());
+        let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+        let mut written = 0;
+        for (index, mut part) in self.parts.into_iter().enumerate() {
+            let head = part_head(&self.boundary, index, &part.headers);
+            out.write_all(head.as_bytes())?;
+            let copied = copy(&mut part.source, out, &mut buffer, part.len)?;
+            if let Some(len) = part.len
+                && copied != len
+            {
+                return Err(MultipartError::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("part {} has {} bytes but {} were declared", index, copied, len),
+                )));
+            }
+            written += head.len() as u64 + copied;
+        }
+        out.write_all(close.as_bytes())?;
+        out.flush()?;
+        Ok(written + close.len() as u64)
+    }
+
+    fn push(&mut self, headers: String, source: Source, len: Option<u64>) {
+        self.parts.push(Part { headers, source, len });
+    }
+}
+
+/// delimiter กับ header ของ part ลำดับที่ `index` (ถึงบรรทัดว่างก่อนข้อมูล)
+fn part_head(boundary: &str, index: usize, headers: &str) -> String {
+    // CRLF หน้า delimiter เป็นของ delimiter ไม่ใช่ของข้อมูล part ก่อนหน้า (RFC 2046 §5.1.1)
+    let separator = if index == 0 { "" } else { "\r\n" };
+    format!("{}--{}\r\n{}\r\n", separator, boundary, headers)
+}
+
+fn close_delimiter(boundary: &str, no_parts: bool) -> String {
+    let separator = if no_parts { "" } else { "\r\n" };
+    format!("{}--{}--\r\n", separator, boundary)
+}
+
+/// copy จาก `source` ไป `out` คืนจำนวน bytes ที่อ่านได้
+///
+/// ถ้ารู้ขนาด อ่านเกินไป 1 byte พอให้รู้ว่า source ยาวกว่าที่บอกไว้ (byte นั้นไม่ถูกเขียน)
+fn copy<W: Write>(source: &mut Source, out: &mut W, buffer: &mut [u8], len: Option<u64>) -> io::Result<u64> {
+    let mut source = source.take(len.map_or(u64::MAX, |len| len.saturating_add(1)));
+    let mut copied = 0;
+    loop {
+        let n = match source.read(buffer) {
+            Ok(0) => return Ok(copied),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let keep = len.map_or(n as u64, |len| len.saturating_sub(copied).min(n as u64));
+        out.write_all(&buffer[..keep as usize])?;
+        copied += n as u64;
+    }
+}
+
+/// ค่าใน quote แบบที่ browser ส่ง (HTML spec: `"` → `%22`, CR → `%0D`, LF → `%0A`)
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "%22").replace('\r', "%0D").replace('\n', "%0A"))
+}
+
+/// พารามิเตอร์ชื่อไฟล์ ชื่อที่ไม่ใช่ ASCII ได้ `filename*` (RFC 5987) เพิ่มคู่กับ `filename` ที่แทนตัวอักษรนั้นด้วย `_`
+fn filename_params(filename: &str) -> String {
+    if filename.is_ascii() {
+        return format!("filename={}", quote(filename));
+    }
+
+    let fallback: String = filename.chars().map(|c| if c.is_ascii() { c } else { '_' }).collect();
+    let mut encoded = String::with_capacity(filename.len() * 3);
+    for byte in filename.bytes() {
+        // attr-char ของ RFC 5987 ไม่ต้อง encode
+        if byte.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    format!("filename={}; filename*=UTF-8''{}", quote(&fallback), encoded)
+}
+
+fn check_header_value(value: &str) -> Result<(), MultipartError> {
+    if value.bytes().any(|b| matches!(b, b'\r' | b'\n' | 0)) {
+        return Err(MultipartError::MalformedPartHeaders(format!("invalid header value {:?}", value)));
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//! Tests ของ `Transfer-Encoding: chunked` decoder
+//!
+//! feed ทุกขนาด chunk เหมือน delimiter tests เพื่อให้บรรทัด chunk-size, CRLF
+//! และ trailer ถูกตัดขาดทุกตำแหน่ง
+
+use multipart_core::{ChunkedDecoder, ChunkedEvent, MultipartError};
+
+#[derive(Debug, PartialEq)]
+struct Decoded {
+    body: Vec<u8>,
+    sizes: Vec<u64>,
+    trailers: Vec<(String, String)>,
+    remaining: Vec<u8>,
+}
+
+fn decode_in_chunks(input: &[u8], chunk_size: usize) -> Result<Decoded, MultipartError> {
+    let mut decoder = ChunkedDecoder::new();
+    let mut decoded = Decoded {
+        body: Vec::new(),
+        sizes: Vec::new(),
+        trailers: Vec::new(),
+        remaining: Vec::new(),
+    };
+
+    let mut handle = |decoder: &mut ChunkedDecoder| -> Result<(), MultipartError> {
+        while let Some(event) = decoder.next_event()? {
+            match event {
+                ChunkedEvent::ChunkStart { size, .. } => decoded.sizes.push(size),
+                ChunkedEvent::Data(data) => decoded.body.extend_from_slice(data),
+                ChunkedEvent::Trailer { name, value } => decoded.trailers.push((name, value)),
+                ChunkedEvent::ChunkEnd | ChunkedEvent::Finished => {}
+            }
+        }
+        Ok(())
+    };
+
+    for chunk in input.chunks(chunk_size) {
+        decoder.feed(chunk);
+        handle(&mut decoder)?;
+    }
+    decoder.end_of_input();
+    handle(&mut decoder)?;
+
+    assert!(decoder.is_finished());
+    decoded.remaining = decoder.remaining().to_vec();
+    Ok(decoded)
+}
+
+fn decode_every_split(input: &[u8]) -> Result<Decoded, MultipartError> {
+    let expected = decode_in_chunks(input, input.len().max(1));
+    for chunk_size in 1..input.len() {
+        let actual = decode_in_chunks(input, chunk_size);
+        match (&expected, &actual) {
+            (Ok(e), Ok(a)) => assert_eq!(e, a, "chunk size {}", chunk_size),
+            (Err(e), Err(a)) => assert_eq!(e.to_string(), a.to_string(), "chunk size {}", chunk_size),
+            _ => panic!("chunk size {}: {:?} vs {:?}", chunk_size, expected, actual),
+        }
+    }
+    expected
+}
+
+#[test]
+fn decodes_chunks_and_trailers() {
+    let input = b"5\r\nhello\r\n7;ext=\"x\"\r\n, world\r\n0\r\nX-Checksum: abc\r\nX-Other:1\r\n\r\n";
+    let decoded = decode_every_split(input).unwrap();
+
+    assert_eq!(decoded.body, b"hello, world");
+    assert_eq!(decoded.sizes, vec![5, 7, 0]);
+    assert_eq!(
+        decoded.trailers,
+        vec![
+            ("X-Checksum".to_string(), "abc".to_string()),
+            ("X-Other".to_string(), "1".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn accepts_uppercase_hex_and_whitespace_before_extension() {
+    let mut input = b"1A ;name\r\n".to_vec();
+    input.extend_from_slice(&[b'x'; 26]);
+    input.extend_from_slice(b"\r\n0\r\n\r\n");
+
+    let decoded = decode_every_split(&input).unwrap();
+    assert_eq!(decoded.body, vec![b'x'; 26]);
+}
+
+#[test]
+fn keeps_bytes_after_last_chunk() {
+    let decoded = decode_in_chunks(b"3\r\nabc\r\n0\r\n\r\nGET / HTTP/1.1\r\n", 4).unwrap();
+    assert_eq!(decoded.body, b"abc");
+    assert_eq!(decoded.remaining, b"GET / HTTP/1.1\r\n");
+}
+
+#[test]
+fn rejects_invalid_chunk_size() {
+    for input in [
+        &b"zz\r\nabc\r\n0\r\n\r\n"[..],
+        b"\r\nabc\r\n0\r\n\r\n",
+        b"-1\r\nabc\r\n0\r\n\r\n",
+        b"0x3\r\nabc\r\n0\r\n\r\n",
+        b"1ffffffffffffffff\r\n",
+    ] {
+        let err = decode_every_split(input).unwrap_err();
+        assert!(matches!(err, MultipartError::InvalidChunkedEncoding(_)), "{:?}", err);
+        assert_eq!(err.status().0, 400);
+    }
+}
+
+#[test]
+fn rejects_missing_crlf_after_data() {
+    let err = decode_every_split(b"3\r\nabcd\r\n0\r\n\r\n").unwrap_err();
+    assert!(matches!(err, MultipartError::InvalidChunkedEncoding(_)));
+}
+
+#[test]
+fn rejects_malformed_trailer() {
+    let err = decode_every_split(b"0\r\nnot a header\r\n\r\n").unwrap_err();
+    assert!(matches!(err, MultipartError::InvalidChunkedEncoding(_)));
+}
+
+#[test]
+fn rejects_truncated_body() {
+    for input in [&b"5\r\nhel"[..], b"5\r\nhello\r\n", b"0\r\n", b"0\r\nX: 1\r\n"] {
+        let err = decode_every_split(input).unwrap_err();
+        assert!(matches!(err, MultipartError::InvalidChunkedEncoding(_)), "{:?}", err);
+    }
+}
+
+#[test]
+fn rejects_overlong_size_line() {
+    let mut input = b"1;".to_vec();
+    input.extend_from_slice(&[b'a'; 8192]);
+
+    let mut decoder = ChunkedDecoder::new();
+    decoder.feed(&input);
+    assert!(decoder.next_event().is_err());
+}
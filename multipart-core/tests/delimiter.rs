@@ -0,0 +1,263 @@
+//! Regression tests ของการหา delimiter ตาม RFC 2046
+//!
+//! ทุก case จะถูก feed ทุกขนาด chunk ตั้งแต่ 1 byte จนถึงทั้ง body
+//! เพื่อให้ delimiter ถูกตัดขาดทุกตำแหน่งที่เป็นไปได้
+
+use multipart_core::{Event, MultipartError, StreamingParser};
+
+type Parts = Vec<(String, Vec<u8>)>;
+
+fn parse_in_chunks(body: &[u8], chunk_size: usize) -> Result<Parts, MultipartError> {
+    let mut parser = StreamingParser::new("--b")?;
+    let mut parts: Parts = Vec::new();
+    let mut finished = false;
+
+    let mut handle = |parser: &mut StreamingParser| -> Result<(), MultipartError> {
+        while let Some(event) = parser.next_event()? {
+            match event {
+                Event::PartStart { headers } => parts.push((headers.name, Vec::new())),
+                Event::PartData(data) => parts.last_mut().unwrap().1.extend_from_slice(data),
+                Event::PartEnd => {}
+                Event::Finished => finished = true,
+            }
+        }
+        Ok(())
+    };
+
+    for chunk in body.chunks(chunk_size) {
+        parser.feed(chunk);
+        handle(&mut parser)?;
+    }
+    parser.end_of_input();
+    handle(&mut parser)?;
+
+    assert!(finished);
+    assert!(parser.is_complete());
+    Ok(parts)
+}
+
+/// parse ทุกขนาด chunk แล้วตรวจว่าได้ผลเหมือนกันหมด
+fn parse_every_split(body: &[u8]) -> Result<Parts, MultipartError> {
+    let expected = parse_in_chunks(body, body.len().max(1));
+    for chunk_size in 1..body.len() {
+        let actual = parse_in_chunks(body, chunk_size);
+        match (&expected, &actual) {
+            (Ok(e), Ok(a)) => assert_eq!(e, a, "chunk size {}", chunk_size),
+            (Err(e), Err(a)) => assert_eq!(e.to_string(), a.to_string(), "chunk size {}", chunk_size),
+            _ => panic!("chunk size {}: {:?} vs {:?}", chunk_size, expected, actual),
+        }
+    }
+    expected
+}
+
+fn part(name: &str, data: &[u8]) -> (String, Vec<u8>) {
+    (name.to_string(), data.to_vec())
+}
+
+#[test]
+fn parses_simple_body() {
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+        hello\r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"f\"; filename=\"x.bin\"\r\n\
+        Content-Type: application/octet-stream\r\n\r\n\
+        \x00\x01\x02\r\n\
+        --b--\r\n";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(parts, vec![part("a", b"hello"), part("f", b"\x00\x01\x02")]);
+}
+
+#[test]
+fn boundary_mid_line_is_data() {
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+        x--b\r\ny --b--\r\n\
+        --b--";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(parts, vec![part("a", b"x--b\r\ny --b--")]);
+}
+
+#[test]
+fn boundary_followed_by_other_text_is_data() {
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+        one\r\n--bc\r\ntwo\r\n\
+        --b--";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(parts, vec![part("a", b"one\r\n--bc\r\ntwo")]);
+}
+
+#[test]
+fn lf_only_line_ending_is_not_a_delimiter() {
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+        line\n--b\nmore\r\n\
+        --b--";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(parts, vec![part("a", b"line\n--b\nmore")]);
+}
+
+#[test]
+fn keeps_trailing_cr_and_lf_in_data() {
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+        data\r\n\r\r\n\
+        --b--";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(parts, vec![part("a", b"data\r\n\r")]);
+}
+
+#[test]
+fn empty_part_data() {
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+        \r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"b\"\r\n\r\n\
+        \r\n\
+        --b--";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(parts, vec![part("a", b""), part("b", b"")]);
+}
+
+#[test]
+fn tolerates_transport_padding() {
+    let body = b"--b \t \r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+        1\r\n\
+        --b\t\r\n\
+        Content-Disposition: form-data; name=\"b\"\r\n\r\n\
+        2\r\n\
+        --b--  \r\n";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(parts, vec![part("a", b"1"), part("b", b"2")]);
+}
+
+#[test]
+fn skips_preamble() {
+    let body = b"This is the preamble --b not at line start\r\n\
+        --bogus line\r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+        value\r\n\
+        --b--";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(parts, vec![part("a", b"value")]);
+}
+
+#[test]
+fn ignores_epilogue() {
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\r\n\
+        value\r\n\
+        --b--\r\n\
+        epilogue\r\n--b\r\nContent-Disposition: form-data; name=\"x\"\r\n\r\nignored";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(parts, vec![part("a", b"value")]);
+}
+
+#[test]
+fn close_delimiter_without_parts() {
+    let parts = parse_every_split(b"preamble\r\n--b--\r\n").unwrap();
+    assert!(parts.is_empty());
+}
+
+#[test]
+fn truncated_body_is_unexpected_eof() {
+    let truncated: [&[u8]; 4] = [
+        b"",
+        b"--b\r\nContent-Disposition: form-data; name=\"a\"\r\n",
+        b"--b\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\npartial data",
+        b"--b\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\ndata\r\n--b",
+    ];
+
+    for body in truncated {
+        let err = parse_every_split(body).unwrap_err();
+        assert!(matches!(err, MultipartError::UnexpectedEof), "{:?}", err);
+    }
+}
+
+#[test]
+fn descends_into_nested_multipart_mixed() {
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+        hi\r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"attachments\"\r\n\
+        Content-Type: multipart/mixed; boundary=inner\r\n\r\n\
+        inner preamble\r\n\
+        --inner\r\n\
+        Content-Disposition: file; filename=\"a.txt\"\r\n\
+        Content-Type: text/plain\r\n\r\n\
+        AAA --inner mid-line\r\n\
+        --inner\r\n\
+        Content-Type: text/plain\r\n\r\n\
+        BBB\r\n\
+        --inner--\r\n\
+        inner epilogue\r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"after\"\r\n\r\n\
+        x\r\n\
+        --b--";
+
+    let parts = parse_every_split(body).unwrap();
+    assert_eq!(
+        parts,
+        vec![
+            part("title", b"hi"),
+            part("attachments[0]", b"AAA --inner mid-line"),
+            part("attachments[1]", b"BBB"),
+            part("after", b"x"),
+        ]
+    );
+
+    // multipart/mixed ที่ว่างเปล่าไม่ปล่อย part ใดเลย
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"empty\"\r\n\
+        Content-Type: multipart/mixed; boundary=\"in ner\"\r\n\r\n\
+        --in ner--\r\n\
+        --b--";
+    assert!(parse_every_split(body).unwrap().is_empty());
+}
+
+#[test]
+fn nested_body_errors() {
+    // ซ้อนสองชั้นเกิน default (1 ชั้น)
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\
+        Content-Type: multipart/mixed; boundary=x\r\n\r\n\
+        --x\r\n\
+        Content-Type: multipart/mixed; boundary=y\r\n\r\n\
+        --y--\r\n\
+        --x--\r\n\
+        --b--";
+    let err = parse_every_split(body).unwrap_err();
+    assert!(matches!(err, MultipartError::LimitExceeded { limit: "nesting depth", max: 1 }), "{:?}", err);
+
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\
+        Content-Type: multipart/mixed\r\n\r\n\
+        --b--";
+    let err = parse_every_split(body).unwrap_err();
+    assert!(matches!(err, MultipartError::MissingBoundary), "{:?}", err);
+
+    // body หลักจบก่อน closing delimiter ของชั้นใน
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"a\"\r\n\
+        Content-Type: multipart/mixed; boundary=x\r\n\r\n\
+        --x\r\n\r\n\
+        data\r\n\
+        --x--";
+    let err = parse_every_split(body).unwrap_err();
+    assert!(matches!(err, MultipartError::UnexpectedEof), "{:?}", err);
+}
@@ -0,0 +1,137 @@
+//! Tests ของ hash แบบ streaming กับ test vector มาตรฐาน
+
+use multipart_core::MultipartError;
+use multipart_core::digest::{
+    Crc32c, DigestAlgorithm, Hasher, Md5, Sha256, hmac_sha256, parse_digest_header, to_base64, to_hex,
+};
+
+#[test]
+fn sha256_test_vectors() {
+    // FIPS 180-4 examples
+    assert_eq!(
+        to_hex(&Sha256::digest(b"")),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        to_hex(&Sha256::digest(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        to_hex(&Sha256::digest(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+}
+
+#[test]
+fn sha256_streams_across_any_split() {
+    let data: Vec<u8> = (0..1000u32).map(|i| (i * 31 % 251) as u8).collect();
+    let expected = Sha256::digest(&data);
+
+    for chunk_size in [1, 7, 55, 56, 63, 64, 65, 128, 999] {
+        let mut hasher = Sha256::new();
+        for chunk in data.chunks(chunk_size) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), expected, "chunk size {}", chunk_size);
+    }
+
+    let million_a = vec![b'a'; 1_000_000];
+    assert_eq!(
+        to_hex(&Sha256::digest(&million_a)),
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+    );
+}
+
+#[test]
+fn md5_and_crc32c_test_vectors() {
+    // RFC 1321 §A.5
+    assert_eq!(to_hex(&Md5::digest(b"")), "d41d8cd98f00b204e9800998ecf8427e");
+    assert_eq!(to_hex(&Md5::digest(b"abc")), "900150983cd24fb0d6963f7d28e17f72");
+    assert_eq!(to_hex(&Md5::digest(b"message digest")), "f96b697d7cb7938d525a2f31aaf161d0");
+    assert_eq!(
+        to_hex(&Md5::digest(
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"
+        )),
+        "57edf4a22be3c955ac49da2e2107b67a"
+    );
+
+    // check value ของ CRC-32C และตัวอย่างใน IANA registry
+    assert_eq!(Crc32c::digest(b"123456789"), 0xe3069283u32.to_be_bytes());
+    assert_eq!(Crc32c::digest(b"dog"), 0x0a72a4dfu32.to_be_bytes());
+    assert_eq!(Crc32c::digest(b""), [0; 4]);
+
+    let data: Vec<u8> = (0..1000u32).map(|i| (i * 31 % 251) as u8).collect();
+    for algorithm in [DigestAlgorithm::Sha256, DigestAlgorithm::Md5, DigestAlgorithm::Crc32c] {
+        let mut whole = Hasher::new(algorithm);
+        whole.update(&data);
+        let expected = whole.finalize();
+        assert_eq!(expected.len(), algorithm.output_len());
+
+        for chunk_size in [1, 3, 63, 64, 65, 999] {
+            let mut hasher = Hasher::new(algorithm);
+            for chunk in data.chunks(chunk_size) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(), expected, "{} chunk size {}", algorithm, chunk_size);
+        }
+    }
+}
+
+#[test]
+fn parses_client_supplied_digests() {
+    let md5 = Md5::digest(b"hello").to_vec();
+    let sha = Sha256::digest(b"hello").to_vec();
+    let crc = Crc32c::digest(b"hello").to_vec();
+    assert_eq!(to_base64(&md5), "XUFAKrxLKna5cZ2REBfFkg==");
+
+    assert_eq!(
+        parse_digest_header("Content-MD5", "XUFAKrxLKna5cZ2REBfFkg==").unwrap(),
+        vec![(DigestAlgorithm::Md5, md5.clone())]
+    );
+    assert_eq!(
+        parse_digest_header(
+            "Digest",
+            &format!("SHA-256={}, crc32c={}, unixsum=30637", to_base64(&sha), to_hex(&crc))
+        )
+        .unwrap(),
+        vec![(DigestAlgorithm::Sha256, sha.clone()), (DigestAlgorithm::Crc32c, crc.clone())]
+    );
+    assert_eq!(
+        parse_digest_header(
+            "repr-digest",
+            &format!("sha-512=:AAAA:, md5=:{}:;x=1, crc32c=:{}:", to_base64(&md5), to_base64(&crc))
+        )
+        .unwrap(),
+        vec![(DigestAlgorithm::Md5, md5), (DigestAlgorithm::Crc32c, crc)]
+    );
+    assert_eq!(parse_digest_header("X-Other", "md5=abc").unwrap(), vec![]);
+
+    for (name, value) in [
+        ("Content-MD5", "not base64!"),
+        ("Content-MD5", "AAAA"),
+        ("Digest", "md5"),
+        ("Digest", "crc32c=xyz"),
+        ("Repr-Digest", "sha-256=abc"),
+    ] {
+        let err = parse_digest_header(name, value).unwrap_err();
+        assert!(matches!(err, MultipartError::InvalidDigest(_)), "{} {:?}: {:?}", name, value, err);
+    }
+}
+
+#[test]
+fn hmac_sha256_test_vectors() {
+    // RFC 4231 test case 1 และ 2
+    assert_eq!(
+        to_hex(&hmac_sha256(&[0x0b; 20], b"Hi There")),
+        "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+    );
+    assert_eq!(
+        to_hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    // key ยาวกว่า block (RFC 4231 test case 6)
+    assert_eq!(
+        to_hex(&hmac_sha256(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First")),
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+    );
+}
@@ -0,0 +1,123 @@
+//! Tests ของ `Content-Disposition` จาก client จริงแต่ละเจ้า
+
+use multipart_core::{ContentDisposition, MultipartError, PartHeaders, PartType};
+
+fn parse(value: &str) -> ContentDisposition {
+    ContentDisposition::parse(value).unwrap()
+}
+
+#[test]
+fn parses_chrome_style() {
+    // Chrome ส่งชื่อไทยเป็น UTF-8 ตรงๆ และแทน `"` ด้วย `%22`
+    let disposition = parse("form-data; name=\"file\"; filename=\"รายงาน ปี 2567.pdf\"");
+    assert!(disposition.is_form_data());
+    assert_eq!(disposition.name.as_deref(), Some("file"));
+    assert_eq!(disposition.resolved_filename(), Some("รายงาน ปี 2567.pdf"));
+
+    let disposition = parse("form-data; name=\"file\"; filename=\"say %22hi%22.txt\"");
+    assert_eq!(disposition.resolved_filename(), Some("say \"hi\".txt"));
+
+    // IE / Edge รุ่นเก่าส่ง path เต็มของ Windows มา `\` ต้องอยู่ครบ
+    let disposition = parse("form-data; name=\"file\"; filename=\"C:\\Users\\me\\photo.jpg\"");
+    assert_eq!(disposition.resolved_filename(), Some("C:\\Users\\me\\photo.jpg"));
+}
+
+#[test]
+fn parses_firefox_and_curl_escapes() {
+    // Firefox รุ่นเก่า escape `"` ด้วย `\`
+    let disposition = parse("form-data; name=\"file\"; filename=\"say \\\"hi\\\".txt\"");
+    assert_eq!(disposition.resolved_filename(), Some("say \"hi\".txt"));
+
+    // curl escape ทั้ง `"` และ `\`
+    let disposition = parse("form-data; name=\"file\"; filename=\"a\\\\b.txt\"");
+    assert_eq!(disposition.resolved_filename(), Some("a\\b.txt"));
+}
+
+#[test]
+fn parses_python_requests_extended_filename() {
+    // urllib3 1.x ส่งชื่อที่ไม่ใช่ ASCII เป็น RFC 2231 อย่างเดียว
+    let disposition = parse("form-data; name=\"upload\"; filename*=utf-8''%E0%B9%84%E0%B8%9F%E0%B8%A5%E0%B9%8C.txt");
+    assert_eq!(disposition.filename, None);
+    assert_eq!(disposition.extended_filename.as_deref(), Some("ไฟล์.txt"));
+    assert_eq!(disposition.resolved_filename(), Some("ไฟล์.txt"));
+
+    // ถ้ามีทั้งสองแบบ ใช้ `filename*`
+    let disposition = parse("attachment; filename=\"fallback.txt\"; filename*=UTF-8'th'%E0%B8%81.txt");
+    assert_eq!(disposition.disposition_type, "attachment");
+    assert_eq!(disposition.filename.as_deref(), Some("fallback.txt"));
+    assert_eq!(disposition.resolved_filename(), Some("ก.txt"));
+
+    let disposition = parse("attachment; filename*=iso-8859-1'en'%E9t%E9.txt");
+    assert_eq!(disposition.resolved_filename(), Some("été.txt"));
+
+    // charset ที่ไม่รองรับให้ fallback ไปที่ `filename`
+    let disposition = parse("attachment; filename=\"plain.txt\"; filename*=koi8-r''%C1.txt");
+    assert_eq!(disposition.resolved_filename(), Some("plain.txt"));
+}
+
+#[test]
+fn joins_rfc2231_continuations() {
+    let disposition = parse("form-data; name=\"f\"; filename*0*=UTF-8''%E0%B8%81; filename*1=\"-100%.txt\"");
+    assert_eq!(disposition.resolved_filename(), Some("ก-100%.txt"));
+
+    let disposition = parse("form-data; name=\"f\"; filename*0=\"long\"; filename*1=\"name.txt\"");
+    assert_eq!(disposition.resolved_filename(), Some("longname.txt"));
+}
+
+#[test]
+fn tolerates_case_and_unquoted_values() {
+    let disposition = parse("Form-Data; NAME=field1 ;FileName=\"x.bin\"");
+    assert!(disposition.is_form_data());
+    assert_eq!(disposition.name.as_deref(), Some("field1"));
+    assert_eq!(disposition.filename.as_deref(), Some("x.bin"));
+
+    let disposition = parse("form-data; name=\"a;b\"");
+    assert_eq!(disposition.name.as_deref(), Some("a;b"));
+    assert_eq!(disposition.resolved_filename(), None);
+}
+
+#[test]
+fn rejects_malformed_dispositions() {
+    for value in [
+        "",
+        "form data; name=\"a\"",
+        "form-data; name=\"a",
+        "form-data; name=\"a\"; name=\"b\"",
+        "form-data; name=\"a\"; filename*=utf-8''%ZZ",
+        // `u8::from_str_radix("+5", 16)` ได้ 5 แต่ `%+5` ไม่ใช่ percent-encoding
+        "form-data; name=\"a\"; filename*=utf-8''%+5.txt",
+        "form-data; name=\"a\"; filename*=utf-8''a%4",
+        "form-data; name=\"a\"; filename*=no-quotes",
+        "form-data; name=\"a\"; filename*=utf-8''%FF",
+    ] {
+        let err = ContentDisposition::parse(value).unwrap_err();
+        assert!(matches!(err, MultipartError::MalformedPartHeaders(_)), "{:?}: {:?}", value, err);
+        assert_eq!(err.status().0, 400);
+    }
+}
+
+#[test]
+fn part_headers_use_disposition() {
+    let headers = PartHeaders::parse(
+        "content-disposition: form-data; name=\"doc\"; filename*=UTF-8''%E0%B8%81.txt\r\nContent-Type: text/plain",
+    )
+    .unwrap();
+    assert_eq!(headers.name, "doc");
+    assert_eq!(
+        headers.part_type,
+        PartType::File {
+            filename: "ก.txt".to_string(),
+            content_type: "text/plain".to_string(),
+        }
+    );
+
+    // `filename=""` (ไม่ได้เลือกไฟล์) ยังนับเป็น part แบบไฟล์
+    let headers = PartHeaders::parse("Content-Disposition: form-data; name=\"f\"; filename=\"\"").unwrap();
+    assert_eq!(headers.filename(), Some(""));
+
+    let err = PartHeaders::parse("Content-Disposition: form-data; filename=\"x\"").unwrap_err();
+    assert!(matches!(err, MultipartError::MalformedPartHeaders(_)));
+    let err = PartHeaders::parse("Content-Disposition: form-data; name=a\r\nContent-Disposition: form-data; name=b")
+        .unwrap_err();
+    assert!(matches!(err, MultipartError::MalformedPartHeaders(_)));
+}
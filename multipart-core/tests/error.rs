@@ -0,0 +1,122 @@
+//! Tests ของ `MultipartError`: HTTP status ของทุก variant และ response ที่ส่งกลับ client
+
+use std::error::Error;
+use std::io;
+
+use multipart_core::http;
+use multipart_core::{MultipartError, UploadProcessor};
+
+/// match แบบไม่มี `_` ทำให้ compile ไม่ผ่านถ้าเพิ่ม variant ใหม่แล้วลืมเพิ่มใน table ข้างล่าง
+fn variant_name(error: &MultipartError) -> &'static str {
+    match error {
+        MultipartError::MissingBoundary => "MissingBoundary",
+        MultipartError::InvalidBoundary(_) => "InvalidBoundary",
+        MultipartError::InvalidMediaType(_) => "InvalidMediaType",
+        MultipartError::UnsupportedMediaType(_) => "UnsupportedMediaType",
+        MultipartError::InvalidContentLength(_) => "InvalidContentLength",
+        MultipartError::MalformedPartHeaders(_) => "MalformedPartHeaders",
+        MultipartError::InvalidFilename(_) => "InvalidFilename",
+        MultipartError::InvalidDigest(_) => "InvalidDigest",
+        MultipartError::DigestMismatch { .. } => "DigestMismatch",
+        MultipartError::InvalidContentTransferEncoding(_) => "InvalidContentTransferEncoding",
+        MultipartError::UnexpectedEof => "UnexpectedEof",
+        MultipartError::InvalidChunkedEncoding(_) => "InvalidChunkedEncoding",
+        MultipartError::UnsupportedTransferEncoding(_) => "UnsupportedTransferEncoding",
+        MultipartError::MalformedRequest(_) => "MalformedRequest",
+        MultipartError::HeaderFieldsTooLarge { .. } => "HeaderFieldsTooLarge",
+        MultipartError::MethodNotAllowed(_) => "MethodNotAllowed",
+        MultipartError::NotFound(_) => "NotFound",
+        MultipartError::Unauthorized => "Unauthorized",
+        MultipartError::ExpectationFailed(_) => "ExpectationFailed",
+        MultipartError::Io(_) => "Io",
+        MultipartError::Storage(_) => "Storage",
+        MultipartError::LimitExceeded { .. } => "LimitExceeded",
+    }
+}
+
+#[test]
+fn maps_every_variant_to_a_status() {
+    let text = || "x".to_string();
+    let cases = [
+        (MultipartError::MissingBoundary, 400),
+        (MultipartError::InvalidBoundary(text()), 400),
+        (MultipartError::InvalidMediaType(text()), 400),
+        (MultipartError::UnsupportedMediaType(text()), 415),
+        (MultipartError::InvalidContentLength(text()), 400),
+        (MultipartError::MalformedPartHeaders(text()), 400),
+        (MultipartError::InvalidFilename(text()), 400),
+        (MultipartError::InvalidDigest(text()), 400),
+        (
+            MultipartError::DigestMismatch {
+                algorithm: "sha-256",
+                expected: text(),
+                actual: text(),
+            },
+            400,
+        ),
+        (MultipartError::InvalidContentTransferEncoding(text()), 400),
+        (MultipartError::UnexpectedEof, 400),
+        (MultipartError::InvalidChunkedEncoding(text()), 400),
+        (MultipartError::UnsupportedTransferEncoding(text()), 501),
+        (MultipartError::MalformedRequest(text()), 400),
+        (MultipartError::HeaderFieldsTooLarge { limit: "head size", max: 1 }, 431),
+        (MultipartError::MethodNotAllowed(text()), 405),
+        (MultipartError::NotFound(text()), 404),
+        (MultipartError::Unauthorized, 401),
+        (MultipartError::ExpectationFailed(text()), 417),
+        (MultipartError::Io(io::Error::other("disk full")), 500),
+        (MultipartError::Storage(text()), 502),
+        (MultipartError::LimitExceeded { limit: "file size", max: 1 }, 413),
+    ];
+
+    let mut names: Vec<&str> = cases.iter().map(|(error, _)| variant_name(error)).collect();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), 22, "every variant exactly once");
+
+    for (error, status) in &cases {
+        let (code, reason) = error.status();
+        assert_eq!(code, *status, "{:?}", error);
+        assert!(!reason.is_empty() && !error.to_string().is_empty(), "{:?}", error);
+
+        // error_response ปิด connection เสมอเพราะไม่รู้ว่า body เหลืออยู่เท่าไหร่
+        let response = http::error_response(error);
+        assert!(response.starts_with(&format!("HTTP/1.1 {} {}\r\n", code, reason)), "{}", response);
+        assert!(response.contains("Connection: close\r\n"));
+        assert!(response.ends_with(&error.to_string()));
+    }
+}
+
+#[test]
+fn wraps_io_errors() {
+    let error = MultipartError::from(io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+    assert!(matches!(&error, MultipartError::Io(e) if e.kind() == io::ErrorKind::PermissionDenied));
+    assert_eq!(error.to_string(), "I/O error: denied");
+    assert!(error.source().is_some());
+    assert!(MultipartError::UnexpectedEof.source().is_none());
+}
+
+#[test]
+fn reports_failures_instead_of_ignoring_them() {
+    // upload directory สร้างไม่ได้ (path เป็นไฟล์อยู่แล้ว)
+    let file = std::env::temp_dir().join(format!("multipart-core-error-{}", std::process::id()));
+    std::fs::write(&file, b"not a directory").unwrap();
+    let err = UploadProcessor::new("--b", file.to_str().unwrap()).err().unwrap();
+    assert_eq!(err.status().0, 500);
+    std::fs::remove_file(&file).unwrap();
+
+    let err = UploadProcessor::new("", "./unused").err().unwrap();
+    assert_eq!(err.status().0, 400);
+
+    // body ขาดก่อน closing delimiter
+    let dir = std::env::temp_dir().join(format!("multipart-core-error-dir-{}", std::process::id()));
+    let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap()).unwrap();
+    upload.process_chunk(b"--b\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nvalue").unwrap();
+    assert!(matches!(upload.finalize(), Err(MultipartError::UnexpectedEof)));
+
+    // headers ของ part ไม่มี Content-Disposition
+    let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap()).unwrap();
+    let err = upload.process_chunk(b"--b\r\nContent-Type: text/plain\r\n\r\n").unwrap_err();
+    assert!(matches!(err, MultipartError::MalformedPartHeaders(_)));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
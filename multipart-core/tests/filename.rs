@@ -0,0 +1,95 @@
+//! Tests ของการ sanitize ชื่อไฟล์และการตั้งชื่อไฟล์ที่บันทึก
+
+use multipart_core::filename::{self, MAX_FILENAME_LEN};
+use multipart_core::{MultipartError, NamingStrategy};
+
+fn sanitize(name: &str) -> String {
+    filename::sanitize(name).unwrap()
+}
+
+#[test]
+fn strips_directories() {
+    assert_eq!(sanitize("photo.jpg"), "photo.jpg");
+    assert_eq!(sanitize("C:\\Users\\me\\photo.jpg"), "photo.jpg");
+    assert_eq!(sanitize("/etc/passwd"), "passwd");
+    assert_eq!(sanitize("dir/sub/รายงาน.pdf"), "รายงาน.pdf");
+    assert_eq!(sanitize(".htaccess"), "htaccess");
+    assert_eq!(sanitize("  trailing. . "), "trailing");
+    assert_eq!(sanitize("a<b>c:d\"e|f?g*h.txt"), "a_b_c_d_e_f_g_h.txt");
+}
+
+#[test]
+fn rejects_dangerous_names() {
+    for name in [
+        "../../etc/cron.d/x",
+        "..\\..\\boot.ini",
+        "..",
+        "a/../b",
+        "evil\0.txt",
+        "line\nbreak.txt",
+        "bell\u{7}.txt",
+        "del\u{7f}.txt",
+        "CON",
+        "nul.txt",
+        "Com1.tar.gz",
+        "lpt9 .log",
+        "",
+        "...",
+        "dir/",
+    ] {
+        let err = filename::sanitize(name).unwrap_err();
+        assert!(matches!(err, MultipartError::InvalidFilename(_)), "{:?}: {:?}", name, err);
+        assert_eq!(err.status().0, 400);
+    }
+
+    // ชื่อที่แค่ขึ้นต้นคล้ายชื่อสงวนใช้ได้
+    assert_eq!(sanitize("console.log"), "console.log");
+    assert_eq!(sanitize("..hidden"), "hidden");
+}
+
+#[test]
+fn normalises_decomposed_latin() {
+    // macOS ส่งชื่อแบบ NFD
+    assert_eq!(sanitize("e\u{301}te\u{301}.txt"), "été.txt");
+    assert_eq!(sanitize("S\u{30c}kode\u{308}.doc"), "Škodë.doc");
+    // ภาษาไทยไม่เปลี่ยน
+    assert_eq!(sanitize("ไฟล์ที่ไม่มีการเปลี่ยน.txt"), "ไฟล์ที่ไม่มีการเปลี่ยน.txt");
+}
+
+#[test]
+fn caps_length_and_keeps_extension() {
+    let long = format!("{}.jpeg", "ก".repeat(200));
+    let name = sanitize(&long);
+    assert!(name.len() <= MAX_FILENAME_LEN);
+    assert!(name.ends_with(".jpeg"));
+    assert!(name.starts_with("ก"));
+
+    let suffixed = filename::with_suffix(&name, 12);
+    assert!(suffixed.len() <= MAX_FILENAME_LEN);
+    assert!(suffixed.ends_with("-12.jpeg"));
+
+    assert_eq!(filename::with_suffix("photo.jpg", 1), "photo-1.jpg");
+    assert_eq!(filename::with_suffix("README", 2), "README-2");
+    assert_eq!(filename::with_suffix("archive.tar.gz", 3), "archive.tar-3.gz");
+}
+
+#[test]
+fn generates_uuid_v4() {
+    let a = filename::uuid_v4();
+    let b = filename::uuid_v4();
+    assert_ne!(a, b);
+    assert_eq!(a.len(), 36);
+    assert_eq!(&a[14..15], "4");
+    assert!(matches!(&a[19..20], "8" | "9" | "a" | "b"));
+    assert!(a.split('-').map(str::len).eq([8, 4, 4, 4, 12]));
+}
+
+#[test]
+fn parses_naming_strategy() {
+    assert_eq!("original".parse(), Ok(NamingStrategy::Original));
+    assert_eq!("UUID".parse(), Ok(NamingStrategy::Uuid));
+    assert_eq!("hash".parse(), Ok(NamingStrategy::ContentHash));
+    assert_eq!(" suffix ".parse(), Ok(NamingStrategy::SuffixOnCollision));
+    assert!("random".parse::<NamingStrategy>().is_err());
+    assert_eq!(NamingStrategy::default(), NamingStrategy::SuffixOnCollision);
+}
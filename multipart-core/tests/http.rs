@@ -0,0 +1,260 @@
+//! Tests ของ `http`: parse request head, ตรวจ head ก่อนรับ body, framing และ keep-alive
+
+use std::io::Read;
+
+use multipart_core::MultipartError;
+use multipart_core::http::{self, BodyFraming, Connection, HeadLimits, RequestHead, UploadPolicy};
+
+const CONTENT_TYPE: &str = "Content-Type: multipart/form-data; boundary=abc";
+
+fn parse(text: &str) -> Result<RequestHead, MultipartError> {
+    RequestHead::parse(text.as_bytes(), &HeadLimits::default())
+}
+
+fn head(request_line: &str, extra: &[&str]) -> RequestHead {
+    let mut text = format!("{}\r\nHost: localhost\r\n{}\r\n", request_line, CONTENT_TYPE);
+    for line in extra {
+        text.push_str(line);
+        text.push_str("\r\n");
+    }
+    text.push_str("\r\n");
+    parse(&text).unwrap()
+}
+
+fn status(head: &RequestHead, policy: &UploadPolicy) -> u16 {
+    match http::check_head(head, policy) {
+        Ok(()) => 100,
+        Err(e) => e.status().0,
+    }
+}
+
+#[test]
+fn detects_expect_continue() {
+    let headers = head("POST /upload HTTP/1.1", &["Expect: 100-Continue"]);
+    assert!(http::expects_continue(&headers));
+
+    let headers = head("POST /upload HTTP/1.0", &["Expect: 100-continue"]);
+    assert!(!http::expects_continue(&headers));
+
+    let headers = head("POST /upload HTTP/1.1", &[]);
+    assert!(!http::expects_continue(&headers));
+}
+
+#[test]
+fn accepts_request_within_policy() {
+    let policy = UploadPolicy::new()
+        .with_path("/upload")
+        .with_max_body_size(1000)
+        .with_auth_token("s3cret");
+    let headers = head(
+        "POST /upload?x=1 HTTP/1.1",
+        &["Content-Length: 1000", "Authorization: Bearer s3cret", "Expect: 100-continue"],
+    );
+    assert_eq!(status(&headers, &policy), 100);
+}
+
+#[test]
+fn rejects_before_body() {
+    let policy = UploadPolicy::new()
+        .with_path("/upload")
+        .with_max_body_size(1000)
+        .with_auth_token("s3cret");
+    let auth = "Authorization: Bearer s3cret";
+
+    assert_eq!(status(&head("GET /upload HTTP/1.1", &[auth]), &policy), 405);
+    assert_eq!(status(&head("POST /other HTTP/1.1", &[auth]), &policy), 404);
+    assert_eq!(status(&head("POST /upload HTTP/1.1", &[]), &policy), 401);
+    assert_eq!(status(&head("POST /upload HTTP/1.1", &["Authorization: Bearer nope"]), &policy), 401);
+    assert_eq!(
+        status(&head("POST /upload HTTP/1.1", &[auth, "Content-Length: 1001"]), &policy),
+        413
+    );
+    assert_eq!(
+        status(&head("POST /upload HTTP/1.1", &[auth, "Expect: something-else"]), &policy),
+        417
+    );
+    assert_eq!(
+        status(&head("POST /upload HTTP/1.1", &[auth, "Transfer-Encoding: gzip"]), &policy),
+        501
+    );
+
+    let no_boundary = parse("POST /upload HTTP/1.1\r\nContent-Type: multipart/form-data\r\n\r\n").unwrap();
+    assert!(matches!(
+        http::check_head(&no_boundary, &UploadPolicy::new()),
+        Err(MultipartError::MissingBoundary)
+    ));
+    let not_multipart = parse("POST /upload HTTP/1.1\r\nContent-Type: text/plain\r\n\r\n").unwrap();
+    assert_eq!(status(&not_multipart, &UploadPolicy::new()), 415);
+}
+
+#[test]
+fn chooses_body_framing() {
+    let headers = head("POST / HTTP/1.1", &["Content-Length: 10"]);
+    assert_eq!(http::body_framing(&headers).unwrap(), BodyFraming::ContentLength(10));
+
+    let headers = head("POST / HTTP/1.1", &["Transfer-Encoding: Chunked"]);
+    assert_eq!(http::body_framing(&headers).unwrap(), BodyFraming::Chunked);
+
+    let headers = head("POST / HTTP/1.1", &[]);
+    assert_eq!(http::body_framing(&headers).unwrap(), BodyFraming::UntilClose);
+}
+
+#[test]
+fn rejects_both_transfer_encoding_and_content_length() {
+    // ถ้าเลือกตัวใดตัวหนึ่ง proxy ที่เลือกอีกตัวจะเห็นขอบเขตของ body ไม่ตรงกับเรา
+    for headers in [
+        &["Content-Length: 10", "Transfer-Encoding: chunked"][..],
+        &["Transfer-Encoding: chunked", "Content-Length: 0"],
+        &["Transfer-Encoding: gzip", "Content-Length: 10"],
+    ] {
+        let err = http::body_framing(&head("POST / HTTP/1.1", headers)).unwrap_err();
+        assert!(matches!(err, MultipartError::MalformedRequest(_)), "{:?}: {:?}", headers, err);
+        assert_eq!(err.status().0, 400);
+        assert!(http::error_response(&err).contains("Connection: close\r\n"));
+    }
+}
+
+/// อ่าน body ตาม framing แบบเดียวกับ server คืน bytes ที่อ่านได้
+fn read_body<R: Read>(conn: &mut Connection<R>, framing: BodyFraming) -> Vec<u8> {
+    let mut body = Vec::new();
+    let mut buffer = [0u8; 8];
+    while let Some(to_read) = framing.next_read(body.len(), buffer.len()) {
+        match conn.read(&mut buffer[..to_read]).unwrap() {
+            0 => break,
+            n => body.extend_from_slice(&buffer[..n]),
+        }
+    }
+    body
+}
+
+#[test]
+fn reads_exactly_content_length_on_pipelined_connections() {
+    assert_eq!(BodyFraming::ContentLength(0).next_read(0, 8), None);
+    assert_eq!(BodyFraming::ContentLength(10).next_read(4, 8), Some(6));
+    assert_eq!(BodyFraming::ContentLength(10).next_read(10, 8), None);
+    assert_eq!(BodyFraming::UntilClose.next_read(100, 8), Some(8));
+
+    // Content-Length: 0 ต้องไม่อ่าน request ถัดไปมาเป็น body
+    let raw = b"POST /a HTTP/1.1\r\nContent-Length: 0\r\n\r\n\
+        POST /b HTTP/1.1\r\nContent-Length: 12\r\n\r\nhello, world\
+        GET /c HTTP/1.1\r\n\r\n";
+    let mut conn = Connection::new(&raw[..]);
+    let limits = HeadLimits::default();
+    let mut requests = Vec::new();
+    while let Some(head) = conn.read_head(&limits).unwrap() {
+        let body = read_body(&mut conn, http::body_framing(&head).unwrap());
+        requests.push((head.target.clone(), String::from_utf8(body).unwrap()));
+    }
+    assert_eq!(
+        requests,
+        [("/a", ""), ("/b", "hello, world"), ("/c", "")].map(|(target, body)| (target.to_string(), body.to_string()))
+    );
+}
+
+#[test]
+fn decides_keep_alive() {
+    assert!(http::keep_alive(&head("POST / HTTP/1.1", &[])));
+    assert!(!http::keep_alive(&head("POST / HTTP/1.1", &["Connection: close"])));
+    assert!(!http::keep_alive(&head("POST / HTTP/1.1", &["Connection: Upgrade, Close"])));
+    assert!(!http::keep_alive(&head("POST / HTTP/1.0", &[])));
+    assert!(http::keep_alive(&head("POST / HTTP/1.0", &["Connection: keep-alive"])));
+}
+
+#[test]
+fn unread_bytes_come_before_stream() {
+    let mut conn = Connection::new(&b"POST /b HTTP/1.1\r\n\r\n"[..]);
+    conn.unread(b"GET /a HTTP/1.1\r\n\r\n");
+    let limits = HeadLimits::default();
+
+    assert_eq!(conn.read_head(&limits).unwrap().unwrap().target, "/a");
+    assert_eq!(conn.read_head(&limits).unwrap().unwrap().target, "/b");
+    assert!(conn.read_head(&limits).unwrap().is_none());
+}
+
+#[test]
+fn parses_request_line_and_headers() {
+    let head = parse("POST /upload?x=1 HTTP/1.1\r\nHOST: a\r\ncontent-type: multipart/form-data; boundary=b\r\n\r\n")
+        .unwrap();
+
+    assert_eq!(head.method, "POST");
+    assert_eq!(head.target, "/upload?x=1");
+    assert_eq!(head.path(), "/upload");
+    assert_eq!(head.version, "HTTP/1.1");
+    assert_eq!(head.header("Host"), Some("a"));
+    assert_eq!(head.header("Content-Type"), Some("multipart/form-data; boundary=b"));
+    assert_eq!(head.headers.len(), 2);
+}
+
+#[test]
+fn unfolds_and_combines_headers() {
+    let head = parse("POST / HTTP/1.1\r\nX-Long: a\r\n  b\r\n\tc\r\nAccept: x\r\naccept: y\r\n\r\n").unwrap();
+    assert_eq!(head.header("x-long"), Some("a b c"));
+    assert_eq!(head.header("accept"), Some("x, y"));
+
+    // Content-Length ซ้ำด้วยค่าเดียวกันยอมรับได้ ค่าต่างกันต้องปฏิเสธ
+    let head = parse("POST / HTTP/1.1\r\nContent-Length: 5\r\nContent-Length: 5\r\n\r\n").unwrap();
+    assert_eq!(head.header("content-length"), Some("5"));
+    let err = parse("POST / HTTP/1.1\r\nContent-Length: 5\r\nContent-Length: 6\r\n\r\n").unwrap_err();
+    assert_eq!(err.status().0, 400);
+}
+
+#[test]
+fn rejects_malformed_heads() {
+    for text in [
+        "POST /\r\n\r\n",
+        "POST  / HTTP/1.1\r\n\r\n",
+        "POST / HTTP/2.0\r\n\r\n",
+        "POST / HTTP/1.1\r\n folded-first: x\r\n\r\n",
+        "POST / HTTP/1.1\r\nNo-Colon\r\n\r\n",
+        "POST / HTTP/1.1\r\nBad Name: x\r\n\r\n",
+        "POST / HTTP/1.1\r\nHost : x\r\n\r\n",
+    ] {
+        let err = parse(text).unwrap_err();
+        assert!(matches!(err, MultipartError::MalformedRequest(_)), "{:?}: {:?}", text, err);
+    }
+}
+
+#[test]
+fn enforces_head_limits() {
+    let limits = HeadLimits {
+        max_size: 64,
+        max_headers: 2,
+    };
+
+    let many = "POST / HTTP/1.1\r\nA: 1\r\nB: 2\r\nC: 3\r\n\r\n";
+    let err = RequestHead::parse(many.as_bytes(), &limits).unwrap_err();
+    assert_eq!(err.status(), (431, "Request Header Fields Too Large"));
+
+    // head ที่ยังไม่จบแต่ใหญ่เกินต้องหยุดอ่านทันที ไม่รอจนเจอบรรทัดว่าง
+    let huge = format!("POST / HTTP/1.1\r\nX: {}", "a".repeat(1000));
+    let mut conn = Connection::new(huge.as_bytes());
+    assert_eq!(conn.read_head(&limits).unwrap_err().status().0, 431);
+}
+
+#[test]
+fn content_length_must_be_digits_only() {
+    assert_eq!(http::content_length(&head("POST / HTTP/1.1", &["Content-Length: 05"])).unwrap(), Some(5));
+    assert_eq!(http::content_length(&head("POST / HTTP/1.1", &[])).unwrap(), None);
+    for value in ["+5", "5, 5", "-1", "5 5", "0x5", "99999999999999999999999"] {
+        let head = head("POST / HTTP/1.1", &[&format!("Content-Length: {}", value)]);
+        let err = http::content_length(&head).unwrap_err();
+        assert!(matches!(&err, MultipartError::InvalidContentLength(v) if v == value), "{:?}", err);
+        assert_eq!(err.status().0, 400);
+    }
+}
+
+#[test]
+fn keeps_buffered_body_bytes() {
+    use std::io::Read;
+
+    let mut conn = Connection::new(&b"\r\nPOST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhelloGET"[..]);
+    let head = conn.read_head(&HeadLimits::default()).unwrap().unwrap();
+    assert_eq!(http::content_length(&head).unwrap(), Some(5));
+
+    let mut body = [0u8; 5];
+    conn.read_exact(&mut body).unwrap();
+    assert_eq!(&body, b"hello");
+
+    // request ที่ขาดกลางคันเป็น 400 ไม่ใช่การปิด connection ปกติ
+    assert_eq!(conn.read_head(&HeadLimits::default()).unwrap_err().status().0, 400);
+}
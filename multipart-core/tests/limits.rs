@@ -0,0 +1,164 @@
+//! Tests ของ `Limits`: ทุก limit ต้องหยุดระหว่าง stream ด้วย 413 ที่บอกชื่อ limit
+
+use std::path::PathBuf;
+
+use multipart_core::{Limits, MultipartError, StreamingParser, UploadProcessor};
+
+fn upload_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("multipart-core-limits-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// feed body ทีละ 16 bytes แล้วคืนชื่อ limit ที่เกิน (หรือ `None` ถ้าผ่าน)
+fn exceeded(test: &str, limits: Limits, body: &[u8]) -> Option<&'static str> {
+    let dir = upload_dir(test);
+    let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap()).unwrap().with_limits(limits);
+    let result = body
+        .chunks(16)
+        .try_for_each(|chunk| upload.process_chunk(chunk))
+        .and_then(|()| upload.finalize());
+    drop(upload);
+
+    // ไม่ว่าจะหยุดตรงไหนต้องไม่เหลือไฟล์ชั่วคราว
+    let leftovers = std::fs::read_dir(&dir)
+        .unwrap()
+        .filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().ends_with(".tmp"))
+        .count();
+    assert_eq!(leftovers, 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    match result {
+        Ok(()) => None,
+        Err(MultipartError::LimitExceeded { limit, .. }) => Some(limit),
+        Err(e) => panic!("unexpected error: {:?}", e),
+    }
+}
+
+fn body(parts: &[(&str, Option<&str>, &str)]) -> Vec<u8> {
+    let mut body = String::new();
+    for (name, filename, value) in parts {
+        body.push_str("--b\r\nContent-Disposition: form-data; name=\"");
+        body.push_str(name);
+        if let Some(filename) = filename {
+            body.push_str("\"; filename=\"");
+            body.push_str(filename);
+        }
+        body.push_str("\"\r\n\r\n");
+        body.push_str(value);
+        body.push_str("\r\n");
+    }
+    body.push_str("--b--\r\n");
+    body.into_bytes()
+}
+
+#[test]
+fn limits_file_and_field_size() {
+    let files = body(&[("a", Some("a.bin"), &"x".repeat(100))]);
+    let limits = Limits {
+        max_file_size: 100,
+        ..Limits::default()
+    };
+    assert_eq!(exceeded("file-ok", limits, &files), None);
+    let limits = Limits {
+        max_file_size: 99,
+        ..Limits::default()
+    };
+    assert_eq!(exceeded("file", limits, &files), Some("file size"));
+
+    let fields = body(&[("note", None, &"y".repeat(50))]);
+    let limits = Limits {
+        max_field_size: 49,
+        ..Limits::default()
+    };
+    assert_eq!(exceeded("field", limits, &fields), Some("field size"));
+}
+
+#[test]
+fn max_field_size_wins_over_limits_in_either_call_order() {
+    let fields = body(&[("note", None, &"y".repeat(50))]);
+    let limits = Limits {
+        max_field_size: 10,
+        max_parts: 5,
+        ..Limits::default()
+    };
+    let run = |mut upload: UploadProcessor| {
+        upload.process_chunk(&fields).and_then(|()| upload.finalize())
+    };
+    let dir = upload_dir("field-order");
+    let new = || UploadProcessor::new("--b", dir.to_str().unwrap()).unwrap();
+
+    // with_max_field_size ชนะเสมอ ส่วน limit อื่นของ with_limits ยังใช้ได้
+    assert!(run(new().with_max_field_size(50).with_limits(limits)).is_ok());
+    assert!(run(new().with_limits(limits).with_max_field_size(50)).is_ok());
+    for upload in [new().with_max_field_size(49).with_limits(limits), new().with_limits(limits).with_max_field_size(49)] {
+        let err = run(upload).unwrap_err();
+        assert!(matches!(err, MultipartError::LimitExceeded { limit: "field size", .. }), "{:?}", err);
+    }
+    let many = body(&[("a", None, "1"); 6]);
+    let err = new().with_max_field_size(50).with_limits(limits).process_chunk(&many).unwrap_err();
+    assert!(matches!(err, MultipartError::LimitExceeded { limit: "part count", .. }), "{:?}", err);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn limits_total_body_size() {
+    let data = body(&[("a", None, "1"), ("b", Some("b.bin"), &"z".repeat(200))]);
+    let limits = Limits {
+        max_body_size: data.len(),
+        ..Limits::default()
+    };
+    assert_eq!(exceeded("total-ok", limits, &data), None);
+    let limits = Limits {
+        max_body_size: data.len() - 1,
+        ..Limits::default()
+    };
+    assert_eq!(exceeded("total", limits, &data), Some("body size"));
+}
+
+#[test]
+fn limits_number_of_parts_and_files() {
+    let data = body(&[
+        ("a", None, "1"),
+        ("b", Some("b.txt"), "2"),
+        ("c", Some("c.txt"), "3"),
+        ("d", None, "4"),
+    ]);
+
+    let limits = Limits {
+        max_parts: 3,
+        ..Limits::default()
+    };
+    assert_eq!(exceeded("parts", limits, &data), Some("part count"));
+    let limits = Limits {
+        max_files: 1,
+        ..Limits::default()
+    };
+    assert_eq!(exceeded("files", limits, &data), Some("file count"));
+    let limits = Limits {
+        max_parts: 4,
+        max_files: 2,
+        ..Limits::default()
+    };
+    assert_eq!(exceeded("counts-ok", limits, &data), None);
+}
+
+#[test]
+fn limits_part_header_size() {
+    let long_name = "n".repeat(200);
+    let data = body(&[(&long_name, None, "v")]);
+    let limits = Limits {
+        max_part_header_size: 100,
+        ..Limits::default()
+    };
+    assert_eq!(exceeded("header", limits, &data), Some("part header size"));
+
+    // header ที่ไม่มีวันจบต้องหยุดทันทีที่เกิน limit ไม่ใช่สะสมไว้เรื่อยๆ
+    let mut parser = StreamingParser::new("--b").unwrap().with_max_header_size(64);
+    parser.feed(b"--b\r\nX-Endless: ");
+    assert_eq!(parser.next_event().unwrap(), None);
+    parser.feed(&[b'a'; 64]);
+    let err = parser.next_event().unwrap_err();
+    assert_eq!(err.status(), (413, "Payload Too Large"));
+    assert_eq!(err.to_string(), "part header size limit exceeded (max 64)");
+}
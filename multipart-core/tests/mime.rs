@@ -0,0 +1,91 @@
+//! Tests ของการ parse `Content-Type` และการตรวจ boundary
+
+use multipart_core::http::{self, HeadLimits, RequestHead};
+use multipart_core::{MediaType, MultipartError};
+
+fn boundary(content_type: &str) -> Result<String, MultipartError> {
+    MediaType::parse(content_type)?.boundary().map(str::to_string)
+}
+
+fn delimiter(content_type: Option<&str>) -> Result<String, MultipartError> {
+    let mut text = "POST /upload HTTP/1.1\r\nHost: x\r\n".to_string();
+    if let Some(content_type) = content_type {
+        text.push_str(&format!("Content-Type: {}\r\n", content_type));
+    }
+    text.push_str("\r\n");
+    let head = RequestHead::parse(text.as_bytes(), &HeadLimits::default())?;
+    http::boundary_delimiter(&head)
+}
+
+#[test]
+fn parses_real_client_boundaries() {
+    // curl
+    assert_eq!(
+        boundary("multipart/form-data; boundary=------------------------d74496d66958873e").unwrap(),
+        "------------------------d74496d66958873e"
+    );
+    // Chrome / Safari
+    assert_eq!(
+        boundary("multipart/form-data; boundary=----WebKitFormBoundary7MA4YWxkTrZu0gW").unwrap(),
+        "----WebKitFormBoundary7MA4YWxkTrZu0gW"
+    );
+    // Firefox
+    assert_eq!(
+        boundary("multipart/form-data; boundary=---------------------------974767299852498929531610575").unwrap(),
+        "---------------------------974767299852498929531610575"
+    );
+}
+
+#[test]
+fn handles_quotes_order_and_case() {
+    assert_eq!(boundary("multipart/form-data; boundary=\"abc\"").unwrap(), "abc");
+    assert_eq!(boundary("multipart/form-data; boundary=abc; charset=utf-8").unwrap(), "abc");
+    assert_eq!(boundary("multipart/form-data;charset=utf-8;boundary=abc").unwrap(), "abc");
+    assert_eq!(boundary("Multipart/Form-Data; BOUNDARY=AbC").unwrap(), "AbC");
+    assert_eq!(boundary("multipart/form-data; boundary=\"a b:c\\=d\" ; x=\"y;z\"").unwrap(), "a b:c=d");
+    assert_eq!(boundary("multipart/form-data; boundary=abc;").unwrap(), "abc");
+
+    let media_type = MediaType::parse("Multipart/Related; Type=\"text/html\"; start=\"<root>\"").unwrap();
+    assert_eq!(media_type.essence(), "multipart/related");
+    assert_eq!(media_type.param("type"), Some("text/html"));
+    assert_eq!(media_type.param("START"), Some("<root>"));
+}
+
+#[test]
+fn validates_boundary_grammar() {
+    let invalid = |content_type: &str| matches!(boundary(content_type), Err(MultipartError::InvalidBoundary(_)));
+
+    assert_eq!(boundary(&format!("multipart/form-data; boundary={}", "a".repeat(70))).unwrap().len(), 70);
+    assert!(invalid(&format!("multipart/form-data; boundary={}", "a".repeat(71))));
+    assert!(invalid("multipart/form-data; boundary=\"\""));
+    assert!(invalid("multipart/form-data; boundary=\"abc \""));
+    assert!(invalid("multipart/form-data; boundary=\"a\\\"b\""));
+    assert!(invalid("multipart/form-data; boundary=a*b"));
+    assert!(matches!(boundary("multipart/form-data"), Err(MultipartError::MissingBoundary)));
+}
+
+#[test]
+fn rejects_malformed_media_types() {
+    for content_type in [
+        "multipart",
+        "multipart/",
+        "multipart/form-data; boundary",
+        "multipart/form-data; boundary=\"abc",
+        "multipart/form-data; boundary=abc def",
+        "multipart/form-data; boundary=a; boundary=b",
+    ] {
+        let err = MediaType::parse(content_type).unwrap_err();
+        assert!(matches!(err, MultipartError::InvalidMediaType(_)), "{:?}: {:?}", content_type, err);
+        assert_eq!(err.status().0, 400);
+    }
+}
+
+#[test]
+fn rejects_non_multipart_with_415() {
+    assert_eq!(delimiter(Some("multipart/form-data; boundary=\"x y\"")).unwrap(), "--x y");
+
+    for content_type in [Some("application/json"), Some("text/plain; boundary=abc"), None] {
+        let err = delimiter(content_type).unwrap_err();
+        assert_eq!(err.status(), (415, "Unsupported Media Type"), "{:?}", content_type);
+    }
+}
@@ -0,0 +1,211 @@
+//! Tests ของ API สาธารณะของ `StreamingParser` และ helper อ่าน header ที่ทั้งสอง lab ใช้ร่วมกัน
+
+use multipart_core::http::{self, HeadLimits, RequestHead};
+use multipart_core::{Event, MultipartError, ParserState, PartHeaders, PartType, StreamingParser, UploadProcessor};
+
+const BODY: &[u8] = b"--b\r\n\
+    Content-Disposition: form-data; name=\"user\"\r\n\r\n\
+    alice\r\n\
+    --b\r\n\
+    Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\
+    Content-Type: text/plain\r\n\r\n\
+    hello\r\n\
+    --b--\r\n";
+
+fn request_head(headers: &str) -> RequestHead {
+    RequestHead::parse(format!("POST /upload HTTP/1.1\r\n{}\r\n", headers).as_bytes(), &HeadLimits::default()).unwrap()
+}
+
+/// event ทุกตัวของ body ที่ feed ทีละ `chunk_size` bytes (`PartData` ที่ติดกันรวมเป็นตัวเดียว)
+fn events(body: &[u8], chunk_size: usize) -> Vec<String> {
+    let mut parser = StreamingParser::new("--b").unwrap();
+    let mut log: Vec<String> = Vec::new();
+    let mut record = |parser: &mut StreamingParser| {
+        while let Some(event) = parser.next_event().unwrap() {
+            let entry = match event {
+                Event::PartStart { headers } => format!("start {}", headers.name),
+                Event::PartData(data) => {
+                    let data = String::from_utf8_lossy(data);
+                    match log.last_mut() {
+                        Some(last) if last.starts_with("data ") => {
+                            last.push_str(&data);
+                            continue;
+                        }
+                        _ => format!("data {}", data),
+                    }
+                }
+                Event::PartEnd => "end".to_string(),
+                Event::Finished => "finished".to_string(),
+            };
+            log.push(entry);
+        }
+    };
+    for chunk in body.chunks(chunk_size) {
+        parser.feed(chunk);
+        record(&mut parser);
+    }
+    parser.end_of_input();
+    record(&mut parser);
+    log
+}
+
+/// ดึง event ออกจนกว่า parser จะต้องการข้อมูลเพิ่ม
+fn drain(parser: &mut StreamingParser) {
+    while parser.next_event().unwrap().is_some() {}
+}
+
+#[test]
+fn reads_boundary_and_content_length_from_request_head() {
+    let head = request_head("Content-Type: multipart/form-data; boundary=b\r\nContent-Length: 42\r\n");
+    assert_eq!(http::boundary_delimiter(&head).unwrap(), "--b");
+    assert_eq!(http::content_length(&head).unwrap(), Some(42));
+
+    let head = request_head("Content-Type: multipart/form-data\r\nContent-Length: 4x\r\n");
+    assert!(matches!(http::boundary_delimiter(&head), Err(MultipartError::MissingBoundary)));
+    assert!(matches!(http::content_length(&head), Err(MultipartError::InvalidContentLength(value)) if value == "4x"));
+
+    let head = request_head("");
+    assert!(matches!(http::boundary_delimiter(&head), Err(MultipartError::UnsupportedMediaType(_))));
+    assert_eq!(http::content_length(&head).unwrap(), None);
+}
+
+#[test]
+fn walks_through_parser_states() {
+    let mut parser = StreamingParser::new("--b").unwrap();
+    assert_eq!(parser.state(), ParserState::SearchingBoundary);
+
+    let headers_start = b"--b\r\n".len();
+    parser.feed(&BODY[..headers_start]);
+    drain(&mut parser);
+    assert_eq!(parser.state(), ParserState::ReadingHeaders);
+
+    let data_start = headers_start + b"Content-Disposition: form-data; name=\"user\"\r\n\r\n".len();
+    parser.feed(&BODY[headers_start..data_start]);
+    drain(&mut parser);
+    assert_eq!(parser.state(), ParserState::ReadingData);
+
+    parser.feed(&BODY[data_start..]);
+    drain(&mut parser);
+    assert_eq!(parser.state(), ParserState::Done);
+    assert!(parser.is_complete());
+}
+
+#[test]
+fn parses_part_headers() {
+    let field = PartHeaders::parse("Content-Disposition: form-data; name=\"user\"").unwrap();
+    assert_eq!(field.name, "user");
+    assert_eq!(field.part_type, PartType::Field);
+    assert_eq!(field.filename(), None);
+
+    let file = PartHeaders::parse("content-disposition: form-data; name=doc; filename=\"a b.txt\"").unwrap();
+    assert_eq!(file.name, "doc");
+    assert_eq!(
+        file.part_type,
+        PartType::File {
+            filename: "a b.txt".to_string(),
+            content_type: String::new(),
+        }
+    );
+
+    assert!(StreamingParser::new("").is_err());
+    for malformed in ["Content-Type: text/plain", "Content-Disposition: form-data", "no colon"] {
+        let err = PartHeaders::parse(malformed).unwrap_err();
+        assert!(matches!(err, MultipartError::MalformedPartHeaders(_)), "{:?}: {:?}", malformed, err);
+    }
+}
+
+#[test]
+fn emits_events_in_order() {
+    let expected = ["start user", "data alice", "end", "start doc", "data hello", "end", "finished"];
+    for chunk_size in 1..=BODY.len() {
+        assert_eq!(events(BODY, chunk_size), expected, "chunk size {}", chunk_size);
+    }
+}
+
+#[test]
+fn streams_part_data_before_the_next_delimiter_arrives() {
+    let mut parser = StreamingParser::new("--b").unwrap();
+    parser.feed(b"--b\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\r\n");
+    assert!(matches!(parser.next_event().unwrap(), Some(Event::PartStart { headers }) if headers.name == "doc"));
+    assert_eq!(parser.next_event().unwrap(), None);
+
+    // ข้อมูลที่ไม่มีทางเป็น delimiter ปล่อยออกมาได้ทันที เก็บไว้แค่ท้าย chunk ที่อาจเป็น delimiter ที่ถูกตัด
+    let mut data = Vec::new();
+    parser.feed(b"first chunk\r\n-");
+    match parser.next_event().unwrap() {
+        Some(Event::PartData(chunk)) => data.extend_from_slice(chunk),
+        other => panic!("expected data before the delimiter, got {:?}", other),
+    }
+    assert!(data.starts_with(b"first"));
+    assert_eq!(parser.next_event().unwrap(), None);
+
+    parser.feed(b"-x\r\n--b--");
+    while let Some(event) = parser.next_event().unwrap() {
+        match event {
+            Event::PartData(chunk) => data.extend_from_slice(chunk),
+            Event::PartEnd | Event::Finished => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+    assert_eq!(data, b"first chunk\r\n--x");
+    assert!(parser.is_complete());
+}
+
+#[test]
+fn upload_processor_consumes_the_event_stream() {
+    let dir = std::env::temp_dir().join(format!("multipart-core-parser-{}", std::process::id()));
+    let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap()).unwrap();
+    for chunk in BODY.chunks(3) {
+        upload.process_chunk(chunk).unwrap();
+    }
+    upload.finalize().unwrap();
+
+    let stats = upload.get_stats();
+    assert_eq!(stats.fields_count, 1);
+    assert_eq!(stats.files_count, 1);
+    assert_eq!(std::fs::read(&stats.files_saved[0].path).unwrap(), b"hello");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn stops_at_the_closing_delimiter() {
+    // epilogue ที่หน้าตาเหมือน part อีกตัวต้องถูกทิ้ง
+    let mut body = BODY.to_vec();
+    body.extend_from_slice(b"--b\r\nContent-Disposition: form-data; name=\"extra\"\r\n\r\nignored\r\n--b--\r\n");
+    for chunk_size in [1, 7, body.len()] {
+        let log = events(&body, chunk_size);
+        assert_eq!(log.last().map(String::as_str), Some("finished"));
+        assert!(!log.iter().any(|entry| entry.contains("extra") || entry.contains("ignored")), "{:?}", log);
+    }
+
+    let mut parser = StreamingParser::new("--b").unwrap();
+    parser.feed(BODY);
+    drain(&mut parser);
+    assert_eq!(parser.state(), ParserState::Done);
+    parser.feed(b"more epilogue --b\r\n");
+    parser.end_of_input();
+    assert_eq!(parser.next_event().unwrap(), None);
+    assert!(parser.is_complete());
+}
+
+#[test]
+fn flags_bodies_without_a_closing_delimiter() {
+    // ขาด `--` ท้าย delimiter สุดท้าย: ยังไม่จบ
+    let truncated = &BODY[..BODY.len() - b"--\r\n".len()];
+    let mut parser = StreamingParser::new("--b").unwrap();
+    parser.feed(truncated);
+    drain(&mut parser);
+    parser.end_of_input();
+    assert!(matches!(parser.next_event(), Err(MultipartError::UnexpectedEof)));
+    assert!(!parser.is_complete());
+
+    let dir = std::env::temp_dir().join(format!("multipart-core-parser-truncated-{}", std::process::id()));
+    let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap()).unwrap();
+    upload.process_chunk(truncated).unwrap();
+    assert!(!upload.is_complete());
+    assert!(matches!(upload.finalize(), Err(MultipartError::UnexpectedEof)));
+    // ไฟล์ของ part ที่ยังไม่จบไม่ถูกบันทึก
+    assert!(upload.get_stats().files_saved.is_empty());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
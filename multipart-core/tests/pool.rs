@@ -0,0 +1,105 @@
+//! Tests ของ `ThreadPool`: จำนวน worker, คิวเต็ม, งานที่ panic และการรองานตอน drop
+
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Barrier, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use multipart_core::pool::{PoolConfig, ThreadPool};
+
+fn config(workers: usize, queue_depth: usize) -> PoolConfig {
+    PoolConfig { workers, queue_depth }
+}
+
+#[test]
+fn runs_jobs_on_every_worker() {
+    // ทุกงานรอจนครบ 3 งานพร้อมกัน จึงผ่านได้ก็ต่อเมื่อมี worker 3 ตัวทำงานขนานกันจริง
+    let barrier = Arc::new(Barrier::new(3));
+    let ids = Arc::new(Mutex::new(BTreeSet::new()));
+    let pool = {
+        let (barrier, ids) = (Arc::clone(&barrier), Arc::clone(&ids));
+        ThreadPool::new(config(3, 0), move |(), id| {
+            barrier.wait();
+            ids.lock().unwrap().insert((id, thread::current().name().unwrap().to_string()));
+        })
+    };
+    assert_eq!(pool.size(), 3);
+    for _ in 0..3 {
+        // queue_depth 0: ส่งได้เมื่อมี worker ว่างรอรับอยู่เท่านั้น
+        while pool.try_submit(()).is_err() {
+            thread::yield_now();
+        }
+    }
+    drop(pool);
+
+    let ids: Vec<(usize, String)> = ids.lock().unwrap().iter().cloned().collect();
+    assert_eq!(ids, [1, 2, 3].map(|id| (id, format!("upload-worker-{}", id))));
+    assert_eq!(ThreadPool::new(config(0, 1), |(), _| {}).size(), 1);
+}
+
+#[test]
+fn returns_the_job_once_the_queue_is_full() {
+    let (started, on_start) = channel();
+    let (release, on_release) = channel::<()>();
+    let (started, on_release) = (Mutex::new(started), Mutex::new(on_release));
+    let done = Arc::new(Mutex::new(Vec::new()));
+    let pool = {
+        let done = Arc::clone(&done);
+        ThreadPool::new(config(1, 2), move |job: usize, _| {
+            started.lock().unwrap().send(job).unwrap();
+            on_release.lock().unwrap().recv().unwrap();
+            done.lock().unwrap().push(job);
+        })
+    };
+
+    // worker ตัวเดียวถืองานแรกไว้ คิวรับได้อีก 2 งาน งานที่ 4 ต้องถูกคืนกลับมา
+    pool.try_submit(1).unwrap();
+    assert_eq!(on_start.recv().unwrap(), 1);
+    pool.try_submit(2).unwrap();
+    pool.try_submit(3).unwrap();
+    assert_eq!(pool.try_submit(4), Err(4));
+
+    for _ in 0..3 {
+        release.send(()).unwrap();
+    }
+    drop(pool);
+    assert_eq!(*done.lock().unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn keeps_the_worker_after_a_job_panics() {
+    let done = Arc::new(Mutex::new(Vec::new()));
+    let pool = {
+        let done = Arc::clone(&done);
+        ThreadPool::new(config(1, 4), move |job: usize, id| {
+            assert_ne!(job, 0, "job 0 panics");
+            done.lock().unwrap().push((job, id));
+        })
+    };
+    for job in [0, 1, 0, 2] {
+        pool.try_submit(job).unwrap();
+    }
+    drop(pool);
+    assert_eq!(*done.lock().unwrap(), [(1, 1), (2, 1)]);
+}
+
+#[test]
+fn drop_joins_every_worker_after_queued_jobs() {
+    let finished = Arc::new(AtomicUsize::new(0));
+    let pool = {
+        let finished = Arc::clone(&finished);
+        ThreadPool::new(config(2, 8), move |(), _| {
+            thread::sleep(Duration::from_millis(20));
+            finished.fetch_add(1, Ordering::SeqCst);
+        })
+    };
+    for _ in 0..6 {
+        pool.try_submit(()).unwrap();
+    }
+    drop(pool);
+    // drop คืนค่าหลังงานทั้งหมด (รวมที่ยังรอในคิว) เสร็จแล้ว
+    assert_eq!(finished.load(Ordering::SeqCst), 6);
+    assert_eq!(Arc::strong_count(&finished), 1, "workers released the handler");
+}
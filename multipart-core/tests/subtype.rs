@@ -0,0 +1,134 @@
+//! Tests ของ body ที่ไม่ใช่ `multipart/form-data`: mixed, related และ byteranges
+
+use multipart_core::subtype::{ContentRange, Related, cid_reference};
+use multipart_core::{Event, MediaType, MultipartError, PartHeaders, StreamingParser, Subtype};
+
+type Parts = Vec<(PartHeaders, Vec<u8>)>;
+
+/// parse ทั้ง body คืน subtype และ headers กับข้อมูลของทุก part
+fn parse(content_type: &str, body: &[u8]) -> Result<(Subtype, Parts), MultipartError> {
+    let mut parser = StreamingParser::for_media_type(&MediaType::parse(content_type)?)?;
+    let mut parts: Parts = Vec::new();
+    for chunk in body.chunks(5) {
+        parser.feed(chunk);
+        while let Some(event) = parser.next_event()? {
+            match event {
+                Event::PartStart { headers } => parts.push((headers, Vec::new())),
+                Event::PartData(data) => parts.last_mut().unwrap().1.extend_from_slice(data),
+                Event::PartEnd | Event::Finished => {}
+            }
+        }
+    }
+    parser.end_of_input();
+    assert_eq!(parser.next_event()?, None);
+    assert!(parser.is_complete());
+    Ok((parser.subtype().clone(), parts))
+}
+
+#[test]
+fn exposes_subtype() {
+    assert_eq!(Subtype::parse("Form-Data"), Subtype::FormData);
+    assert_eq!(Subtype::parse("x-custom"), Subtype::Other("x-custom".to_string()));
+    assert_eq!(Subtype::ByteRanges.to_string(), "multipart/byteranges");
+    assert_eq!(Subtype::of(&MediaType::parse("text/plain").unwrap()), None);
+
+    let err = StreamingParser::for_media_type(&MediaType::parse("application/json").unwrap()).err().unwrap();
+    assert!(matches!(err, MultipartError::UnsupportedMediaType(_)));
+    let err = StreamingParser::for_media_type(&MediaType::parse("multipart/mixed").unwrap()).err().unwrap();
+    assert!(matches!(err, MultipartError::MissingBoundary));
+
+    // form-data ยังบังคับ Content-Disposition name
+    let body = b"--b\r\nContent-Type: text/plain\r\n\r\nx\r\n--b--";
+    let err = parse("multipart/form-data; boundary=b", body).unwrap_err();
+    assert!(matches!(err, MultipartError::MalformedPartHeaders(_)));
+}
+
+#[test]
+fn mixed_parts_are_named_by_position() {
+    let body = b"preamble\r\n--b\r\n\
+        Content-Type: text/plain\r\n\r\n\
+        hello\r\n\
+        --b\r\n\
+        Content-Disposition: attachment; filename=\"a.txt\"\r\n\r\n\
+        file\r\n\
+        --b--\r\n";
+
+    let (subtype, parts) = parse("multipart/mixed; boundary=b", body).unwrap();
+    assert_eq!(subtype, Subtype::Mixed);
+    assert_eq!(parts[0].0.name, "0");
+    assert_eq!(parts[0].1, b"hello");
+    assert_eq!(parts[1].0.name, "1");
+    assert_eq!(parts[1].0.filename(), Some("a.txt"));
+}
+
+#[test]
+fn resolves_related_root_and_content_ids() {
+    // SOAP/MTOM: root ระบุด้วย start และไม่ได้อยู่เป็น part แรก
+    let content_type = "multipart/related; type=\"application/xop+xml\"; start=\"<root@example.com>\"; \
+                        start-info=\"text/xml\"; boundary=mime";
+    let body = b"--mime\r\n\
+        Content-Type: image/png\r\n\
+        Content-ID: <image%1@example.com>\r\n\r\n\
+        PNG\r\n\
+        --mime\r\n\
+        Content-Type: application/xop+xml\r\n\
+        Content-ID: <root@example.com>\r\n\r\n\
+        <Include href=\"cid:image%251@example.com\"/>\r\n\
+        --mime--\r\n";
+
+    let media_type = MediaType::parse(content_type).unwrap();
+    let related = Related::from_media_type(&media_type).unwrap();
+    assert_eq!(related.root_type.as_deref(), Some("application/xop+xml"));
+    assert_eq!(related.start.as_deref(), Some("root@example.com"));
+    assert_eq!(related.start_info.as_deref(), Some("text/xml"));
+
+    let (subtype, parts) = parse(content_type, body).unwrap();
+    assert_eq!(subtype, Subtype::Related);
+    let roots: Vec<bool> = parts.iter().enumerate().map(|(i, (headers, _))| related.is_root(i, headers)).collect();
+    assert_eq!(roots, [false, true]);
+
+    // reference ใน root ชี้ไปที่ part แรก
+    let reference = cid_reference("cid:image%251@example.com").unwrap();
+    assert_eq!(parts[0].0.content_id(), Some(reference.as_str()));
+    assert_eq!(cid_reference("CID:a%40b"), Some("a@b".to_string()));
+    assert_eq!(cid_reference("http://x"), None);
+    assert_eq!(cid_reference("cid:bad%4"), None);
+    assert_eq!(cid_reference("cid:bad%+4@example.com"), None);
+
+    // ไม่มี start: root คือ part แรก (แบบ metadata + media ของ Google Drive)
+    let related = Related::from_media_type(&MediaType::parse("multipart/related; boundary=x").unwrap()).unwrap();
+    assert!(related.is_root(0, &parts[1].0));
+    assert!(!related.is_root(1, &parts[0].0));
+    assert!(Related::from_media_type(&MediaType::parse("multipart/mixed; boundary=x").unwrap()).is_err());
+}
+
+#[test]
+fn reads_content_range_of_byteranges_parts() {
+    let body = b"--r\r\n\
+        Content-Type: text/plain\r\n\
+        Content-Range: bytes 0-4/26\r\n\r\n\
+        abcde\r\n\
+        --r\r\n\
+        Content-Type: text/plain\r\n\
+        Content-Range: bytes 20-25/*\r\n\r\n\
+        uvwxyz\r\n\
+        --r--\r\n";
+
+    let (subtype, parts) = parse("multipart/byteranges; boundary=r", body).unwrap();
+    assert_eq!(subtype, Subtype::ByteRanges);
+    let ranges: Vec<ContentRange> = parts.iter().map(|(headers, _)| headers.content_range().unwrap().unwrap()).collect();
+    assert_eq!(
+        ranges,
+        [
+            ContentRange { first: 0, last: 4, complete_length: Some(26) },
+            ContentRange { first: 20, last: 25, complete_length: None },
+        ]
+    );
+    for ((_, data), range) in parts.iter().zip(&ranges) {
+        assert_eq!(data.len() as u64, range.length());
+    }
+
+    for invalid in ["bytes */26", "bytes 5-4/26", "bytes 0-26/26", "items 0-1/2", "bytes 0-1", "bytes -1-2/5", "bytes 0-+1/5"] {
+        assert!(ContentRange::parse(invalid).is_err(), "{}", invalid);
+    }
+}
@@ -0,0 +1,77 @@
+//! Tests ของ decoder `Content-Transfer-Encoding` ที่ต้องได้ผลเดียวกันไม่ว่าข้อมูลจะถูกตัดตรงไหน
+
+use multipart_core::MultipartError;
+use multipart_core::transfer::{TransferDecoder, TransferEncoding};
+
+fn decode_in_chunks(encoding: TransferEncoding, input: &[u8], chunk_size: usize) -> Result<Vec<u8>, MultipartError> {
+    let mut decoder = TransferDecoder::new(encoding);
+    let mut out = Vec::new();
+    for chunk in input.chunks(chunk_size) {
+        decoder.decode(chunk, &mut out)?;
+    }
+    decoder.finish()?;
+    Ok(out)
+}
+
+/// decode ทุกขนาด chunk แล้วตรวจว่าได้ผลเหมือนกันหมด
+fn decode_every_split(encoding: TransferEncoding, input: &[u8]) -> Result<Vec<u8>, MultipartError> {
+    let expected = decode_in_chunks(encoding, input, input.len().max(1));
+    for chunk_size in 1..input.len() {
+        let actual = decode_in_chunks(encoding, input, chunk_size);
+        match (&expected, &actual) {
+            (Ok(e), Ok(a)) => assert_eq!(e, a, "chunk size {}", chunk_size),
+            (Err(e), Err(a)) => assert_eq!(e.to_string(), a.to_string(), "chunk size {}", chunk_size),
+            _ => panic!("chunk size {}: {:?} vs {:?}", chunk_size, expected, actual),
+        }
+    }
+    expected
+}
+
+#[test]
+fn parses_encoding_names() {
+    assert_eq!(TransferEncoding::parse("BASE64").unwrap(), TransferEncoding::Base64);
+    assert_eq!(TransferEncoding::parse(" Quoted-Printable ").unwrap(), TransferEncoding::QuotedPrintable);
+    for identity in ["7bit", "8bit", "binary"] {
+        assert_eq!(TransferEncoding::parse(identity).unwrap(), TransferEncoding::Identity);
+    }
+    let err = TransferEncoding::parse("x-uuencode").unwrap_err();
+    assert!(matches!(err, MultipartError::InvalidContentTransferEncoding(_)));
+    assert_eq!(err.status().0, 400);
+}
+
+#[test]
+fn decodes_base64_across_splits() {
+    let base64 = TransferEncoding::Base64;
+    assert_eq!(decode_every_split(base64, b"").unwrap(), b"");
+    assert_eq!(decode_every_split(base64, b"aGVsbG8gd29ybGQ=\r\n").unwrap(), b"hello world");
+    assert_eq!(decode_every_split(base64, b"YQ==").unwrap(), b"a");
+    // ตัดบรรทัดแบบ MIME และ whitespace ระหว่างทาง
+    assert_eq!(
+        decode_every_split(base64, b"AAEC\r\nA/7/\r\n +/ 8=\r\n").unwrap(),
+        [0x00, 0x01, 0x02, 0x03, 0xfe, 0xff, 0xfb, 0xff]
+    );
+
+    for malformed in [&b"aGVsbG8"[..], b"YQ=", b"Y===", b"YQ==YQ==", b"aGV*", b"=AAA"] {
+        let err = decode_every_split(base64, malformed).unwrap_err();
+        assert!(matches!(err, MultipartError::InvalidContentTransferEncoding(_)), "{:?}: {:?}", malformed, err);
+    }
+}
+
+#[test]
+fn decodes_quoted_printable_across_splits() {
+    let qp = TransferEncoding::QuotedPrintable;
+    assert_eq!(
+        decode_every_split(qp, b"caf=C3=A9 au lait\r\nline two =3D ok").unwrap(),
+        "café au lait\r\nline two = ok".as_bytes()
+    );
+    // soft line break (CRLF หรือ LF, มี whitespace หลัง `=` ได้) และ `=` ท้ายข้อมูล
+    assert_eq!(decode_every_split(qp, b"long =\r\nline=  \nend=").unwrap(), b"long lineend");
+    // whitespace ท้ายบรรทัดถูกทิ้ง แต่กลางบรรทัดยังอยู่
+    assert_eq!(decode_every_split(qp, b"a \t b  \r\nc\t\r\n  ").unwrap(), b"a \t b\r\nc\r\n");
+    assert_eq!(decode_every_split(qp, b"lower =c3=a9").unwrap(), "lower é".as_bytes());
+
+    for malformed in [&b"bad =G1"[..], b"bad =4", b"bad =4x", b"bad = x\r\n"] {
+        let err = decode_every_split(qp, malformed).unwrap_err();
+        assert!(matches!(err, MultipartError::InvalidContentTransferEncoding(_)), "{:?}: {:?}", malformed, err);
+    }
+}
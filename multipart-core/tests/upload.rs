@@ -0,0 +1,477 @@
+//! Tests ของ `UploadProcessor` และ headers ของแต่ละ part
+
+use std::path::{Path, PathBuf};
+
+use multipart_core::digest::{Crc32c, Md5, Sha256, to_base64, to_hex};
+use multipart_core::{
+    DigestAlgorithm, FsyncMode, Limits, MultipartError, NamingStrategy, PartHeaders, PartType, Stats, UploadProcessor,
+};
+
+/// directory ชั่วคราวแยกตาม test เพื่อให้รันพร้อมกันได้
+fn upload_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("multipart-core-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn upload(dir: &Path, body: &[u8]) -> Result<Stats, MultipartError> {
+    upload_with(dir, body, NamingStrategy::default())
+}
+
+fn upload_with(dir: &Path, body: &[u8], naming: NamingStrategy) -> Result<Stats, MultipartError> {
+    let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap())?.with_naming(naming);
+    for chunk in body.chunks(7) {
+        upload.process_chunk(chunk)?;
+    }
+    upload.finalize()?;
+    Ok(upload.get_stats().clone())
+}
+
+#[test]
+fn keeps_every_part_header() {
+    let headers = PartHeaders::parse(
+        "Content-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\n\
+         Content-Type: text/plain; charset=utf-8\r\n\
+         Content-Transfer-Encoding: 8bit\r\n\
+         Content-ID: <doc@example>\r\n\
+         X-Trace: one\r\n\
+         x-trace: two\r\n\
+         X-Folded: a\r\n\tb",
+    )
+    .unwrap();
+
+    assert_eq!(headers.headers.len(), 6);
+    assert_eq!(headers.content_type(), Some("text/plain; charset=utf-8"));
+    assert_eq!(headers.header("content-transfer-encoding"), Some("8bit"));
+    assert_eq!(headers.header("CONTENT-ID"), Some("<doc@example>"));
+    assert_eq!(headers.header("x-trace"), Some("one, two"));
+    assert_eq!(headers.header("x-folded"), Some("a b"));
+    assert_eq!(
+        headers.part_type,
+        PartType::File {
+            filename: "a.txt".to_string(),
+            content_type: "text/plain; charset=utf-8".to_string(),
+        }
+    );
+
+    // field ธรรมดาก็เก็บ headers ไว้เหมือนกัน
+    let headers = PartHeaders::parse("Content-Disposition: form-data; name=\"n\"\r\nContent-Type: application/json")
+        .unwrap();
+    assert_eq!(headers.part_type, PartType::Field);
+    assert_eq!(headers.content_type(), Some("application/json"));
+}
+
+#[test]
+fn rejects_bad_part_header_lines() {
+    for block in [
+        "Content-Disposition: form-data; name=\"a\"\r\nContent-Type: text/plain\r\ncontent-type: text/html",
+        "Content-Disposition: form-data; name=\"a\"\r\nBad Name: x",
+        " folded: first\r\nContent-Disposition: form-data; name=\"a\"",
+        "Content-Disposition: form-data; name=\"a\"\r\nno colon",
+    ] {
+        let err = PartHeaders::parse(block).unwrap_err();
+        assert!(matches!(err, MultipartError::MalformedPartHeaders(_)), "{:?}: {:?}", block, err);
+    }
+}
+
+#[test]
+fn records_content_type_and_headers_of_saved_files() {
+    let dir = upload_dir("headers");
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+        hello\r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"photo\"; filename=\"p.png\"\r\n\
+        Content-Type: image/png\r\n\
+        X-Checksum: abc\r\n\r\n\
+        PNGDATA\r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"raw\"; filename=\"r.bin\"\r\n\r\n\
+        raw\r\n\
+        --b--\r\n";
+
+    let stats = upload(&dir, body).unwrap();
+    assert_eq!(stats.field("title"), Some("hello"));
+    assert_eq!(stats.files_saved.len(), 2);
+
+    let photo = &stats.files_saved[0];
+    assert_eq!(photo.content_type, "image/png");
+    assert_eq!(photo.headers.get("x-checksum"), Some("abc"));
+    assert_eq!(photo.size, 7);
+    assert_eq!(std::fs::read(&photo.path).unwrap(), b"PNGDATA");
+
+    let raw = &stats.files_saved[1];
+    assert_eq!(raw.content_type, "");
+    assert_eq!(raw.headers.len(), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+fn file_body(filename: &str, content: &str) -> Vec<u8> {
+    format!(
+        "--b\r\nContent-Disposition: form-data; name=\"f\"; filename=\"{}\"\r\n\r\n{}\r\n--b--\r\n",
+        filename, content
+    )
+    .into_bytes()
+}
+
+#[test]
+fn never_writes_outside_upload_dir() {
+    let dir = upload_dir("traversal");
+
+    let err = upload(&dir, &file_body("../../escape.txt", "x")).unwrap_err();
+    assert!(matches!(err, MultipartError::InvalidFilename(_)));
+    assert!(!dir.parent().unwrap().join("escape.txt").exists());
+
+    let stats = upload(&dir, &file_body("C:\\tmp\\report.txt", "x")).unwrap();
+    assert_eq!(stats.files_saved[0].filename, "report.txt");
+    assert_eq!(stats.files_saved[0].original_filename, "C:\\tmp\\report.txt");
+    assert_eq!(Path::new(&stats.files_saved[0].path), dir.join("report.txt"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn adds_suffix_on_collision() {
+    let dir = upload_dir("suffix");
+
+    let names: Vec<String> = ["one", "two", "three"]
+        .iter()
+        .map(|content| upload(&dir, &file_body("photo.jpg", content)).unwrap().files_saved[0].filename.clone())
+        .collect();
+    assert_eq!(names, ["photo.jpg", "photo-1.jpg", "photo-2.jpg"]);
+    assert_eq!(std::fs::read(dir.join("photo.jpg")).unwrap(), b"one");
+    assert_eq!(std::fs::read(dir.join("photo-2.jpg")).unwrap(), b"three");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn applies_naming_strategies() {
+    let dir = upload_dir("naming");
+
+    upload_with(&dir, &file_body("a.txt", "old"), NamingStrategy::Original).unwrap();
+    let stats = upload_with(&dir, &file_body("a.txt", "new"), NamingStrategy::Original).unwrap();
+    assert_eq!(stats.files_saved[0].filename, "a.txt");
+    assert_eq!(std::fs::read(dir.join("a.txt")).unwrap(), b"new");
+
+    let stats = upload_with(&dir, &file_body("ภาพ.png", "img"), NamingStrategy::Uuid).unwrap();
+    let file = &stats.files_saved[0];
+    assert_eq!(file.filename.len(), 36 + ".png".len());
+    assert!(file.filename.ends_with(".png"));
+    assert_eq!(file.original_filename, "ภาพ.png");
+    assert_eq!(std::fs::read(&file.path).unwrap(), b"img");
+
+    let stats = upload_with(&dir, &file_body("data.csv", "1,2,3"), NamingStrategy::ContentHash).unwrap();
+    let file = &stats.files_saved[0];
+    assert_eq!(file.filename, format!("{}.csv", to_hex(&Sha256::digest(b"1,2,3"))));
+    assert_eq!(std::fs::read(&file.path).unwrap(), b"1,2,3");
+    // ไม่มีไฟล์ชั่วคราวค้างอยู่
+    assert!(std::fs::read_dir(&dir).unwrap().all(|entry| !entry.unwrap().file_name().to_string_lossy().ends_with(".tmp")));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn skips_file_inputs_without_a_file() {
+    let dir = upload_dir("empty-filename");
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"note\"\r\n\r\n\
+        hi\r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"attachment\"; filename=\"\"\r\n\
+        Content-Type: application/octet-stream\r\n\r\n\
+        \r\n\
+        --b--\r\n";
+
+    let stats = upload(&dir, body).unwrap();
+    assert_eq!(stats.field("note"), Some("hi"));
+    assert_eq!(stats.files_count, 0);
+    assert!(stats.files_saved.is_empty());
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn keeps_repeated_fields_in_order() {
+    let dir = upload_dir("repeated-fields");
+    let mut body = Vec::new();
+    for (name, value) in [("tag", "a"), ("user", "alice"), ("tag", "b"), ("tag", "")] {
+        body.extend(format!("--b\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", name, value).bytes());
+    }
+    body.extend(b"--b--\r\n");
+
+    let stats = upload(&dir, &body).unwrap();
+    assert_eq!(stats.fields_count, 4);
+    assert_eq!(stats.field_values("tag"), ["a", "b", ""]);
+    assert_eq!(stats.field("tag"), Some("a"));
+    assert_eq!(stats.field("user"), Some("alice"));
+    assert_eq!(stats.field("missing"), None);
+    let names: Vec<&str> = stats.fields.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["tag", "user", "tag", "tag"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// ชื่อไฟล์ทั้งหมดใน directory เรียงตามชื่อ
+fn list(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn renames_into_place_only_after_part_ends() {
+    let dir = upload_dir("atomic");
+    let body = file_body("big.bin", "0123456789");
+    let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap()).unwrap();
+
+    // ยังไม่เจอ delimiter ที่ปิด part มีแค่ไฟล์ชั่วคราว
+    upload.process_chunk(&body[..body.len() - 12]).unwrap();
+    let names = list(&dir);
+    assert_eq!(names.len(), 1);
+    assert!(names[0].starts_with(".upload-") && names[0].ends_with(".tmp"), "{:?}", names);
+
+    upload.process_chunk(&body[body.len() - 12..]).unwrap();
+    upload.finalize().unwrap();
+    assert_eq!(list(&dir), ["big.bin"]);
+    assert_eq!(std::fs::read(dir.join("big.bin")).unwrap(), b"0123456789");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn removes_partial_files_on_truncation_and_disconnect() {
+    let dir = upload_dir("partial");
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"done\"; filename=\"done.txt\"\r\n\r\n\
+        complete\r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"cut\"; filename=\"cut.txt\"\r\n\r\n\
+        half of the da";
+
+    // body ถูกตัด: part ที่จบแล้วยังอยู่ ส่วน part ที่ค้างต้องไม่มีทั้งชื่อจริงและไฟล์ชั่วคราว
+    let err = upload(&dir, body).unwrap_err();
+    assert!(matches!(err, MultipartError::UnexpectedEof));
+    assert_eq!(list(&dir), ["done.txt"]);
+
+    // client หลุดแล้วผู้เรียกทิ้ง processor ไปโดยไม่เรียก finalize
+    std::fs::remove_dir_all(&dir).unwrap();
+    {
+        let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap()).unwrap();
+        upload.process_chunk(body).unwrap();
+        assert_eq!(list(&dir).len(), 2);
+    }
+    assert_eq!(list(&dir), ["done.txt"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn keeps_completed_files_when_a_later_part_is_malformed() {
+    let dir = upload_dir("error");
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"f\"; filename=\"a.txt\"\r\n\r\n\
+        data\r\n\
+        --b\r\n\
+        no colon here\r\n\r\n\
+        x\r\n\
+        --b--\r\n";
+
+    let err = upload(&dir, body).unwrap_err();
+    assert!(matches!(err, MultipartError::MalformedPartHeaders(_)));
+    assert_eq!(list(&dir), ["a.txt"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn fsync_modes_still_commit_files() {
+    for fsync in [FsyncMode::Never, FsyncMode::File, FsyncMode::FileAndDirectory] {
+        let dir = upload_dir("fsync");
+        let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap()).unwrap().with_fsync(fsync);
+        upload.process_chunk(&file_body("s.txt", "synced")).unwrap();
+        upload.finalize().unwrap();
+        assert_eq!(std::fs::read(dir.join("s.txt")).unwrap(), b"synced", "{:?}", fsync);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    assert_eq!("full".parse(), Ok(FsyncMode::FileAndDirectory));
+    assert_eq!("File".parse(), Ok(FsyncMode::File));
+    assert!("sometimes".parse::<FsyncMode>().is_err());
+}
+
+#[test]
+fn computes_selected_digests_while_streaming() {
+    let dir = upload_dir("digests");
+    let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap())
+        .unwrap()
+        .with_digests(&[DigestAlgorithm::Md5, DigestAlgorithm::Crc32c]);
+    for chunk in file_body("a.txt", "hello").chunks(3) {
+        upload.process_chunk(chunk).unwrap();
+    }
+    upload.finalize().unwrap();
+
+    let file = &upload.get_stats().files_saved[0];
+    assert_eq!(file.digests.len(), 2);
+    assert_eq!(file.digest(DigestAlgorithm::Md5), Some(&Md5::digest(b"hello")[..]));
+    assert_eq!(file.digest(DigestAlgorithm::Crc32c), Some(&Crc32c::digest(b"hello")[..]));
+    assert_eq!(file.digest(DigestAlgorithm::Sha256), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn verifies_client_supplied_digests() {
+    let md5 = to_base64(&Md5::digest(b"hello"));
+    let part = |extra_header: &str, content: &str| {
+        format!(
+            "--b\r\nContent-Disposition: form-data; name=\"f\"; filename=\"a.txt\"\r\n{}\r\n{}\r\n--b--\r\n",
+            extra_header, content
+        )
+        .into_bytes()
+    };
+
+    // header ของ part ที่ตรงกันผ่าน และ digest ที่ใช้ตรวจถูกเก็บไว้ใน FileInfo
+    let dir = upload_dir("verify-ok");
+    let stats = upload(&dir, &part(&format!("Content-MD5: {}\r\n", md5), "hello")).unwrap();
+    assert_eq!(stats.files_saved[0].digest(DigestAlgorithm::Md5), Some(&Md5::digest(b"hello")[..]));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // ไม่ตรงกัน: part ล้มและไม่เหลือทั้งไฟล์จริงและไฟล์ชั่วคราว
+    let dir = upload_dir("verify-mismatch");
+    let header = format!("Repr-Digest: sha-256=:{}:\r\n", to_base64(&Sha256::digest(b"other")));
+    let err = upload(&dir, &part(&header, "hello")).unwrap_err();
+    assert!(matches!(err, MultipartError::DigestMismatch { algorithm: "sha-256", .. }), "{:?}", err);
+    assert_eq!(err.status().0, 400);
+    assert!(list(&dir).is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // field แบบ S3 POST ที่ส่งมาก่อนไฟล์ใช้ตรวจไฟล์ถัดไป
+    let dir = upload_dir("verify-field");
+    let field = |value: &str| {
+        let mut body = format!("--b\r\nContent-Disposition: form-data; name=\"Content-MD5\"\r\n\r\n{}\r\n", value)
+            .into_bytes();
+        body.extend(file_body("a.txt", "hello"));
+        body
+    };
+    assert!(upload(&dir, &field(&md5)).is_ok());
+    let err = upload(&dir, &field(&to_base64(&Md5::digest(b"hell")))).unwrap_err();
+    assert!(matches!(err, MultipartError::DigestMismatch { algorithm: "md5", .. }), "{:?}", err);
+    assert_eq!(list(&dir), ["a.txt"]);
+
+    // field ชื่อ digest ที่ไม่ใช่ digest เป็นแค่ field ธรรมดา
+    let mut body = b"--b\r\nContent-Disposition: form-data; name=\"digest\"\r\n\r\nweekly\r\n".to_vec();
+    body.extend(file_body("b.txt", "hello"));
+    assert_eq!(upload(&dir, &body).unwrap().field("digest"), Some("weekly"));
+
+    // ปิดการตรวจได้
+    let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap())
+        .unwrap()
+        .with_digest_verification(false);
+    upload.process_chunk(&part(&header, "hello")).unwrap();
+    upload.finalize().unwrap();
+    assert!(upload.get_stats().files_saved[0].digests.is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn saves_files_from_nested_multipart_mixed() {
+    let body = b"--b\r\n\
+        Content-Disposition: form-data; name=\"files\"\r\n\
+        Content-Type: multipart/mixed; boundary=outer\r\n\r\n\
+        --outer\r\n\
+        Content-Disposition: file; filename=\"a.txt\"\r\n\
+        Content-Type: text/plain\r\n\r\n\
+        first\r\n\
+        --outer\r\n\
+        Content-Type: multipart/mixed; boundary=deeper\r\n\r\n\
+        --deeper\r\n\
+        Content-Disposition: attachment; filename=\"a.txt\"\r\n\r\n\
+        second\r\n\
+        --deeper\r\n\
+        Content-Type: text/plain\r\n\r\n\
+        note\r\n\
+        --deeper--\r\n\
+        --outer--\r\n\
+        --b--\r\n";
+    let upload_with_depth = |dir: &Path, depth: usize| {
+        let limits = Limits {
+            max_nesting_depth: depth,
+            ..Limits::default()
+        };
+        let mut upload = UploadProcessor::new("--b", dir.to_str().unwrap())?.with_limits(limits);
+        for chunk in body.chunks(7) {
+            upload.process_chunk(chunk)?;
+        }
+        upload.finalize()?;
+        Ok::<_, MultipartError>(upload.get_stats().clone())
+    };
+
+    let dir = upload_dir("nested");
+    let stats = upload_with_depth(&dir, 2).unwrap();
+    let files: Vec<_> = stats.files_saved.iter().map(|file| (file.field_name.as_str(), file.filename.as_str())).collect();
+    assert_eq!(files, [("files[0]", "a.txt"), ("files[1][0]", "a-1.txt")]);
+    assert_eq!(std::fs::read_to_string(dir.join("a-1.txt")).unwrap(), "second");
+    assert_eq!(stats.field("files[1][1]"), Some("note"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // depth 1 (default) รับชั้นเดียว ส่วน 0 ไม่รับ body ที่ซ้อนเลย
+    for depth in [1, 0] {
+        let dir = upload_dir("nested-limit");
+        let err = upload_with_depth(&dir, depth).unwrap_err();
+        assert!(matches!(err, MultipartError::LimitExceeded { limit: "nesting depth", .. }), "{:?}", err);
+        assert_eq!(err.status().0, 413);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+#[test]
+fn decodes_content_transfer_encoding() {
+    let content = "binary \u{0}\u{1} data";
+    let body = format!(
+        "--b\r\n\
+         Content-Disposition: form-data; name=\"note\"\r\n\
+         Content-Transfer-Encoding: quoted-printable\r\n\r\n\
+         caf=C3=A9 =\r\nnoir\r\n\
+         --b\r\n\
+         Content-Disposition: form-data; name=\"doc\"; filename=\"a.bin\"\r\n\
+         Content-Transfer-Encoding: base64\r\n\
+         Content-MD5: {}\r\n\r\n\
+         {}\r\n\
+         --b--\r\n",
+        to_base64(&Md5::digest(content.as_bytes())),
+        to_base64(content.as_bytes())
+    );
+
+    let dir = upload_dir("transfer");
+    let stats = upload(&dir, body.as_bytes()).unwrap();
+    assert_eq!(stats.field("note"), Some("café noir"));
+    assert_eq!(std::fs::read_to_string(dir.join("a.bin")).unwrap(), content);
+    assert_eq!(stats.files_saved[0].size, content.len());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // ปิดการ decode: เก็บตามที่ส่งมา (ไม่ตรวจ Content-MD5 เพราะเป็นของข้อมูลที่ decode แล้ว)
+    let dir = upload_dir("transfer-raw");
+    let mut raw = UploadProcessor::new("--b", dir.to_str().unwrap())
+        .unwrap()
+        .with_transfer_decoding(false)
+        .with_digest_verification(false);
+    raw.process_chunk(body.as_bytes()).unwrap();
+    raw.finalize().unwrap();
+    assert_eq!(std::fs::read_to_string(dir.join("a.bin")).unwrap(), to_base64(content.as_bytes()));
+    assert_eq!(raw.get_stats().field("note"), Some("caf=C3=A9 =\r\nnoir"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // base64 ผิดรูปแบบ: 400 และไม่มีไฟล์ค้าง
+    let dir = upload_dir("transfer-bad");
+    let malformed = body.replace(&to_base64(content.as_bytes()), "not*base64");
+    let err = upload(&dir, malformed.as_bytes()).unwrap_err();
+    assert!(matches!(err, MultipartError::InvalidContentTransferEncoding(_)), "{:?}", err);
+    assert_eq!(err.status().0, 400);
+    assert!(list(&dir).is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
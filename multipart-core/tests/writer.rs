@@ -0,0 +1,179 @@
+//! Tests ของ `MultipartWriter` โดย parse body ที่สร้างกลับด้วย parser ของ crate เอง
+
+use std::io::Read;
+use std::path::PathBuf;
+
+use multipart_core::mime::validate_boundary;
+use multipart_core::subtype::ContentRange;
+use multipart_core::{Event, MediaType, MultipartError, MultipartWriter, PartHeaders, StreamingParser, Subtype, UploadProcessor};
+
+type Parts = Vec<(PartHeaders, Vec<u8>)>;
+
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("multipart-core-writer-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// เขียน body แล้วตรวจว่า `content_length` ตรงกับที่เขียนจริง
+fn write(writer: MultipartWriter) -> (String, Vec<u8>) {
+    let content_type = writer.content_type();
+    let content_length = writer.content_length();
+    let mut body = Vec::new();
+    let written = writer.write_to(&mut body).unwrap();
+    assert_eq!(written, body.len() as u64);
+    if let Some(content_length) = content_length {
+        assert_eq!(content_length, written);
+    }
+    (content_type, body)
+}
+
+fn parse(content_type: &str, body: &[u8]) -> Parts {
+    let mut parser = StreamingParser::for_media_type(&MediaType::parse(content_type).unwrap()).unwrap();
+    let mut parts: Parts = Vec::new();
+    for chunk in body.chunks(3) {
+        parser.feed(chunk);
+        while let Some(event) = parser.next_event().unwrap() {
+            match event {
+                Event::PartStart { headers } => parts.push((headers, Vec::new())),
+                Event::PartData(data) => parts.last_mut().unwrap().1.extend_from_slice(data),
+                Event::PartEnd | Event::Finished => {}
+            }
+        }
+    }
+    assert!(parser.is_complete());
+    parts
+}
+
+/// source ที่ไม่บอกขนาดและอ่านได้ทีละ byte
+struct Trickle(std::vec::IntoIter<u8>);
+
+impl Read for Trickle {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match (self.0.next(), buf.first_mut()) {
+            (Some(byte), Some(slot)) => {
+                *slot = byte;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn generates_random_valid_boundaries() {
+    let first = MultipartWriter::new();
+    let second = MultipartWriter::new();
+    assert_ne!(first.boundary(), second.boundary());
+    validate_boundary(first.boundary()).unwrap();
+    assert_eq!(first.content_type(), format!("multipart/form-data; boundary={}", first.boundary()));
+
+    // boundary ที่มี tspecials ต้องอยู่ใน quote
+    let writer = MultipartWriter::new().with_boundary("a:b c").unwrap();
+    assert_eq!(writer.content_type(), "multipart/form-data; boundary=\"a:b c\"");
+    let err = MultipartWriter::new().with_boundary(&"x".repeat(71)).err().unwrap();
+    assert!(matches!(err, MultipartError::InvalidBoundary(_)));
+
+    // ไม่มี part เลยก็ยังเป็น body ที่ถูกต้อง
+    let (content_type, body) = write(MultipartWriter::new().with_boundary("empty").unwrap());
+    assert_eq!(body, b"--empty--\r\n");
+    assert!(parse(&content_type, &body).is_empty());
+}
+
+#[test]
+fn round_trips_fields_and_files() {
+    let dir = temp_dir("round-trip");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("report.csv");
+    std::fs::write(&path, b"a,b\r\n--not-a-boundary\r\n1,2").unwrap();
+
+    let mut writer = MultipartWriter::new();
+    writer.add_field("title", "สวัสดี\r\nชาวโลก").add_field("say \"hi\"", "");
+    writer
+        .add_file("doc", "ราย\"งาน\".txt", "text/plain; charset=utf-8", &b"hello"[..], Some(5))
+        .unwrap()
+        .add_file("bin", "quote\"and\nnewline.bin", "application/octet-stream", Trickle(vec![0, 13, 10, 45, 45].into_iter()), None)
+        .unwrap()
+        .add_file_path("csv", &path, "text/csv")
+        .unwrap();
+    assert_eq!(writer.len(), 5);
+    // ไม่รู้ขนาดของ part หนึ่ง จึงไม่รู้ Content-Length
+    assert_eq!(writer.content_length(), None);
+
+    let (content_type, body) = write(writer);
+    let text = String::from_utf8_lossy(&body);
+    assert!(text.contains("name=\"say %22hi%22\""));
+    assert!(text.contains("filename=\"quote%22and%0Anewline.bin\""));
+    assert!(text.contains("filename=\"___%22___%22.txt\"; filename*=UTF-8''%E0%B8%A3%E0%B8%B2%E0%B8%A2%22"));
+
+    let parts = parse(&content_type, &body);
+    let names: Vec<&str> = parts.iter().map(|(headers, _)| headers.name.as_str()).collect();
+    assert_eq!(names, ["title", "say \"hi\"", "doc", "bin", "csv"]);
+    assert_eq!(parts[0].1, "สวัสดี\r\nชาวโลก".as_bytes());
+    assert_eq!(parts[1].1, b"");
+    assert_eq!(parts[2].0.filename(), Some("ราย\"งาน\".txt"));
+    assert_eq!(parts[2].0.content_type(), Some("text/plain; charset=utf-8"));
+    assert_eq!(parts[3].0.filename(), Some("quote\"and\nnewline.bin"));
+    assert_eq!(parts[3].1, [0, 13, 10, 45, 45]);
+    assert_eq!(parts[4].0.filename(), Some("report.csv"));
+    assert_eq!(parts[4].1, std::fs::read(&path).unwrap());
+
+    // ผ่าน UploadProcessor ได้เหมือน request จาก browser
+    let saved = dir.join("uploads");
+    let mut writer = MultipartWriter::new();
+    writer.add_field("n", "1").add_file_path("csv", &path, "text/csv").unwrap();
+    let delimiter = format!("--{}", writer.boundary());
+    let (_, body) = write(writer);
+    let mut processor = UploadProcessor::new(&delimiter, saved.to_str().unwrap()).unwrap();
+    for chunk in body.chunks(11) {
+        processor.process_chunk(chunk).unwrap();
+    }
+    processor.finalize().unwrap();
+    let stats = processor.get_stats();
+    assert_eq!(stats.field("n"), Some("1"));
+    assert_eq!(stats.files_saved[0].original_filename, "report.csv");
+    assert_eq!(std::fs::read(&stats.files_saved[0].path).unwrap(), std::fs::read(&path).unwrap());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn writes_byteranges_responses() {
+    let resource = b"abcdefghijklmnopqrstuvwxyz";
+    let mut writer = MultipartWriter::new().with_subtype(Subtype::ByteRanges);
+    for (first, last, complete_length) in [(0, 4, Some(26)), (20, 25, None)] {
+        let range = ContentRange { first, last, complete_length };
+        let slice = resource[first as usize..=last as usize].to_vec();
+        writer.add_range("text/plain", range, std::io::Cursor::new(slice)).unwrap();
+    }
+
+    let (content_type, body) = write(writer);
+    assert!(content_type.starts_with("multipart/byteranges; boundary="));
+    let parts = parse(&content_type, &body);
+    assert_eq!(parts[0].0.header("content-range"), Some("bytes 0-4/26"));
+    assert_eq!(parts[1].0.content_range().unwrap().unwrap(), ContentRange { first: 20, last: 25, complete_length: None });
+    assert_eq!(parts[0].1, b"abcde");
+    assert_eq!(parts[1].1, b"uvwxyz");
+}
+
+#[test]
+fn rejects_bad_headers_and_wrong_lengths() {
+    let mut writer = MultipartWriter::new().with_subtype(Subtype::Mixed);
+    for headers in [&[("Bad Name", "x")][..], &[("X-Inject", "a\r\nSet-Cookie: b")], &[("", "x")]] {
+        let err = writer.add_part(headers, &b""[..], Some(0)).err().unwrap();
+        assert!(matches!(err, MultipartError::MalformedPartHeaders(_)), "{:?}", headers);
+    }
+    assert!(writer.add_file("f", "a.txt", "text/plain\r\nX: y", &b""[..], None).is_err());
+    assert!(writer.is_empty());
+
+    let err = MultipartWriter::new().add_file_path("f", "/nonexistent/multipart-core", "text/plain").err().unwrap();
+    assert!(matches!(err, MultipartError::Io(_)));
+
+    // source สั้นหรือยาวกว่าที่บอกไว้ทำให้ Content-Length ผิด ต้อง error
+    for (data, len) in [(&b"abc"[..], 4), (&b"abcde"[..], 4)] {
+        let mut writer = MultipartWriter::new();
+        writer.add_part(&[("Content-Type", "text/plain")], data, Some(len)).unwrap();
+        let err = writer.write_to(&mut Vec::new()).unwrap_err();
+        assert!(matches!(err, MultipartError::Io(_)), "{:?}", err);
+    }
+}
@@ -1,6 +1,10 @@
 use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
 
+use multipart_core::http::{self, BodyFraming, Connection, HeadLimits, RequestHead};
+use multipart_core::{ChunkedDecoder, ChunkedEvent, MultipartError};
+use multipart_core::pool::{PoolConfig, ThreadPool};
+
 // ตั้ง buffer size ให้เล็กเพื่อให้เห็นการแบ่ง boundary ชัดเจน
 const BUFFER_SIZE: usize = 64;
 
@@ -42,7 +46,7 @@ fn visualize_boundary(data: &[u8], boundary_pattern: &str) {
     // ตรวจสอบ partial boundary ที่ท้าย chunk
     let boundary_bytes = boundary_pattern.as_bytes();
     for i in 1..boundary_bytes.len() {
-        if data.len() >= i && &data[data.len() - i..] == &boundary_bytes[..i] {
+        if data.len() >= i && data[data.len() - i..] == boundary_bytes[..i] {
             println!("\n⚠️  PARTIAL BOUNDARY at end ({} bytes): {:?}", 
                      i, 
                      String::from_utf8_lossy(&data[data.len() - i..]));
@@ -52,62 +56,132 @@ fn visualize_boundary(data: &[u8], boundary_pattern: &str) {
     }
 }
 
-fn handle_client(mut stream: TcpStream) {
+/// decode bytes ดิบของ chunked body พร้อมแสดง framing ของแต่ละ chunk
+/// คืนข้อมูล body ที่ decode แล้ว (ส่วนที่มี multipart boundary อยู่จริง)
+fn visualize_chunk_framing(decoder: &mut ChunkedDecoder, data: &[u8]) -> Result<Vec<u8>, MultipartError> {
+    let mut decoded = Vec::new();
+    decoder.feed(data);
+
+    println!("\n🧱 CHUNKED FRAMING:");
+    while let Some(event) = decoder.next_event()? {
+        match event {
+            ChunkedEvent::ChunkStart { size: 0, .. } => println!("  [last-chunk] 0"),
+            ChunkedEvent::ChunkStart { size, extensions } => {
+                if extensions.is_empty() {
+                    println!("  [chunk-size] 0x{:x} ({} bytes)", size, size);
+                } else {
+                    println!("  [chunk-size] 0x{:x} ({} bytes) ;{}", size, size, extensions);
+                }
+            }
+            ChunkedEvent::Data(bytes) => {
+                println!("  [data] {} bytes", bytes.len());
+                decoded.extend_from_slice(bytes);
+            }
+            ChunkedEvent::ChunkEnd => println!("  [CRLF] end of chunk"),
+            ChunkedEvent::Trailer { name, value } => println!("  [trailer] {}: {}", name, value),
+            ChunkedEvent::Finished => println!("  [end] chunked body complete"),
+        }
+    }
+
+    Ok(decoded)
+}
+
+fn handle_client(stream: TcpStream, worker: usize) {
+    let peer = stream
+        .peer_addr()
+        .map(|addr| addr.to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+
     print_separator();
-    println!("🔌 NEW CLIENT CONNECTED");
+    println!("🔌 NEW CLIENT CONNECTED: {} (worker #{})", peer, worker);
     print_separator();
 
+    // HTTP/1.1 keep-alive: อ่าน request ต่อบน connection เดิม (รวมถึงที่ pipeline มา) จนกว่าจะปิด
+    let mut conn = Connection::new(stream);
+    let limits = HeadLimits::default();
+    let mut request_num = 0;
+    loop {
+        // รอ request ถัดไปได้ไม่เกิน idle timeout
+        conn.get_mut().set_read_timeout(Some(http::DEFAULT_IDLE_TIMEOUT)).ok();
+        let head = match conn.read_head(&limits) {
+            Ok(Some(head)) => head,
+            Ok(None) | Err(MultipartError::Io(_)) => break,
+            Err(e) => {
+                send_error(conn.get_mut(), &e);
+                break;
+            }
+        };
+        // client ที่ส่ง head แล้วเงียบไปต้องไม่ถือ worker ไว้ตลอด
+        conn.get_mut().set_read_timeout(Some(http::DEFAULT_BODY_TIMEOUT)).ok();
+
+        request_num += 1;
+        println!("📨 REQUEST #{} on this connection", request_num);
+        if !handle_request(&mut conn, &head, worker, &peer) {
+            break;
+        }
+    }
+
+    println!("👋 CONNECTION CLOSED: {} ({} requests)", peer, request_num);
+    print_separator();
+}
+
+/// แสดง request หนึ่งตัว คืน `true` ถ้าเก็บ connection ไว้รับ request ถัดไปได้
+fn handle_request(conn: &mut Connection<TcpStream>, head: &RequestHead, worker: usize, peer: &str) -> bool {
     let mut buffer = [0u8; BUFFER_SIZE];
     let mut chunk_num = 0;
     let mut total_bytes = 0;
     let mut all_data = Vec::new();
 
-    // อ่าน HTTP headers ก่อนเพื่อหา boundary
-    let mut header_buffer = Vec::new();
-    let mut temp_buf = [0u8; 1];
-    let mut found_boundary = String::new();
-    let mut content_length = 0usize;
-    
-    // อ่าน headers จนเจอ \r\n\r\n
-    loop {
-        if stream.read(&mut temp_buf).unwrap_or(0) == 0 {
-            break;
-        }
-        header_buffer.push(temp_buf[0]);
-        
-        if header_buffer.len() >= 4 
-            && &header_buffer[header_buffer.len()-4..] == b"\r\n\r\n" {
-            break;
-        }
-    }
-    
-    // Parse boundary และ Content-Length จาก headers
-    let headers = String::from_utf8_lossy(&header_buffer);
+    // แสดง request line และ headers ที่ parse แล้ว (ชื่อซ้ำถูกรวมเป็นค่าเดียว)
     println!("📋 HTTP HEADERS:");
-    println!("{}", headers);
+    println!("{}", head.request_line());
+    for (name, value) in head.headers.iter() {
+        println!("{}: {}", name, value);
+    }
     print_separator();
     
-    // หา boundary
-    if let Some(content_type_line) = headers.lines()
-        .find(|l| l.to_lowercase().starts_with("content-type:")) {
-        if let Some(boundary_start) = content_type_line.find("boundary=") {
-            found_boundary = content_type_line[boundary_start + 9..]
-                .trim()
-                .to_string();
-            found_boundary = format!("--{}", found_boundary);
-            println!("🔍 Detected boundary: {:?}", found_boundary);
+    // หา boundary (ไม่ใช่ multipart ตอบ 415, ไม่มีหรือ boundary ผิดตอบ 400)
+    let found_boundary = match http::boundary_delimiter(head) {
+        Ok(boundary) => boundary,
+        Err(e) => {
+            send_error(conn.get_mut(), &e);
+            return false;
         }
-    }
+    };
+    println!("🔍 Detected boundary: {:?}", found_boundary);
     
-    // หา Content-Length
-    if let Some(cl_line) = headers.lines()
-        .find(|l| l.to_lowercase().starts_with("content-length:")) {
-        if let Some(len_str) = cl_line.split(':').nth(1) {
-            content_length = len_str.trim().parse().unwrap_or(0);
-            println!("📏 Content-Length: {} bytes", content_length);
+    // หา Content-Length หรือ Transfer-Encoding: chunked
+    let framing = match http::body_framing(head) {
+        Ok(framing) => framing,
+        Err(e) => {
+            send_error(conn.get_mut(), &e);
+            return false;
         }
+    };
+    let content_length = match framing {
+        BodyFraming::ContentLength(len) => len,
+        _ => 0,
+    };
+    let mut decoder = (framing == BodyFraming::Chunked).then(ChunkedDecoder::new);
+    if let BodyFraming::ContentLength(len) = framing {
+        println!("📏 Content-Length: {} bytes", len);
     }
+    if decoder.is_some() {
+        println!("🧱 Transfer-Encoding: chunked (แสดง chunk framing คู่กับ boundary)");
+    }
+    // body ที่ไม่มีทั้ง Content-Length และ chunked อ่านจน connection ปิด จึงรับ request ถัดไปไม่ได้
+    let mut keep_alive = http::keep_alive(head) && framing != BodyFraming::UntilClose;
+    println!("🔁 Keep-alive: {}", if keep_alive { "yes" } else { "no" });
     
+    // curl ส่ง Expect: 100-continue กับ body เกิน 1KB แล้วรอคำตอบก่อนส่ง body
+    if http::expects_continue(head) {
+        println!("📨 Expect: 100-continue → ตอบ 100 Continue");
+        if let Err(e) = conn.get_mut().write_all(http::CONTINUE_RESPONSE.as_bytes()) {
+            eprintln!("❌ Error sending 100 Continue: {}", e);
+            return false;
+        }
+    }
+
     print_separator();
 
     // อ่าน body ตาม Content-Length
@@ -116,22 +190,27 @@ fn handle_client(mut stream: TcpStream) {
     let mut found_end = false;
     
     loop {
-        // ถ้ามี Content-Length ให้ใช้เป็นตัวกำหนด
-        if content_length > 0 && bytes_read >= content_length {
-            println!("\n✅ READ COMPLETE ({}/{} bytes)", bytes_read, content_length);
+        if decoder.as_ref().is_some_and(ChunkedDecoder::is_finished) {
+            println!("\n✅ READ COMPLETE ({} bytes, chunked)", bytes_read);
             break;
         }
-        
-        // คำนวณว่าจะอ่านกี่ bytes
-        let to_read = if content_length > 0 {
-            (content_length - bytes_read).min(BUFFER_SIZE)
-        } else {
-            BUFFER_SIZE
+        // ถ้ามี Content-Length ให้ใช้เป็นตัวกำหนด (รวม Content-Length: 0 ที่ไม่ต้องอ่านเลย)
+        let Some(to_read) = framing.next_read(bytes_read, BUFFER_SIZE) else {
+            println!("\n✅ READ COMPLETE ({}/{} bytes)", bytes_read, content_length);
+            break;
         };
         
-        match stream.read(&mut buffer[..to_read]) {
+        match conn.read(&mut buffer[..to_read]) {
             Ok(0) => {
                 println!("\n🔚 CONNECTION CLOSED"); //ส่วนใหญ่ตอนนี้ใช้ http 1.1 ทำให้เกิด keep alive แปลว่า ต่อให้ส่งข้อมูลครบแล้วก็จะไม่่ปิด  connterction tcp
+                keep_alive = false;
+                if let Some(decoder) = &mut decoder {
+                    decoder.end_of_input();
+                    if let Err(e) = decoder.next_event() {
+                        send_error(conn.get_mut(), &e);
+                        return false;
+                    }
+                }
                 break;
             }
             Ok(n) => {
@@ -143,10 +222,29 @@ fn handle_client(mut stream: TcpStream) {
                 print_chunk_header(chunk_num, n, bytes_read, progress_total);
                 
                 let chunk_data = &buffer[..n];
-                all_data.extend_from_slice(chunk_data);
+
+                // chunked: แสดง framing ก่อน แล้วหา boundary ในข้อมูลที่ decode แล้ว
+                let body_data = match &mut decoder {
+                    Some(decoder) => {
+                        println!("Raw data (chunked):");
+                        println!("{}", String::from_utf8_lossy(chunk_data));
+                        match visualize_chunk_framing(decoder, chunk_data) {
+                            Ok(decoded) => decoded,
+                            Err(e) => {
+                                send_error(conn.get_mut(), &e);
+                                return false;
+                            }
+                        }
+                    }
+                    None => chunk_data.to_vec(),
+                };
+                all_data.extend_from_slice(&body_data);
                 
                 // แสดง chunk ในรูปแบบที่อ่านง่าย
-                visualize_boundary(chunk_data, &found_boundary);
+                if decoder.is_some() {
+                    println!("\nDecoded body ({} bytes):", body_data.len());
+                }
+                visualize_boundary(&body_data, &found_boundary);
                 
                 // แสดง hex ของ 20 bytes แรกและท้าย
                 println!("\nFirst 20 bytes (hex): {:02x?}", 
@@ -157,22 +255,28 @@ fn handle_client(mut stream: TcpStream) {
                 }
                 
                 // ตรวจสอบ final boundary (สำหรับกรณีไม่มี Content-Length)
-                let chunk_str = String::from_utf8_lossy(chunk_data);
+                let chunk_str = String::from_utf8_lossy(&body_data);
                 if chunk_str.contains(&final_boundary) {
                     found_end = true;
                     println!("\n🏁 FOUND FINAL BOUNDARY");
-                    if content_length == 0 {
+                    if content_length == 0 && decoder.is_none() {
                         break;
                     }
                 }
             }
             Err(e) => {
                 eprintln!("❌ Error reading: {}", e);
+                keep_alive = false;
                 break;
             }
         }
     }
 
+    // chunked decoder อาจอ่านเลย last-chunk ไปถึง request ที่ pipeline มา คืนให้ connection
+    if let Some(decoder) = &decoder {
+        conn.unread(decoder.remaining());
+    }
+
     print_separator();
     println!("📊 SUMMARY");
     println!("Total chunks: {}", chunk_num);
@@ -190,9 +294,31 @@ fn handle_client(mut stream: TcpStream) {
     print_separator();
 
     // ส่ง response กลับ
-    let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK";
+    let response = http::keep_alive_response(200, "OK", "OK", keep_alive);
+    match conn.get_mut().write_all(response.as_bytes()) {
+        Ok(_) => println!("✅ [worker #{}] Response sent successfully ({})", worker, peer),
+        Err(e) => {
+            eprintln!("❌ Error sending response: {}", e);
+            keep_alive = false;
+        }
+    }
+    keep_alive
+}
+
+/// ทุก worker ไม่ว่างและคิวเต็ม ตอบ 503 ทันที
+fn reject_busy(mut stream: TcpStream) {
+    eprintln!("🚫 Server busy: ปฏิเสธ connection ใหม่");
+    let response = http::simple_response(503, "Service Unavailable", "server busy, try again later");
+    stream.write_all(response.as_bytes()).ok();
+}
+
+fn send_error(stream: &mut TcpStream, error: &MultipartError) {
+    eprintln!("❌ {}", error);
+    print_separator();
+
+    let response = http::error_response(error);
     match stream.write_all(response.as_bytes()) {
-        Ok(_) => println!("✅ Response sent successfully"),
+        Ok(_) => println!("✅ Error response sent ({})", error.status().0),
         Err(e) => eprintln!("❌ Error sending response: {}", e),
     }
 }
@@ -216,10 +342,18 @@ fn main() {
     println!("        -F \"profile=@/path/to/file.jpg\"");
     print_separator();
 
+    // ตั้งค่าได้ด้วย env UPLOAD_WORKERS และ UPLOAD_QUEUE_DEPTH
+    let config = PoolConfig::from_env();
+    let pool = ThreadPool::new(config, handle_client);
+    println!("🧵 Workers: {} (queue depth: {})", pool.size(), config.queue_depth);
+    print_separator();
+
     for stream in listener.incoming() {
         match stream {
             Ok(stream) => {
-                handle_client(stream);
+                if let Err(stream) = pool.try_submit(stream) {
+                    reject_busy(stream);
+                }
             }
             Err(e) => {
                 eprintln!("❌ Error accepting connection: {}", e);
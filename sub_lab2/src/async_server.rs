@@ -0,0 +1,144 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use multipart_core::{DigestAlgorithm, FsyncMode, Limits, MultipartError, NamingStrategy};
+use multipart_core::async_io::{self, AsyncUploadProcessor};
+use multipart_core::http::{self, BodyFraming, Connection, HeadLimits, RequestHead, UploadPolicy};
+use multipart_core::storage::StorageRoutes;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+
+use super::{LISTEN_ADDR, UPLOAD_DIR, print_summary, storage_routes, upload_policy};
+
+/// รัน server แบบ async: แต่ละ connection เป็น task แยก upload ช้าๆ จึงไม่บล็อก client อื่น
+pub fn run() {
+    let runtime = tokio::runtime::Runtime::new().expect("Cannot start tokio runtime");
+    runtime.block_on(serve());
+}
+
+async fn serve() {
+    let listener = TcpListener::bind(LISTEN_ADDR)
+        .await
+        .expect("Cannot bind to port 8082");
+
+    let policy = Arc::new(upload_policy());
+    let storage = storage_routes();
+
+    println!("\n⏳ Waiting for connections (async)...\n");
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                tokio::spawn(handle_client(stream, peer, Arc::clone(&policy), storage.clone()));
+            }
+            Err(e) => eprintln!("❌ Error: {}", e),
+        }
+    }
+}
+
+async fn handle_client(
+    stream: TcpStream,
+    peer: SocketAddr,
+    policy: Arc<UploadPolicy>,
+    storage: Option<StorageRoutes>,
+) {
+    // keep-alive: รับ request ต่อบน connection เดิมจนกว่าจะปิดหรือ idle เกิน timeout
+    let mut conn = Connection::new(stream);
+    let limits = HeadLimits::default();
+    let mut requests = 0;
+    loop {
+        let head = match timeout(http::DEFAULT_IDLE_TIMEOUT, async_io::read_head(&mut conn, &limits)).await {
+            Ok(Ok(Some(head))) => head,
+            // idle เกิน timeout, client ปิด connection หรืออ่าน socket ไม่ได้
+            Err(_) | Ok(Ok(None)) | Ok(Err(MultipartError::Io(_))) => break,
+            Ok(Err(e)) => {
+                eprintln!("\n🚫 [{}] Bad request head: {}", peer, e);
+                conn.get_mut().write_all(http::error_response(&e).as_bytes()).await.ok();
+                break;
+            }
+        };
+
+        requests += 1;
+        if !handle_request(&mut conn, &head, peer, &policy, storage.as_ref()).await {
+            break;
+        }
+    }
+
+    println!("👋 [{}] ปิด connection ({} requests)", peer, requests);
+}
+
+/// จัดการ request หนึ่งตัว คืน `true` ถ้าเก็บ connection ไว้รับ request ถัดไปได้
+async fn handle_request(
+    conn: &mut Connection<TcpStream>,
+    head: &RequestHead,
+    peer: SocketAddr,
+    policy: &UploadPolicy,
+    storage: Option<&StorageRoutes>,
+) -> bool {
+    let start_time = Instant::now();
+    let stream = conn.get_mut();
+
+    println!("\n🔌 [{}] {}", peer, head.request_line());
+
+    if let Err(e) = http::check_head(head, policy) {
+        eprintln!("\n🚫 [{}] Rejected before body: {}", peer, e);
+        stream.write_all(http::error_response(&e).as_bytes()).await.ok();
+        return false;
+    }
+    if http::expects_continue(head) {
+        println!("📨 [{}] Expect: 100-continue → 100 Continue", peer);
+        if let Err(e) = stream.write_all(http::CONTINUE_RESPONSE.as_bytes()).await {
+            eprintln!("❌ [{}] Error sending 100 Continue: {}", peer, e);
+            return false;
+        }
+    }
+
+    let mut keep_alive = http::keep_alive(head)
+        && http::body_framing(head).is_ok_and(|framing| framing != BodyFraming::UntilClose);
+
+    let response = match receive_upload(conn, head, storage).await {
+        Ok(upload) => {
+            if let Some(stats) = upload.get_stats() {
+                print_summary(stats, start_time.elapsed(), upload.is_complete());
+            }
+            http::keep_alive_response(200, "OK", "OK", keep_alive)
+        }
+        Err(e) => {
+            eprintln!("\n❌ [{}] Upload failed: {}", peer, e);
+            keep_alive = false;
+            http::error_response(&e)
+        }
+    };
+
+    match conn.get_mut().write_all(response.as_bytes()).await {
+        Ok(_) => println!("✅ [{}] Response sent successfully", peer),
+        Err(e) => {
+            eprintln!("❌ [{}] Error sending response: {}", peer, e);
+            keep_alive = false;
+        }
+    }
+    keep_alive
+}
+
+async fn receive_upload(
+    conn: &mut Connection<TcpStream>,
+    head: &RequestHead,
+    storage: Option<&StorageRoutes>,
+) -> Result<AsyncUploadProcessor, MultipartError> {
+    let boundary = http::boundary_delimiter(head)?;
+    let framing = http::body_framing(head)?;
+
+    let mut upload = AsyncUploadProcessor::new(&boundary, UPLOAD_DIR)
+        .await?
+        .with_naming(NamingStrategy::from_env())
+        .with_fsync(FsyncMode::from_env())
+        .with_limits(Limits::from_env())
+        .with_digests(&DigestAlgorithm::list_from_env());
+    if let Some(routes) = storage {
+        upload = upload.with_storage(routes.clone(), head.path());
+    }
+    async_io::read_body(conn, framing, &mut upload).await?;
+    Ok(upload)
+}
@@ -1,282 +1,21 @@
-use std::fs::{File, create_dir_all};
-use std::io::{Read, Write, BufWriter};
+use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-const BUFFER_SIZE: usize = 8192; // 8KB buffer สำหรับ streaming
-const UPLOAD_DIR: &str = "./uploads";
-
-#[allow(dead_code)]
-#[derive(Debug)]
-enum PartType {
-    Field,
-    File { filename: String, content_type: String },
-}
-
-
-struct StreamingParser {
-    boundary: Vec<u8>,
-    retained: Vec<u8>,
-    state: ParserState,
-    current_part_type: Option<PartType>,
-    current_field_name: String,
-    file_writer: Option<BufWriter<File>>,
-    stats: Stats,
-}
-
-#[derive(Debug, PartialEq)]
-enum ParserState {
-    SearchingBoundary,
-    ReadingHeaders,
-    ReadingData,
-}
-
-#[derive(Debug)]
-struct Stats {
-    total_chunks: usize,
-    total_bytes: usize,
-    fields_count: usize,
-    files_count: usize,
-    files_saved: Vec<FileInfo>,
-}
-
-#[derive(Debug, Clone)]
-struct FileInfo {
-    field_name: String,
-    filename: String,
-    size: usize,
-    path: String,
-}
-
-impl StreamingParser {
-    fn new(boundary: &str) -> Self {
-        create_dir_all(UPLOAD_DIR).ok();
-        
-        Self {
-            boundary: boundary.as_bytes().to_vec(),
-            retained: Vec::new(),
-            state: ParserState::SearchingBoundary,
-            current_part_type: None,
-            current_field_name: String::new(),
-            file_writer: None,
-            stats: Stats {
-                total_chunks: 0,
-                total_bytes: 0,
-                fields_count: 0,
-                files_count: 0,
-                files_saved: Vec::new(),
-            },
-        }
-    }
-
-    fn process_chunk(&mut self, chunk: &[u8]) {
-        self.stats.total_chunks += 1;
-        self.stats.total_bytes += chunk.len();
-
-        // รวม retained + chunk ใหม่
-        let mut data = self.retained.clone();
-        data.extend_from_slice(chunk);
-
-        let mut pos = 0;
-
-        while pos < data.len() {
-            match self.state {
-                ParserState::SearchingBoundary => {
-                    // หา boundary
-                    if let Some(boundary_pos) = self.find_boundary(&data[pos..]) {
-                        let actual_pos = pos + boundary_pos;
-                        
-                        // ข้าม boundary
-                        pos = actual_pos + self.boundary.len();
-                        
-                        // ข้าม \r\n หลัง boundary
-                        if pos + 2 <= data.len() && &data[pos..pos + 2] == b"\r\n" {
-                            pos += 2;
-                        }
-                        
-                        self.state = ParserState::ReadingHeaders;
-                        self.current_field_name.clear();
-                        self.current_part_type = None;
-                    } else {
-                        // ยังไม่เจอ boundary, retain ท้าย buffer
-                        break;
-                    }
-                }
-                
-                ParserState::ReadingHeaders => {
-                    // หา \r\n\r\n (จบ headers)
-                    if let Some(header_end) = self.find_pattern(&data[pos..], b"\r\n\r\n") {
-                        let headers_data = &data[pos..pos + header_end];
-                        let headers = String::from_utf8_lossy(headers_data);
-                        
-                        self.parse_headers(&headers);
-                        
-                        pos += header_end + 4; // ข้าม \r\n\r\n
-                        self.state = ParserState::ReadingData;
-                        
-                        // ถ้าเป็นไฟล์ ให้เปิด file writer
-                        // แก้ไข: clone filename ก่อนเพื่อหลีกเลี่ยง borrow conflict
-                        if let Some(PartType::File { filename, .. }) = &self.current_part_type {
-                            let filename_clone = filename.clone();
-                            self.open_file_writer(&filename_clone);
-                        }
-                    } else {
-                        // ยังอ่าน headers ไม่ครบ
-                        break;
-                    }
-                }
-                
-                ParserState::ReadingData => {
-                    // หา boundary ถัดไป
-                    if let Some(boundary_pos) = self.find_boundary(&data[pos..]) {
-                        let data_chunk = &data[pos..pos + boundary_pos - 2]; // -2 เพื่อข้าม \r\n ก่อน boundary
-                        
-                        // เขียนข้อมูลส่วนสุดท้าย
-                        self.write_data(data_chunk);
-                        
-                        // ปิดไฟล์ถ้ามี
-                        self.close_file_writer();
-                        
-                        pos += boundary_pos;
-                        self.state = ParserState::SearchingBoundary;
-                    } else {
-                        // ยังไม่เจอ boundary ถัดไป, เขียนข้อมูลที่มี (ยกเว้นท้าย buffer)
-                        let safe_write_len = data.len().saturating_sub(pos + self.boundary.len());
-                        
-                        if safe_write_len > 0 {
-                            self.write_data(&data[pos..pos + safe_write_len]);
-                            pos += safe_write_len;
-                        }
-                        break;
-                    }
-                }
-            }
-        }
+use multipart_core::{ChunkedDecoder, ChunkedEvent, DigestAlgorithm, FsyncMode, Limits, MultipartError, NamingStrategy, Stats, UploadProcessor};
+use multipart_core::digest::to_hex;
+use multipart_core::http::{self, BodyFraming, Connection, HeadLimits, RequestHead, UploadPolicy};
+use multipart_core::pool::{PoolConfig, ThreadPool};
+use multipart_core::s3::{S3Config, S3Storage};
+use multipart_core::storage::{DiskStorage, StorageRoutes};
 
-        // Retain ข้อมูลที่เหลือ
-        if pos < data.len() {
-            self.retained = data[pos..].to_vec();
-        } else {
-            self.retained.clear();
-        }
-    }
-
-    fn find_boundary(&self, data: &[u8]) -> Option<usize> {
-        for i in 0..=data.len().saturating_sub(self.boundary.len()) {
-            if &data[i..i + self.boundary.len()] == self.boundary.as_slice() {
-                return Some(i);
-            }
-        }
-        None
-    }
-
-    fn find_pattern(&self, data: &[u8], pattern: &[u8]) -> Option<usize> {
-        for i in 0..=data.len().saturating_sub(pattern.len()) {
-            if &data[i..i + pattern.len()] == pattern {
-                return Some(i);
-            }
-        }
-        None
-    }
-
-    fn parse_headers(&mut self, headers: &str) {
-        for line in headers.lines() {
-            if line.to_lowercase().starts_with("content-disposition:") {
-                // Parse field name
-                if let Some(name_start) = line.find("name=\"") {
-                    let name_part = &line[name_start + 6..];
-                    if let Some(name_end) = name_part.find('"') {
-                        self.current_field_name = name_part[..name_end].to_string();
-                    }
-                }
-                
-                // Parse filename
-                if let Some(filename_start) = line.find("filename=\"") {
-                    let filename_part = &line[filename_start + 10..];
-                    if let Some(filename_end) = filename_part.find('"') {
-                        let filename = filename_part[..filename_end].to_string();
-                        self.current_part_type = Some(PartType::File {
-                            filename,
-                            content_type: String::new(),
-                        });
-                    }
-                } else {
-                    self.current_part_type = Some(PartType::Field);
-                }
-            }
-            
-            if line.to_lowercase().starts_with("content-type:") {
-                if let Some(PartType::File { filename, .. }) = &self.current_part_type {
-                    let content_type = line[13..].trim().to_string();
-                    self.current_part_type = Some(PartType::File {
-                        filename: filename.clone(),
-                        content_type,
-                    });
-                }
-            }
-        }
-    }
-
-    fn open_file_writer(&mut self, filename: &str) {
-        self.stats.files_count += 1;
-        let filepath = format!("{}/{}", UPLOAD_DIR, filename);
-        
-        println!("\n📁 เริ่ม stream ไฟล์: {}", filename);
-        println!("   Path: {}", filepath);
-        println!("   🔄 กำลังเขียนโดยตรงไป disk...");
-        
-        if let Ok(file) = File::create(&filepath) {
-            self.file_writer = Some(BufWriter::new(file));
-        }
-    }
-
-    fn write_data(&mut self, data: &[u8]) {
-        if let Some(writer) = &mut self.file_writer {
-            // Stream ไปที่ไฟล์โดยตรง (ไม่เก็บใน memory)
-            writer.write_all(data).ok();
-        } else {
-            // เป็น field ธรรมดา (ไม่ใช่ไฟล์)
-            self.stats.fields_count += 1;
-        }
-    }
-
-    fn close_file_writer(&mut self) {
-        if let Some(mut writer) = self.file_writer.take() {
-            writer.flush().ok();
-            
-            if let Some(PartType::File { filename, content_type: _ }) = &self.current_part_type {
-                let filepath = format!("{}/{}", UPLOAD_DIR, filename);
-                let size = std::fs::metadata(&filepath)
-                    .map(|m| m.len() as usize)
-                    .unwrap_or(0);
-                
-                println!("   ✅ บันทึกเสร็จ: {} ({} bytes)", filename, size);
-                
-                self.stats.files_saved.push(FileInfo {
-                    field_name: self.current_field_name.clone(),
-                    filename: filename.clone(),
-                    size,
-                    path: filepath,
-                });
-            }
-        }
-    }
-
-    fn finalize(&mut self) {
-        // แก้ไข: clone retained ก่อนเพื่อหลีกเลี่ยง borrow conflict
-        if !self.retained.is_empty() {
-            let retained_data = self.retained.clone();
-            self.write_data(&retained_data);
-            self.retained.clear();
-        }
-        
-        self.close_file_writer();
-    }
+#[cfg(feature = "async")]
+mod async_server;
 
-    fn get_stats(&self) -> &Stats {
-        &self.stats
-    }
-}
+const BUFFER_SIZE: usize = 8192; // 8KB buffer สำหรับ streaming
+const UPLOAD_DIR: &str = "./uploads";
+const LISTEN_ADDR: &str = "127.0.0.1:8082";
 
 fn print_separator() {
     println!("{}", "═".repeat(80));
@@ -298,85 +37,170 @@ fn format_bytes(bytes: usize) -> String {
     }
 }
 
-fn handle_client(mut stream: TcpStream) {
-    let start_time = Instant::now();
-    
-    // อ่าน HTTP headers
-    let mut header_buffer = Vec::new();
-    let mut temp_buf = [0u8; 1];
-    
+fn handle_client(stream: TcpStream, worker: usize, policy: &UploadPolicy, storage: Option<&StorageRoutes>) {
+    let peer = stream
+        .peer_addr()
+        .map(|addr| addr.to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+    println!("\n🧵 [worker #{}] รับ connection จาก {}", worker, peer);
+
+    // HTTP/1.1 keep-alive: รับหลาย request (รวมถึงที่ pipeline มา) บน connection เดียวจนกว่าจะปิด
+    let mut conn = Connection::new(stream);
+    let limits = HeadLimits::default();
+    let mut requests = 0;
     loop {
-        match stream.read(&mut temp_buf) {
-            Ok(0) | Err(_) => break,
-            Ok(_) => {
-                header_buffer.push(temp_buf[0]);
-                if header_buffer.len() >= 4 
-                    && &header_buffer[header_buffer.len()-4..] == b"\r\n\r\n" {
-                    break;
-                }
+        // รอ request ถัดไปได้ไม่เกิน idle timeout ส่วนตอนอ่าน body รอแต่ละครั้งได้ไม่เกิน body timeout
+        conn.get_mut().set_read_timeout(Some(http::DEFAULT_IDLE_TIMEOUT)).ok();
+        let head = match conn.read_head(&limits) {
+            Ok(Some(head)) => head,
+            // client ปิด connection หรือ idle เกิน timeout
+            Ok(None) | Err(MultipartError::Io(_)) => break,
+            Err(e) => {
+                eprintln!("\n🚫 [worker #{}] Bad request head ({}): {}", worker, peer, e);
+                conn.get_mut().write_all(http::error_response(&e).as_bytes()).ok();
+                break;
             }
+        };
+        // client ที่ส่ง head แล้วเงียบไปต้องไม่ถือ worker ของ pool ไว้ตลอด
+        conn.get_mut().set_read_timeout(Some(http::DEFAULT_BODY_TIMEOUT)).ok();
+
+        requests += 1;
+        if !handle_request(&mut conn, &head, worker, &peer, policy, storage) {
+            break;
         }
     }
-    
-    let headers = String::from_utf8_lossy(&header_buffer);
-    
-    // Parse boundary
-    let mut boundary = String::new();
-    if let Some(content_type) = headers.lines()
-        .find(|l| l.to_lowercase().starts_with("content-type:")) {
-        if let Some(idx) = content_type.find("boundary=") {
-            boundary = content_type[idx + 9..].trim().to_string();
-            boundary = format!("--{}", boundary);
+
+    println!("👋 [worker #{}] ปิด connection {} ({} requests)", worker, peer, requests);
+}
+
+/// จัดการ request หนึ่งตัว คืน `true` ถ้าเก็บ connection ไว้รับ request ถัดไปได้
+fn handle_request(
+    conn: &mut Connection<TcpStream>,
+    head: &RequestHead,
+    worker: usize,
+    peer: &str,
+    policy: &UploadPolicy,
+    storage: Option<&StorageRoutes>,
+) -> bool {
+    let start_time = Instant::now();
+
+    // ตรวจ head ก่อนรับ body: ถ้าไม่ผ่านตอบ 4xx/413 ทันที client ที่ส่ง Expect: 100-continue จะไม่ส่ง body มาเลย
+    if let Err(e) = http::check_head(head, policy) {
+        eprintln!("\n🚫 [worker #{}] Rejected before body ({}): {}", worker, peer, e);
+        conn.get_mut().write_all(http::error_response(&e).as_bytes()).ok();
+        return false;
+    }
+    if http::expects_continue(head) {
+        println!("📨 [worker #{}] Expect: 100-continue → 100 Continue", worker);
+        if let Err(e) = conn.get_mut().write_all(http::CONTINUE_RESPONSE.as_bytes()) {
+            eprintln!("❌ [worker #{}] Error sending 100 Continue: {}", worker, e);
+            return false;
         }
     }
 
-    // Parse Content-Length
-    let mut content_length = 0usize;
-    if let Some(cl_line) = headers.lines()
-        .find(|l| l.to_lowercase().starts_with("content-length:")) {
-        if let Some(len_str) = cl_line.split(':').nth(1) {
-            content_length = len_str.trim().parse().unwrap_or(0);
+    // body ที่อ่านจน connection ปิดไม่มีทางมี request ถัดไป
+    let mut keep_alive = http::keep_alive(head)
+        && http::body_framing(head).is_ok_and(|framing| framing != BodyFraming::UntilClose);
+
+    // ตอบ 200 เฉพาะเมื่อ parse และบันทึกครบ ไม่งั้นตอบตามประเภท error (400/413/500)
+    let response = match receive_upload(conn, head, storage, start_time) {
+        Ok(()) => http::keep_alive_response(200, "OK", "OK", keep_alive),
+        Err(e) => {
+            eprintln!("\n❌ [worker #{}] Upload failed ({}): {}", worker, peer, e);
+            keep_alive = false;
+            http::error_response(&e)
+        }
+    };
+
+    match conn.get_mut().write_all(response.as_bytes()) {
+        Ok(_) => println!("✅ [worker #{}] Response sent successfully ({})", worker, peer),
+        Err(e) => {
+            eprintln!("❌ [worker #{}] Error sending response: {}", worker, e);
+            keep_alive = false;
         }
     }
+    keep_alive
+}
+
+/// ทุก worker ไม่ว่างและคิวเต็ม ตอบ 503 ทันทีแทนที่จะให้ client รอ
+fn reject_busy(mut stream: TcpStream) {
+    let peer = stream
+        .peer_addr()
+        .map(|addr| addr.to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+    eprintln!("🚫 Server busy: ปฏิเสธ connection จาก {}", peer);
+
+    let response = http::simple_response(503, "Service Unavailable", "server busy, try again later");
+    stream.write_all(response.as_bytes()).ok();
+}
+
+fn receive_upload(
+    conn: &mut Connection<TcpStream>,
+    head: &RequestHead,
+    storage: Option<&StorageRoutes>,
+    start_time: Instant,
+) -> Result<(), MultipartError> {
+    // Parse boundary และวิธีหาจุดจบของ body (Content-Length หรือ chunked)
+    let boundary = http::boundary_delimiter(head)?;
+    let framing = http::body_framing(head)?;
+    let content_length = match framing {
+        BodyFraming::ContentLength(len) => len,
+        _ => 0,
+    };
+    // chunked: bytes จาก socket ต้องผ่าน decoder ก่อนเข้า parser
+    let mut decoder = (framing == BodyFraming::Chunked).then(ChunkedDecoder::new);
 
     println!("\n📋 Request:");
-    if let Some(first_line) = headers.lines().next() {
-        println!("   {}", first_line);
-    }
+    println!("   {}", head.request_line());
     println!("   Boundary: {:?}", boundary);
-    println!("   Content-Length: {} ({})", content_length, format_bytes(content_length));
+    if decoder.is_some() {
+        println!("   Transfer-Encoding: chunked");
+    } else {
+        println!("   Content-Length: {} ({})", content_length, format_bytes(content_length));
+    }
     println!("   Buffer: {} bytes", BUFFER_SIZE);
 
     print_separator();
 
-    let mut parser = StreamingParser::new(&boundary);
+    let mut parser = UploadProcessor::new(&boundary, UPLOAD_DIR)?
+        .with_naming(NamingStrategy::from_env())
+        .with_fsync(FsyncMode::from_env())
+        .with_limits(Limits::from_env())
+        .with_digests(&DigestAlgorithm::list_from_env());
+    if let Some(routes) = storage {
+        parser = parser.with_storage(routes.clone(), head.path());
+    }
     let mut buffer = [0u8; BUFFER_SIZE];
     let mut bytes_read = 0usize;
     let mut last_progress = 0;
 
     loop {
-        // หยุดเมื่ออ่านครบ
-        if content_length > 0 && bytes_read >= content_length {
-            println!("\n✅ Read complete: {}/{} bytes", bytes_read, content_length);
+        // หยุดเมื่ออ่านครบ (Content-Length: 0 ไม่อ่านเลย ไม่งั้นจะกิน request ถัดไปที่ pipeline มา)
+        if decoder.as_ref().is_some_and(ChunkedDecoder::is_finished) {
+            println!("\n✅ Read complete: {} bytes (chunked)", bytes_read);
             break;
         }
-
-        // คำนวณว่าจะอ่านกี่ bytes
-        let to_read = if content_length > 0 {
-            (content_length - bytes_read).min(BUFFER_SIZE)
-        } else {
-            BUFFER_SIZE
+        let Some(to_read) = framing.next_read(bytes_read, BUFFER_SIZE) else {
+            println!("\n✅ Read complete: {}/{} bytes", bytes_read, content_length);
+            break;
         };
 
-        match stream.read(&mut buffer[..to_read]) {
+        match conn.read(&mut buffer[..to_read]) {
             Ok(0) => {
                 println!("\n⚠️  Connection closed early: {}/{} bytes", 
                          bytes_read, content_length);
+                if let Some(decoder) = &mut decoder {
+                    decoder.end_of_input();
+                    feed_chunked(decoder, &mut parser, &[])?;
+                }
                 break;
             }
             Ok(n) => {
                 bytes_read += n;
-                parser.process_chunk(&buffer[..n]);
+                match &mut decoder {
+                    Some(decoder) => feed_chunked(decoder, &mut parser, &buffer[..n])?,
+                    None => parser.process_chunk(&buffer[..n])?,
+                }
                 
                 // คำนวณ progress
                 let progress_pct = if content_length > 0 {
@@ -388,8 +212,8 @@ fn handle_client(mut stream: TcpStream) {
                 let current_mb = bytes_read / (1024 * 1024);
                 
                 // แสดง progress ทุก 10% หรือทุก 10MB (แล้วแต่อันไหนเกิดก่อน)
-                let show_by_percent = progress_pct > 0 && progress_pct % 10 == 0 && progress_pct != last_progress;
-                let show_by_size = current_mb > 0 && current_mb % 10 == 0 && current_mb != last_progress;
+                let show_by_percent = progress_pct > 0 && progress_pct.is_multiple_of(10) && progress_pct != last_progress;
+                let show_by_size = current_mb > 0 && current_mb.is_multiple_of(10) && current_mb != last_progress;
                 
                 if show_by_percent || (show_by_size && progress_pct == 0) {
                     if content_length > 0 {
@@ -403,18 +227,41 @@ fn handle_client(mut stream: TcpStream) {
                     last_progress = if progress_pct > 0 { progress_pct } else { current_mb };
                 }
             }
-            Err(e) => {
-                eprintln!("❌ Error: {}", e);
-                break;
-            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    // chunked decoder อาจอ่านเลย last-chunk ไปถึง request ถัดไป คืนให้ connection
+    if let Some(decoder) = &decoder {
+        conn.unread(decoder.remaining());
+    }
+
+    if let Err(e) = parser.finalize() {
+        if matches!(e, MultipartError::UnexpectedEof) {
+            println!("\n⚠️  Upload truncated: body จบก่อนเจอ closing delimiter");
         }
+        return Err(e);
     }
 
-    parser.finalize();
+    print_summary(parser.get_stats(), start_time.elapsed(), parser.is_complete());
 
-    let elapsed = start_time.elapsed();
-    let stats = parser.get_stats();
+    Ok(())
+}
 
+/// ส่ง bytes ดิบเข้า chunked decoder แล้วส่งข้อมูลที่ decode แล้วต่อเข้า parser
+fn feed_chunked(decoder: &mut ChunkedDecoder, parser: &mut UploadProcessor, data: &[u8]) -> Result<(), MultipartError> {
+    decoder.feed(data);
+    while let Some(event) = decoder.next_event()? {
+        match event {
+            ChunkedEvent::Data(data) => parser.process_chunk(data)?,
+            ChunkedEvent::Trailer { name, value } => println!("📎 Trailer: {}: {}", name, value),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn print_summary(stats: &Stats, elapsed: Duration, complete: bool) {
     print_separator();
     println!("📊 สรุปผลลัพธ์");
     print_separator();
@@ -425,20 +272,42 @@ fn handle_client(mut stream: TcpStream) {
     println!("   Total bytes: {} ({})", stats.total_bytes, format_bytes(stats.total_bytes));
     println!("   Fields: {}", stats.fields_count);
     println!("   Files: {}", stats.files_count);
+    println!("   Closing delimiter: {}", if complete { "✅" } else { "❌" });
 
     if stats.total_bytes > 0 && elapsed.as_secs_f64() > 0.0 {
         let speed = stats.total_bytes as f64 / elapsed.as_secs_f64();
         println!("\n⚡ ความเร็ว: {}/sec", format_bytes(speed as usize));
     }
 
+    if !stats.fields.is_empty() {
+        println!("\n📝 Fields:");
+        for (name, value) in &stats.fields {
+            println!("   {} = {:?}", name, value);
+        }
+    }
+
     if !stats.files_saved.is_empty() {
         println!("\n📁 ไฟล์ที่บันทึก:");
         println!("{}", "─".repeat(80));
         
         for file in &stats.files_saved {
             println!("\n✅ {}", file.filename);
+            if file.original_filename != file.filename {
+                println!("   Original: {:?}", file.original_filename);
+            }
             println!("   Field: {}", file.field_name);
+            if !file.content_type.is_empty() {
+                println!("   Type: {}", file.content_type);
+            }
+            for (name, value) in file.headers.iter() {
+                if !name.eq_ignore_ascii_case("content-disposition") && !name.eq_ignore_ascii_case("content-type") {
+                    println!("   {}: {}", name, value);
+                }
+            }
             println!("   Size: {} ({})", file.size, format_bytes(file.size));
+            for (algorithm, digest) in &file.digests {
+                println!("   {}: {}", algorithm, to_hex(digest));
+            }
             println!("   Path: {}", file.path);
         }
     }
@@ -447,20 +316,73 @@ fn handle_client(mut stream: TcpStream) {
     println!("💾 ไฟล์ทั้งหมดถูก stream ไปยัง disk โดยตรง");
     println!("🚀 ไม่มี memory overhead ไม่ว่าไฟล์จะใหญ่แค่ไหน!");
     print_separator();
+}
 
-    // ส่ง response
-    let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK";
-    match stream.write_all(response.as_bytes()) {
-        Ok(_) => println!("✅ Response sent successfully"),
-        Err(e) => eprintln!("❌ Error sending response: {}", e),
+/// เงื่อนไขที่ตรวจจาก request head (env UPLOAD_PATH, UPLOAD_MAX_BODY, UPLOAD_TOKEN) default รับที่ /upload
+fn upload_policy() -> UploadPolicy {
+    let policy = UploadPolicy::from_env();
+    let policy = match policy.path {
+        Some(_) => policy,
+        None => policy.with_path("/upload"),
+    };
+
+    println!("🛂 Upload path: {}", policy.path.as_deref().unwrap_or_default());
+    if let Some(max) = policy.max_body_size {
+        println!("🛂 Max body: {}", format_bytes(max));
     }
+    if policy.auth_token.is_some() {
+        println!("🛂 Auth: Bearer token required");
+    }
+    policy
+}
+
+/// ตั้งค่า S3_* ไว้จะเก็บไฟล์ใน bucket แทน UPLOAD_DIR (อ่านครั้งเดียวตอนเริ่ม server)
+/// ถ้ามี S3_FIELDS (เช่น avatar,photo) เฉพาะ field เหล่านั้นที่ขึ้น S3 ที่เหลือยังลง disk
+fn storage_routes() -> Option<StorageRoutes> {
+    let config = S3Config::from_env()?;
+    let fields = std::env::var("S3_FIELDS").unwrap_or_default();
+    let fields: Vec<&str> = fields.split(',').map(str::trim).filter(|field| !field.is_empty()).collect();
+    let scope = if fields.is_empty() { "all files".to_string() } else { format!("fields {}", fields.join(",")) };
+    println!("☁️  Storage: s3://{}/{} ({}, {})", config.bucket, config.prefix, config.endpoint, scope);
+
+    let s3 = Arc::new(S3Storage::new(config).expect("Invalid S3 configuration"));
+    if fields.is_empty() {
+        return Some(StorageRoutes::new(s3));
+    }
+
+    let disk = DiskStorage::new(UPLOAD_DIR).with_fsync(FsyncMode::from_env());
+    let routes = fields.iter().fold(StorageRoutes::new(Arc::new(disk)), |routes, field| {
+        routes.with_field(field, s3.clone())
+    });
+    Some(routes)
 }
 
 fn main() {
 
-    println!("\n📍 Server: 127.0.0.1:8082");
+    println!("\n📍 Server: {}", LISTEN_ADDR);
     println!("📦 Stream Buffer: {} bytes", BUFFER_SIZE);
     println!("💾 Upload Directory: {}", UPLOAD_DIR);
+    // ตั้งค่าได้ด้วย env UPLOAD_NAMING (original, uuid, hash, suffix)
+    println!("🏷️  File naming: {:?}", NamingStrategy::from_env());
+    // UPLOAD_FSYNC (never, file, full) ไฟล์จะถูกเขียนลง .upload-*.tmp ก่อนแล้วค่อย rename เมื่อ part จบครบ
+    println!("🔒 Fsync: {:?}", FsyncMode::from_env());
+    let limits = Limits::from_env();
+    println!(
+        "📏 Limits: file {}, field {}, total {}, {} parts, {} files, part header {}, nesting depth {}",
+        format_bytes(limits.max_file_size),
+        format_bytes(limits.max_field_size),
+        format_bytes(limits.max_body_size),
+        limits.max_parts,
+        limits.max_files,
+        format_bytes(limits.max_part_header_size),
+        limits.max_nesting_depth
+    );
+    // UPLOAD_DIGESTS (เช่น sha256,md5,crc32c) คำนวณระหว่าง stream ส่วน Content-MD5/Digest/Repr-Digest ที่ client ส่งมาจะถูกตรวจเสมอ
+    let digests = DigestAlgorithm::list_from_env();
+    if !digests.is_empty() {
+        let names: Vec<_> = digests.iter().map(|algorithm| algorithm.name()).collect();
+        println!("🧮 Digests: {}", names.join(", "));
+    }
     println!("🎯 วัตถุประสงค์: รับไฟล์ขนาดใหญ่โดย stream ไป disk โดยตรง");
     
     println!("\n💡 สร้างไฟล์ทดสอบ 1GB:");
@@ -477,16 +399,34 @@ fn main() {
 
     print_separator();
 
-    let listener = TcpListener::bind("127.0.0.1:8082")
+    // build ด้วย --features async จะใช้ server แบบ tokio รับหลาย upload พร้อมกัน
+    // (ส่ง --sync เพื่อกลับไปใช้ server แบบเดิมที่รับทีละ connection)
+    #[cfg(feature = "async")]
+    if !std::env::args().any(|arg| arg == "--sync") {
+        async_server::run();
+        return;
+    }
+
+    let listener = TcpListener::bind(LISTEN_ADDR)
         .expect("Cannot bind to port 8082");
 
+    // ตั้งค่าได้ด้วย env UPLOAD_WORKERS และ UPLOAD_QUEUE_DEPTH
+    let config = PoolConfig::from_env();
+    let policy = upload_policy();
+    let storage = storage_routes();
+    let pool = ThreadPool::new(config, move |stream, worker| {
+        handle_client(stream, worker, &policy, storage.as_ref())
+    });
+    println!("🧵 Workers: {} (queue depth: {})", pool.size(), config.queue_depth);
+
     println!("\n⏳ Waiting for connections...\n");
 
     for stream in listener.incoming() {
         match stream {
             Ok(stream) => {
-                handle_client(stream);
-                println!("\n⏳ Waiting for next connection...\n");
+                if let Err(stream) = pool.try_submit(stream) {
+                    reject_busy(stream);
+                }
             }
             Err(e) => eprintln!("❌ Error: {}", e),
         }